@@ -0,0 +1,385 @@
+//! Настройки доступа к API: ключ, токен или вход по логину.
+//!
+//! Компонент показывает форму для выбранного способа и передаёт родителю
+//! готовые `AuthSettings`; сохраняет их и собирает из них `AuthProvider`
+//! родитель. Секреты не выводятся обратно в поля — только признак того,
+//! что они заданы.
+use std::rc::Rc;
+
+use web_sys::{HtmlInputElement, InputEvent};
+use yew::prelude::*;
+
+use crate::application::ports::{AuthProvider, Credentials};
+use crate::application::{
+    AuthSettings, AuthenticateUseCase, BackendKind, BackendProfile, NoAuth, RefreshingTokenAuth,
+    SessionAuth, StaticAuth,
+};
+use crate::domain::ApiBaseUrl;
+use crate::infrastructure::{
+    ApiClient, BrowserClock, GigaChatTokenSource, LoginTokenSource, SessionStorageCredentials,
+    SessionStorageSessionRepository,
+};
+
+/// Сценарий входа и сессии для backend по адресу `base_url`.
+pub(super) type SessionUseCase =
+    AuthenticateUseCase<ApiClient, SessionStorageSessionRepository, BrowserClock>;
+
+pub(super) fn session_usecase(base_url: ApiBaseUrl, timeout_ms: u32) -> SessionUseCase {
+    AuthenticateUseCase::new(
+        ApiClient::new(base_url.clone()).with_timeout(timeout_ms),
+        SessionStorageSessionRepository::for_base_url(&base_url),
+        BrowserClock,
+    )
+}
+
+/// Учётные данные запросов выбранного профиля: настройки раздела
+/// «Доступ к API» и собранный по ним провайдер.
+#[derive(Clone)]
+pub(super) struct AuthState {
+    pub(super) settings: AuthSettings,
+    provider: Rc<dyn AuthProvider>,
+}
+
+impl AuthState {
+    pub(super) fn new(settings: AuthSettings, profile: &BackendProfile) -> Self {
+        Self {
+            provider: auth_provider(&settings, profile),
+            settings,
+        }
+    }
+
+    /// Учётные данные профиля из `sessionStorage`.
+    pub(super) fn load(profile: &BackendProfile) -> Self {
+        let settings = SessionStorageCredentials::for_profile(profile.name())
+            .load()
+            .unwrap_or_default();
+        Self::new(settings, profile)
+    }
+
+    /// Провайдер для запросов по профилю `profile`. Если ключ или токен
+    /// не заданы, запросы к backend курса идут с токеном сессии — когда
+    /// пользователь вошёл.
+    pub(super) fn provider(
+        &self,
+        profile: &BackendProfile,
+        timeout_ms: u32,
+    ) -> Rc<dyn AuthProvider> {
+        if self.settings.is_configured() || profile.kind() != BackendKind::Course {
+            self.provider.clone()
+        } else {
+            Rc::new(SessionAuth::new(session_usecase(
+                profile.base_url().clone(),
+                timeout_ms,
+            )))
+        }
+    }
+}
+
+/// Источник учётных данных для сохранённых настроек.
+///
+/// Провайдер со входом по логину помнит полученный токен, поэтому UI
+/// собирает его один раз при сохранении настроек, а не на каждый запрос.
+/// Для GigaChat API ключ API — это ключ авторизации, по которому
+/// выдаётся токен доступа; OAuth‑эндпоинт выбирается по адресу профиля.
+fn auth_provider(settings: &AuthSettings, profile: &BackendProfile) -> Rc<dyn AuthProvider> {
+    match settings.clone() {
+        AuthSettings::None => Rc::new(NoAuth),
+        AuthSettings::ApiKey(key) if profile.kind() == BackendKind::GigaChatDirect => {
+            let source = GigaChatTokenSource::new(key)
+                .with_oauth_url(GigaChatTokenSource::oauth_url_for(profile.base_url()));
+            Rc::new(RefreshingTokenAuth::new(source, BrowserClock))
+        }
+        AuthSettings::ApiKey(key) => Rc::new(StaticAuth::new(Credentials::ApiKey(key))),
+        AuthSettings::Bearer(token) => Rc::new(StaticAuth::new(Credentials::Bearer(token))),
+        AuthSettings::Login {
+            endpoint,
+            username,
+            password,
+        } => Rc::new(RefreshingTokenAuth::new(
+            LoginTokenSource::new(endpoint, username, password),
+            BrowserClock,
+        )),
+    }
+}
+
+/// Способ авторизации в форме (без секретов).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AuthMethod {
+    None,
+    ApiKey,
+    Bearer,
+    Login,
+}
+
+impl AuthMethod {
+    const ALL: [AuthMethod; 4] = [
+        AuthMethod::None,
+        AuthMethod::ApiKey,
+        AuthMethod::Bearer,
+        AuthMethod::Login,
+    ];
+
+    fn of(settings: &AuthSettings) -> Self {
+        match settings {
+            AuthSettings::None => AuthMethod::None,
+            AuthSettings::ApiKey(_) => AuthMethod::ApiKey,
+            AuthSettings::Bearer(_) => AuthMethod::Bearer,
+            AuthSettings::Login { .. } => AuthMethod::Login,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            AuthMethod::None => "Без авторизации",
+            AuthMethod::ApiKey => "Ключ API",
+            AuthMethod::Bearer => "Токен",
+            AuthMethod::Login => "Логин и пароль",
+        }
+    }
+}
+
+/// Значения полей формы.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct AuthForm {
+    secret: String,
+    endpoint: String,
+    username: String,
+    password: String,
+}
+
+impl AuthForm {
+    /// Настройки из формы.
+    ///
+    /// Пустое поле секрета оставляет сохранённый секрет того же способа;
+    /// если его нет, пустые обязательные поля дают ошибку.
+    fn settings(&self, method: AuthMethod, saved: &AuthSettings) -> Result<AuthSettings, String> {
+        let required = |value: &str, saved: Option<&String>, name: &str| match (value.trim(), saved)
+        {
+            ("", Some(saved)) => Ok(saved.clone()),
+            ("", None) => Err(format!("Укажите {name}.")),
+            (value, _) => Ok(value.to_string()),
+        };
+        let saved_secret = match (method, saved) {
+            (AuthMethod::ApiKey, AuthSettings::ApiKey(secret))
+            | (AuthMethod::Bearer, AuthSettings::Bearer(secret)) => Some(secret),
+            (AuthMethod::Login, AuthSettings::Login { password, .. }) => Some(password),
+            _ => None,
+        };
+        Ok(match method {
+            AuthMethod::None => AuthSettings::None,
+            AuthMethod::ApiKey => {
+                AuthSettings::ApiKey(required(&self.secret, saved_secret, "ключ API")?)
+            }
+            AuthMethod::Bearer => {
+                AuthSettings::Bearer(required(&self.secret, saved_secret, "токен")?)
+            }
+            AuthMethod::Login => AuthSettings::Login {
+                endpoint: required(&self.endpoint, None, "адрес входа")?,
+                username: required(&self.username, None, "логин")?,
+                password: required(&self.password, saved_secret, "пароль")?,
+            },
+        })
+    }
+}
+
+/// Свойства компонента `AuthSettingsPanel`.
+#[derive(Properties, PartialEq)]
+pub struct AuthSettingsPanelProps {
+    /// Сохранённые настройки.
+    pub settings: AuthSettings,
+    /// Адрес входа, который подставляется, если он ещё не задан.
+    pub default_login_endpoint: String,
+    /// Просьба войти заново (backend отверг учётные данные).
+    pub prompt: Option<String>,
+    /// Ошибка сохранения.
+    pub error: Option<String>,
+    pub on_save: Callback<AuthSettings>,
+    pub on_clear: Callback<()>,
+}
+
+/// Раздел настроек «Доступ к API».
+#[function_component(AuthSettingsPanel)]
+pub fn auth_settings_panel(props: &AuthSettingsPanelProps) -> Html {
+    let method = use_state(|| AuthMethod::of(&props.settings));
+    let form = use_state(|| match &props.settings {
+        AuthSettings::Login {
+            endpoint, username, ..
+        } => AuthForm {
+            endpoint: endpoint.clone(),
+            username: username.clone(),
+            ..AuthForm::default()
+        },
+        _ => AuthForm {
+            endpoint: props.default_login_endpoint.clone(),
+            ..AuthForm::default()
+        },
+    });
+    let form_error = use_state(|| Option::<String>::None);
+
+    let on_method = |value: AuthMethod| {
+        let method = method.clone();
+        let form_error = form_error.clone();
+        Callback::from(move |_: Event| {
+            method.set(value);
+            form_error.set(None);
+        })
+    };
+
+    let on_field = |update: fn(&mut AuthForm, String)| {
+        let form = form.clone();
+        let form_error = form_error.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            let mut next = (*form).clone();
+            update(&mut next, input.value());
+            form.set(next);
+            form_error.set(None);
+        })
+    };
+
+    let on_save = {
+        let method = method.clone();
+        let form = form.clone();
+        let form_error = form_error.clone();
+        let saved = props.settings.clone();
+        let on_save = props.on_save.clone();
+        Callback::from(move |_| match form.settings(*method, &saved) {
+            Ok(settings) => {
+                form.set(AuthForm {
+                    secret: String::new(),
+                    password: String::new(),
+                    ..(*form).clone()
+                });
+                on_save.emit(settings);
+            }
+            Err(error) => form_error.set(Some(error)),
+        })
+    };
+
+    let on_clear = {
+        let method = method.clone();
+        let form = form.clone();
+        let form_error = form_error.clone();
+        let on_clear = props.on_clear.clone();
+        Callback::from(move |_| {
+            method.set(AuthMethod::None);
+            form.set(AuthForm {
+                secret: String::new(),
+                username: String::new(),
+                password: String::new(),
+                ..(*form).clone()
+            });
+            form_error.set(None);
+            on_clear.emit(());
+        })
+    };
+
+    let secret_hint =
+        if AuthMethod::of(&props.settings) == *method && props.settings.is_configured() {
+            "Сохранено. Оставьте поле пустым, чтобы не менять."
+        } else {
+            "Хранится только до закрытия вкладки."
+        };
+
+    html! {
+        <details class="settings" open={props.prompt.is_some()}>
+            <summary>{format!("Доступ к API ({})", props.settings.label())}</summary>
+
+            {if let Some(message) = &props.prompt {
+                html! { <div class="message message--error">{message.clone()}</div> }
+            } else {
+                html! {}
+            }}
+
+            <div class="actions actions--compact">
+                {for AuthMethod::ALL.into_iter().map(|value| html! {
+                    <label class="checkbox">
+                        <input
+                            type="radio"
+                            name="auth-method"
+                            checked={*method == value}
+                            onchange={on_method(value)}
+                        />
+                        {value.label()}
+                    </label>
+                })}
+            </div>
+
+            {match *method {
+                AuthMethod::None => html! {
+                    <p class="muted">{"Запросы уходят без учётных данных."}</p>
+                },
+                AuthMethod::ApiKey | AuthMethod::Bearer => html! {
+                    <label class="field" for="auth-secret">
+                        <span class="field__label">{
+                            if *method == AuthMethod::ApiKey { "Ключ API" } else { "Токен доступа" }
+                        }</span>
+                        <input
+                            id="auth-secret"
+                            class="field__input"
+                            type="password"
+                            autocomplete="off"
+                            value={form.secret.clone()}
+                            oninput={on_field(|form, value| form.secret = value)}
+                        />
+                        <span class="field__hint">{secret_hint}</span>
+                    </label>
+                },
+                AuthMethod::Login => html! {
+                    <div class="settings__grid">
+                        <label class="field" for="auth-endpoint">
+                            <span class="field__label">{"Адрес входа"}</span>
+                            <input
+                                id="auth-endpoint"
+                                class="field__input"
+                                type="text"
+                                value={form.endpoint.clone()}
+                                oninput={on_field(|form, value| form.endpoint = value)}
+                            />
+                        </label>
+                        <label class="field" for="auth-username">
+                            <span class="field__label">{"Логин"}</span>
+                            <input
+                                id="auth-username"
+                                class="field__input"
+                                type="text"
+                                autocomplete="username"
+                                value={form.username.clone()}
+                                oninput={on_field(|form, value| form.username = value)}
+                            />
+                        </label>
+                        <label class="field" for="auth-password">
+                            <span class="field__label">{"Пароль"}</span>
+                            <input
+                                id="auth-password"
+                                class="field__input"
+                                type="password"
+                                autocomplete="current-password"
+                                value={form.password.clone()}
+                                oninput={on_field(|form, value| form.password = value)}
+                            />
+                            <span class="field__hint">{secret_hint}</span>
+                        </label>
+                    </div>
+                },
+            }}
+
+            <div class="actions actions--compact">
+                <button class="button button--ghost" onclick={on_save}>{"Сохранить"}</button>
+                <button
+                    class="button button--ghost"
+                    disabled={!props.settings.is_configured()}
+                    onclick={on_clear}
+                >
+                    {"Забыть учётные данные"}
+                </button>
+            </div>
+
+            {if let Some(message) = form_error.as_ref().or(props.error.as_ref()) {
+                html! { <div class="message message--error">{message.clone()}</div> }
+            } else {
+                html! {}
+            }}
+        </details>
+    }
+}
@@ -0,0 +1,146 @@
+//! Профили backend из панели «Статус API» и реестр шлюзов для них.
+//!
+//! Здесь, в корне композиции, каждому типу сервера сопоставляется
+//! адаптер из `infrastructure`. Декораторы (повторы, кэш, автомат защиты)
+//! собирает родитель поверх готовой пары шлюзов.
+use super::auth::AuthState;
+use crate::application::{BackendKind, BackendProfile, GatewayPair, GatewayRegistry};
+use crate::config::AppConfig;
+use crate::infrastructure::{
+    ApiClient, GigaChatDirectGateway, MockGateway, OpenAiCompatibleGateway,
+};
+
+/// Фабрики шлюзов для всех поддерживаемых типов сервера.
+pub(super) fn registry(timeout_ms: u32) -> GatewayRegistry {
+    GatewayRegistry::new()
+        .register(BackendKind::Course, move |profile, auth| {
+            let client = ApiClient::new(profile.base_url().clone())
+                .with_timeout(timeout_ms)
+                .with_auth(auth);
+            GatewayPair {
+                chat: Box::new(client.clone()),
+                health: Box::new(client),
+            }
+        })
+        .register(BackendKind::Mock, |_profile, _auth| GatewayPair {
+            chat: Box::new(MockGateway),
+            health: Box::new(MockGateway),
+        })
+        .register(BackendKind::OpenAiCompatible, move |profile, auth| {
+            let client = OpenAiCompatibleGateway::new(profile.base_url().clone())
+                .with_timeout(timeout_ms)
+                .with_auth(auth);
+            GatewayPair {
+                chat: Box::new(client.clone()),
+                health: Box::new(client),
+            }
+        })
+        .register(BackendKind::GigaChatDirect, move |profile, auth| {
+            let client = GigaChatDirectGateway::new(profile.base_url().clone(), auth)
+                .with_timeout(timeout_ms);
+            GatewayPair {
+                chat: Box::new(client.clone()),
+                health: Box::new(client),
+            }
+        })
+}
+
+/// Типы сервера, для которых есть шлюзы, — из них выбирают в панели.
+pub(super) fn supported_kinds() -> Vec<BackendKind> {
+    registry(0).kinds()
+}
+
+/// Шлюзы для профиля `profile` с учётными данными из `auth`.
+pub(super) fn gateways(
+    profile: &BackendProfile,
+    timeout_ms: u32,
+    auth: &AuthState,
+) -> Result<GatewayPair, String> {
+    registry(timeout_ms)
+        .build(profile, auth.provider(profile, timeout_ms))
+        .ok_or_else(|| {
+            format!(
+                "Тип сервера «{}» не поддерживается.",
+                profile.kind().label()
+            )
+        })
+}
+
+/// Профили backend и выбранный из них.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct ProfilesState {
+    profiles: Vec<BackendProfile>,
+    active: usize,
+}
+
+impl ProfilesState {
+    pub(super) fn load(config: &AppConfig) -> Self {
+        Self {
+            profiles: config.backend_profiles.clone(),
+            active: config.active_profile,
+        }
+    }
+
+    pub(super) fn all(&self) -> &[BackendProfile] {
+        &self.profiles
+    }
+
+    pub(super) fn active_index(&self) -> usize {
+        self.active
+    }
+
+    /// Выбранный профиль; если индекс устарел — первый.
+    pub(super) fn active(&self) -> &BackendProfile {
+        self.profiles
+            .get(self.active)
+            .or(self.profiles.first())
+            .expect("список профилей не бывает пустым")
+    }
+
+    /// Выбирает профиль по индексу.
+    pub(super) fn select(&self, index: usize) -> Self {
+        Self {
+            active: index.min(self.profiles.len() - 1),
+            ..self.clone()
+        }
+    }
+
+    /// Заменяет выбранный профиль изменённым.
+    pub(super) fn update_active(&self, profile: BackendProfile) -> Self {
+        let mut next = self.clone();
+        next.profiles[self.active] = profile;
+        next
+    }
+
+    /// Добавляет профиль и выбирает его; названия не повторяются.
+    pub(super) fn add(&self, profile: BackendProfile) -> Result<Self, String> {
+        if self
+            .profiles
+            .iter()
+            .any(|saved| saved.name() == profile.name())
+        {
+            return Err(format!("Профиль «{}» уже есть.", profile.name()));
+        }
+        let mut next = self.clone();
+        next.profiles.push(profile);
+        next.active = next.profiles.len() - 1;
+        Ok(next)
+    }
+
+    /// Удаляет выбранный профиль; последний профиль не удаляется.
+    pub(super) fn remove_active(&self) -> Option<Self> {
+        if self.profiles.len() < 2 {
+            return None;
+        }
+        let mut next = self.clone();
+        next.profiles.remove(self.active);
+        next.active = self.active.saturating_sub(1);
+        Some(next)
+    }
+
+    /// Сохраняет профили и выбор в `localStorage`.
+    pub(super) fn save(&self) -> Result<(), String> {
+        AppConfig::save_backend_profiles(&self.profiles)?;
+        AppConfig::save_active_profile(self.active().name())
+    }
+}
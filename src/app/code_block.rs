@@ -0,0 +1,92 @@
+//! Блок кода в ответе: подсветка, копирование и скачивание.
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use yew::prelude::*;
+
+use super::highlight::highlight;
+use crate::domain::CodeSnippet;
+
+/// Свойства компонента `CodeBlock`.
+#[derive(Properties, PartialEq)]
+pub struct CodeBlockProps {
+    pub snippet: CodeSnippet,
+}
+
+/// Итог последней попытки копирования.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CopyState {
+    Idle,
+    Copied,
+    Failed,
+}
+
+/// Подсвеченный фрагмент кода с кнопками «Копировать» и «Скачать».
+#[function_component(CodeBlock)]
+pub fn code_block(props: &CodeBlockProps) -> Html {
+    let copy_state = use_state(|| CopyState::Idle);
+    let snippet = &props.snippet;
+
+    let on_copy = {
+        let copy_state = copy_state.clone();
+        let code = snippet.code.clone();
+        Callback::from(move |_| {
+            let copy_state = copy_state.clone();
+            let code = code.clone();
+            spawn_local(async move {
+                let copied = match web_sys::window() {
+                    Some(window) => {
+                        let promise = window.navigator().clipboard().write_text(&code);
+                        JsFuture::from(promise).await.is_ok()
+                    }
+                    None => false,
+                };
+                copy_state.set(if copied {
+                    CopyState::Copied
+                } else {
+                    CopyState::Failed
+                });
+            });
+        })
+    };
+
+    // Скачивание через data: URL не требует Blob API и работает офлайн.
+    let download_href = format!(
+        "data:text/plain;charset=utf-8,{}",
+        String::from(js_sys::encode_uri_component(&snippet.code))
+    );
+
+    let copy_label = match *copy_state {
+        CopyState::Idle => "Копировать",
+        CopyState::Copied => "Скопировано",
+        CopyState::Failed => "Не удалось скопировать",
+    };
+
+    html! {
+        <div class="code-block">
+            <div class="code-block__toolbar">
+                <span class="code-block__language">{snippet.language.label().to_string()}</span>
+                <div class="actions actions--compact">
+                    <button class="button button--ghost button--small" onclick={on_copy}>
+                        {copy_label}
+                    </button>
+                    <a
+                        class="button button--ghost button--small"
+                        href={download_href}
+                        download={snippet.file_name()}
+                    >
+                        {"Скачать"}
+                    </a>
+                </div>
+            </div>
+            <pre class="markdown__code">
+                <code>
+                    {for highlight(&snippet.language, &snippet.code).into_iter().map(|token| {
+                        match token.kind.class() {
+                            Some(class) => html! { <span {class}>{token.text.to_string()}</span> },
+                            None => html! { {token.text.to_string()} },
+                        }
+                    })}
+                </code>
+            </pre>
+        </div>
+    }
+}
@@ -0,0 +1,431 @@
+//! Подсветка синтаксиса для блоков кода.
+//!
+//! Это лёгкий лексер, а не полноценный парсер: он выделяет ключевые слова,
+//! строки, комментарии, числа и литералы. Для ответов чат‑модели этого
+//! достаточно, а ошибка подсветки не ломает код — текст выводится как есть.
+use crate::domain::CodeLanguage;
+
+/// Вид фрагмента подсвеченного кода.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Plain,
+    Keyword,
+    Literal,
+    String,
+    Number,
+    Comment,
+    /// Ключ объекта JSON.
+    Key,
+    /// Переменная оболочки (`$HOME`, `${PATH}`).
+    Variable,
+}
+
+impl TokenKind {
+    /// CSS‑класс для фрагмента.
+    pub fn class(self) -> Option<&'static str> {
+        match self {
+            TokenKind::Plain => None,
+            TokenKind::Keyword => Some("hl-keyword"),
+            TokenKind::Literal => Some("hl-literal"),
+            TokenKind::String => Some("hl-string"),
+            TokenKind::Number => Some("hl-number"),
+            TokenKind::Comment => Some("hl-comment"),
+            TokenKind::Key => Some("hl-key"),
+            TokenKind::Variable => Some("hl-variable"),
+        }
+    }
+}
+
+/// Фрагмент кода с его видом.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+}
+
+/// Правила лексера для одного языка.
+struct Syntax {
+    keywords: &'static [&'static str],
+    literals: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    case_insensitive: bool,
+    shell_variables: bool,
+    json_keys: bool,
+}
+
+const RUST: Syntax = Syntax {
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+        "pub", "ref", "return", "static", "struct", "super", "trait", "type", "unsafe", "use",
+        "where", "while",
+    ],
+    literals: &["true", "false", "self", "Self", "None", "Some", "Ok", "Err"],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    // Одинарная кавычка в Rust чаще обозначает время жизни, чем символ.
+    quotes: &['"'],
+    case_insensitive: false,
+    shell_variables: false,
+    json_keys: false,
+};
+
+const PYTHON: Syntax = Syntax {
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+        "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is",
+        "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while", "with",
+        "yield",
+    ],
+    literals: &["True", "False", "None", "self"],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    case_insensitive: false,
+    shell_variables: false,
+    json_keys: false,
+};
+
+const SQL: Syntax = Syntax {
+    keywords: &[
+        "select",
+        "from",
+        "where",
+        "insert",
+        "into",
+        "values",
+        "update",
+        "set",
+        "delete",
+        "create",
+        "table",
+        "drop",
+        "alter",
+        "add",
+        "index",
+        "join",
+        "left",
+        "right",
+        "inner",
+        "outer",
+        "on",
+        "group",
+        "by",
+        "order",
+        "having",
+        "limit",
+        "offset",
+        "as",
+        "and",
+        "or",
+        "not",
+        "in",
+        "is",
+        "like",
+        "between",
+        "distinct",
+        "union",
+        "all",
+        "primary",
+        "key",
+        "foreign",
+        "references",
+        "default",
+        "case",
+        "when",
+        "then",
+        "else",
+        "end",
+        "with",
+        "returning",
+        "asc",
+        "desc",
+    ],
+    literals: &["null", "true", "false"],
+    line_comments: &["--"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['\'', '"'],
+    case_insensitive: true,
+    shell_variables: false,
+    json_keys: false,
+};
+
+const SHELL: Syntax = Syntax {
+    keywords: &[
+        "if", "then", "else", "elif", "fi", "for", "while", "until", "do", "done", "case", "esac",
+        "in", "function", "return", "export", "local", "source", "sudo",
+    ],
+    literals: &["true", "false"],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    case_insensitive: false,
+    shell_variables: true,
+    json_keys: false,
+};
+
+const JSON: Syntax = Syntax {
+    keywords: &[],
+    literals: &["true", "false", "null"],
+    line_comments: &[],
+    block_comment: None,
+    quotes: &['"'],
+    case_insensitive: false,
+    shell_variables: false,
+    json_keys: true,
+};
+
+fn syntax_for(language: &CodeLanguage) -> Option<&'static Syntax> {
+    match language {
+        CodeLanguage::Rust => Some(&RUST),
+        CodeLanguage::Python => Some(&PYTHON),
+        CodeLanguage::Sql => Some(&SQL),
+        CodeLanguage::Shell => Some(&SHELL),
+        CodeLanguage::Json => Some(&JSON),
+        CodeLanguage::PlainText | CodeLanguage::Unknown(_) => None,
+    }
+}
+
+/// Разбивает код на фрагменты для подсветки.
+///
+/// Для языков без правил возвращается один фрагмент `Plain`.
+/// Конкатенация `text` всех фрагментов всегда равна исходному коду.
+pub fn highlight<'a>(language: &CodeLanguage, code: &'a str) -> Vec<Token<'a>> {
+    let Some(syntax) = syntax_for(language) else {
+        return plain(code);
+    };
+
+    // Границы фрагментов: (вид, начало, конец). Соседние фрагменты одного
+    // вида склеиваются.
+    let mut spans: Vec<(TokenKind, usize, usize)> = Vec::new();
+    let mut position = 0;
+    while position < code.len() {
+        let previous = code[..position].chars().next_back();
+        let (kind, length) = next_token(syntax, &code[position..], previous);
+        let end = position + length;
+        match spans.last_mut() {
+            Some(last) if last.0 == kind => last.2 = end,
+            _ => spans.push((kind, position, end)),
+        }
+        position = end;
+    }
+    spans
+        .into_iter()
+        .map(|(kind, start, end)| Token {
+            kind,
+            text: &code[start..end],
+        })
+        .collect()
+}
+
+fn plain(code: &str) -> Vec<Token<'_>> {
+    if code.is_empty() {
+        return Vec::new();
+    }
+    vec![Token {
+        kind: TokenKind::Plain,
+        text: code,
+    }]
+}
+
+/// Определяет вид и длину (в байтах) следующего фрагмента.
+fn next_token(syntax: &Syntax, rest: &str, previous: Option<char>) -> (TokenKind, usize) {
+    let first = rest.chars().next().unwrap_or_default();
+    let at_word_start = previous.is_none_or(|c| !is_word_char(c));
+
+    for marker in syntax.line_comments {
+        // `#` в оболочке начинает комментарий только в начале слова.
+        if rest.starts_with(marker)
+            && (!syntax.shell_variables || previous.is_none_or(char::is_whitespace))
+        {
+            return (TokenKind::Comment, rest.find('\n').unwrap_or(rest.len()));
+        }
+    }
+
+    if let Some((open, close)) = syntax.block_comment {
+        if let Some(body) = rest.strip_prefix(open) {
+            let length = body
+                .find(close)
+                .map_or(rest.len(), |end| open.len() + end + close.len());
+            return (TokenKind::Comment, length);
+        }
+    }
+
+    if syntax.quotes.contains(&first) {
+        let length = string_length(rest, first);
+        let kind = if syntax.json_keys && rest[length..].trim_start().starts_with(':') {
+            TokenKind::Key
+        } else {
+            TokenKind::String
+        };
+        return (kind, length);
+    }
+
+    if syntax.shell_variables && first == '$' {
+        let body = &rest[1..];
+        let length = if body.starts_with('{') {
+            body.find('}').map_or(rest.len(), |end| end + 2)
+        } else {
+            1 + body
+                .char_indices()
+                .find(|(_, c)| !is_word_char(*c))
+                .map_or(body.len(), |(index, _)| index)
+        };
+        if length > 1 {
+            return (TokenKind::Variable, length);
+        }
+    }
+
+    if first.is_ascii_digit() && at_word_start {
+        let length = rest
+            .char_indices()
+            .find(|(_, c)| !(is_word_char(*c) || *c == '.'))
+            .map_or(rest.len(), |(index, _)| index);
+        return (TokenKind::Number, length);
+    }
+
+    if is_word_char(first) {
+        let length = rest
+            .char_indices()
+            .find(|(_, c)| !is_word_char(*c))
+            .map_or(rest.len(), |(index, _)| index);
+        let word = &rest[..length];
+        let matches = |list: &[&str]| {
+            list.iter().any(|candidate| {
+                if syntax.case_insensitive {
+                    candidate.eq_ignore_ascii_case(word)
+                } else {
+                    *candidate == word
+                }
+            })
+        };
+        let kind = if !at_word_start {
+            TokenKind::Plain
+        } else if matches(syntax.keywords) {
+            TokenKind::Keyword
+        } else if matches(syntax.literals) {
+            TokenKind::Literal
+        } else {
+            TokenKind::Plain
+        };
+        return (kind, length);
+    }
+
+    (TokenKind::Plain, first.len_utf8())
+}
+
+/// Длина строкового литерала с учётом экранирования `\`.
+/// Незакрытая строка продолжается до конца строки кода.
+fn string_length(rest: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (index, c) in rest.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '\n' => return index,
+            _ if c == quote => return index + c.len_utf8(),
+            _ => {}
+        }
+    }
+    rest.len()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds<'a>(tokens: &[Token<'a>]) -> Vec<(TokenKind, &'a str)> {
+        tokens
+            .iter()
+            .filter(|token| token.kind != TokenKind::Plain)
+            .map(|token| (token.kind, token.text))
+            .collect()
+    }
+
+    #[test]
+    fn highlights_rust() {
+        let code = "fn main() {\n    // печать\n    let name = \"мир \\\"!\\\"\";\n    println!(\"{}\", 42);\n}";
+        let tokens = highlight(&CodeLanguage::Rust, code);
+        assert_eq!(
+            kinds(&tokens),
+            vec![
+                (TokenKind::Keyword, "fn"),
+                (TokenKind::Comment, "// печать"),
+                (TokenKind::Keyword, "let"),
+                (TokenKind::String, "\"мир \\\"!\\\"\""),
+                (TokenKind::String, "\"{}\""),
+                (TokenKind::Number, "42"),
+            ]
+        );
+        let joined: String = tokens.iter().map(|token| token.text).collect();
+        assert_eq!(joined, code);
+    }
+
+    #[test]
+    fn highlights_json_keys_and_sql_keywords() {
+        let json = highlight(&CodeLanguage::Json, r#"{"ok": true, "name": "x"}"#);
+        assert_eq!(
+            kinds(&json),
+            vec![
+                (TokenKind::Key, "\"ok\""),
+                (TokenKind::Literal, "true"),
+                (TokenKind::Key, "\"name\""),
+                (TokenKind::String, "\"x\""),
+            ]
+        );
+
+        let sql = highlight(
+            &CodeLanguage::Sql,
+            "Select id FROM users -- все\nWHERE x IS NULL",
+        );
+        assert_eq!(
+            kinds(&sql),
+            vec![
+                (TokenKind::Keyword, "Select"),
+                (TokenKind::Keyword, "FROM"),
+                (TokenKind::Comment, "-- все"),
+                (TokenKind::Keyword, "WHERE"),
+                (TokenKind::Keyword, "IS"),
+                (TokenKind::Literal, "NULL"),
+            ]
+        );
+    }
+
+    #[test]
+    fn highlights_shell_variables_and_comments() {
+        let tokens = highlight(
+            &CodeLanguage::Shell,
+            "export PATH=$HOME/bin:${PATH} # путь\necho a#b",
+        );
+        assert_eq!(
+            kinds(&tokens),
+            vec![
+                (TokenKind::Keyword, "export"),
+                (TokenKind::Variable, "$HOME"),
+                (TokenKind::Variable, "${PATH}"),
+                (TokenKind::Comment, "# путь"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_language_stays_plain() {
+        let tokens = highlight(
+            &CodeLanguage::Unknown("kotlin".to_string()),
+            "fun main() {}",
+        );
+        assert_eq!(
+            tokens,
+            vec![Token {
+                kind: TokenKind::Plain,
+                text: "fun main() {}",
+            }]
+        );
+    }
+}
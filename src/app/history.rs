@@ -0,0 +1,224 @@
+//! Панель истории вопросов.
+//!
+//! Показывает сохранённые обмены от новых к старым. Состояние истории
+//! хранит родитель: панель лишь сообщает, какую запись открыть, спросить
+//! заново или удалить. Поисковая строка фильтрует переданные записи
+//! через `SearchHistoryUseCase` и подсвечивает найденные слова.
+use std::ops::Range;
+
+use js_sys::Date;
+use web_sys::{HtmlInputElement, InputEvent};
+use yew::prelude::*;
+
+use super::{source_label, yes_no};
+use crate::application::SearchHistoryUseCase;
+use crate::domain::{HistoryEntry, HistoryId, HistorySearchHit, Timestamp};
+
+/// Сколько байт ответа показывать до и после первого совпадения.
+const EXCERPT_BEFORE: usize = 60;
+const EXCERPT_AFTER: usize = 140;
+
+/// Свойства компонента `HistoryPanel`.
+#[derive(Properties, PartialEq)]
+pub struct HistoryPanelProps {
+    /// Записи от новых к старым.
+    pub entries: Vec<HistoryEntry>,
+    /// Ошибка чтения или записи истории.
+    pub error: Option<String>,
+    /// Блокирует повторную отправку, пока идёт запрос.
+    pub disabled: bool,
+    /// Показать сохранённый ответ.
+    pub on_reopen: Callback<HistoryEntry>,
+    /// Отправить вопрос из записи ещё раз.
+    pub on_reask: Callback<HistoryEntry>,
+    pub on_delete: Callback<HistoryId>,
+}
+
+/// Панель со списком прошлых вопросов.
+#[function_component(HistoryPanel)]
+pub fn history_panel(props: &HistoryPanelProps) -> Html {
+    let query = use_state(String::new);
+
+    // Поиск перезапускается только при смене запроса или списка записей.
+    let search = use_memo(
+        ((*query).clone(), props.entries.clone()),
+        |(query, entries)| {
+            (!query.trim().is_empty()).then(|| SearchHistoryUseCase::new(entries).execute(query))
+        },
+    );
+
+    let on_query_input = {
+        let query = query.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            query.set(input.value());
+        })
+    };
+
+    html! {
+        <section class="panel panel--history">
+            <div class="panel__header">
+                <h2>{"История"}</h2>
+                <p>{"Прошлые вопросы сохраняются в браузере."}</p>
+            </div>
+
+            {if let Some(message) = &props.error {
+                html! { <div class="message message--error">{message.clone()}</div> }
+            } else {
+                html! {}
+            }}
+
+            <input
+                class="field__input"
+                type="search"
+                placeholder="Поиск по вопросам и ответам"
+                aria-label="Поиск по истории"
+                value={(*query).clone()}
+                oninput={on_query_input}
+            />
+
+            {match &*search {
+                None if props.entries.is_empty() => html! {
+                    <p class="muted">{"История пока пуста."}</p>
+                },
+                None => html! {
+                    <ul class="history">
+                        {for props.entries.iter().map(|entry| view_entry(entry, &[], None, props))}
+                    </ul>
+                },
+                Some(hits) if hits.is_empty() => html! {
+                    <p class="muted">{"Ничего не найдено."}</p>
+                },
+                Some(hits) => html! {
+                    <ul class="history">
+                        {for hits.iter().map(|hit| view_hit(hit, props))}
+                    </ul>
+                },
+            }}
+        </section>
+    }
+}
+
+/// Найденная запись: подсвеченный вопрос и фрагмент ответа с совпадением.
+fn view_hit(hit: &HistorySearchHit, props: &HistoryPanelProps) -> Html {
+    let excerpt = excerpt(&hit.entry.result.answer, &hit.answer_highlights).map(
+        |(text, ranges)| html! { <p class="history__excerpt">{highlighted(&text, &ranges)}</p> },
+    );
+    view_entry(&hit.entry, &hit.question_highlights, excerpt, props)
+}
+
+/// Одна запись истории с действиями.
+fn view_entry(
+    entry: &HistoryEntry,
+    question_highlights: &[Range<usize>],
+    excerpt: Option<Html>,
+    props: &HistoryPanelProps,
+) -> Html {
+    let reopen = {
+        let entry = entry.clone();
+        props.on_reopen.reform(move |_: MouseEvent| entry.clone())
+    };
+    let reask = {
+        let entry = entry.clone();
+        props.on_reask.reform(move |_: MouseEvent| entry.clone())
+    };
+    let delete = {
+        let id = entry.id;
+        props.on_delete.reform(move |_: MouseEvent| id)
+    };
+    html! {
+        <li class="history__item">
+            <p class="history__question">{highlighted(entry.question.as_str(), question_highlights)}</p>
+            {excerpt.unwrap_or_default()}
+            <div class="history__meta">
+                <span>{date_time_label(entry.recorded_at)}</span>
+                <span>{format!("Источник: {}", source_label(&entry.result.source))}</span>
+                <span>{format!("Системный промпт: {}", yes_no(entry.result.system_prompt_applied))}</span>
+            </div>
+            <div class="actions actions--compact">
+                <button class="button button--ghost button--small" onclick={reopen}>{"Открыть"}</button>
+                <button
+                    class="button button--ghost button--small"
+                    disabled={props.disabled}
+                    onclick={reask}
+                >
+                    {"Спросить снова"}
+                </button>
+                <button class="button button--ghost button--small" onclick={delete}>{"Удалить"}</button>
+            </div>
+        </li>
+    }
+}
+
+/// Дата и время записи: `дд.мм.гггг чч:мм`.
+pub(super) fn date_time_label(timestamp: Timestamp) -> String {
+    let date = Date::new(&(timestamp.as_millis() as f64).into());
+    format!(
+        "{:02}.{:02}.{} {:02}:{:02}",
+        date.get_date(),
+        date.get_month() + 1,
+        date.get_full_year(),
+        date.get_hours(),
+        date.get_minutes()
+    )
+}
+
+/// Текст, в котором заданные байтовые диапазоны обёрнуты в `<mark>`.
+fn highlighted(text: &str, ranges: &[Range<usize>]) -> Html {
+    let mut parts = Vec::new();
+    let mut position = 0;
+    for range in ranges {
+        if range.start < position || range.end > text.len() {
+            continue;
+        }
+        parts.push(html! { {text[position..range.start].to_string()} });
+        parts.push(html! { <mark>{text[range.clone()].to_string()}</mark> });
+        position = range.end;
+    }
+    parts.push(html! { {text[position..].to_string()} });
+    html! { <>{for parts}</> }
+}
+
+/// Фрагмент текста вокруг первого совпадения и сдвинутые диапазоны.
+///
+/// Границы фрагмента выравниваются по символам, обрезанные края
+/// отмечаются многоточием.
+fn excerpt(text: &str, ranges: &[Range<usize>]) -> Option<(String, Vec<Range<usize>>)> {
+    let first = ranges.first()?;
+    let mut start = first.start.saturating_sub(EXCERPT_BEFORE);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (first.end + EXCERPT_AFTER).min(text.len());
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < text.len() { "…" } else { "" };
+    let shift = prefix.len();
+    let shifted = ranges
+        .iter()
+        .filter(|range| range.start >= start && range.end <= end)
+        .map(|range| range.start - start + shift..range.end - start + shift)
+        .collect();
+    Some((format!("{prefix}{}{suffix}", &text[start..end]), shifted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excerpt_keeps_char_boundaries_and_shifts_ranges() {
+        let text = format!("{}Rocket{}", "я".repeat(100), "ж".repeat(100));
+        let start = text.find("Rocket").unwrap();
+        let found = start..start + "Rocket".len();
+        let (fragment, ranges) = excerpt(&text, &[found]).unwrap();
+
+        assert!(fragment.starts_with('…'));
+        assert!(fragment.ends_with('…'));
+        assert_eq!(&fragment[ranges[0].clone()], "Rocket");
+        assert_eq!(excerpt("без совпадений", &[]), None);
+    }
+}
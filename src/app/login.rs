@@ -0,0 +1,119 @@
+//! Экран входа.
+//!
+//! Появляется поверх приложения, когда backend требует авторизацию,
+//! а ключ или токен в разделе «Доступ к API» не заданы, — или когда
+//! пользователь сам нажал «Войти». Вход выполняет родитель через
+//! `AuthenticateUseCase`; компонент лишь собирает логин и пароль.
+use web_sys::{HtmlInputElement, InputEvent};
+use yew::prelude::*;
+
+/// Свойства компонента `LoginScreen`.
+#[derive(Properties, PartialEq)]
+pub struct LoginScreenProps {
+    /// Почему понадобился вход (например, сессия истекла).
+    pub reason: Option<String>,
+    /// Идёт вход.
+    pub busy: bool,
+    /// Ошибка последней попытки входа.
+    pub error: Option<String>,
+    /// Вызывается с логином и паролем.
+    pub on_login: Callback<(String, String)>,
+    /// Закрыть экран без входа.
+    pub on_cancel: Callback<()>,
+}
+
+/// Форма входа по логину и паролю.
+#[function_component(LoginScreen)]
+pub fn login_screen(props: &LoginScreenProps) -> Html {
+    let username = use_state(String::new);
+    let password = use_state(String::new);
+
+    let on_username_input = {
+        let username = username.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            username.set(input.value());
+        })
+    };
+
+    let on_password_input = {
+        let password = password.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            password.set(input.value());
+        })
+    };
+
+    let on_submit = {
+        let username = username.clone();
+        let password = password.clone();
+        let on_login = props.on_login.clone();
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+            on_login.emit(((*username).clone(), (*password).clone()));
+            password.set(String::new());
+        })
+    };
+
+    let on_cancel = props.on_cancel.reform(|_: MouseEvent| ());
+
+    html! {
+        <div class="login" role="dialog" aria-modal="true" aria-labelledby="login-title">
+            <form class="panel login__form" onsubmit={on_submit}>
+                <div class="panel__header">
+                    <h2 id="login-title">{"Вход"}</h2>
+                    <p>{"Сервер принимает вопросы только от вошедших пользователей: у каждого своя история и квота."}</p>
+                </div>
+
+                {if let Some(reason) = &props.reason {
+                    html! { <div class="message message--error">{reason.clone()}</div> }
+                } else {
+                    html! {}
+                }}
+
+                <label class="field" for="login-username">
+                    <span class="field__label">{"Логин"}</span>
+                    <input
+                        id="login-username"
+                        class="field__input"
+                        type="text"
+                        autocomplete="username"
+                        value={(*username).clone()}
+                        oninput={on_username_input}
+                    />
+                </label>
+                <label class="field" for="login-password">
+                    <span class="field__label">{"Пароль"}</span>
+                    <input
+                        id="login-password"
+                        class="field__input"
+                        type="password"
+                        autocomplete="current-password"
+                        value={(*password).clone()}
+                        oninput={on_password_input}
+                    />
+                </label>
+
+                {if let Some(message) = &props.error {
+                    html! { <div class="message message--error">{message.clone()}</div> }
+                } else {
+                    html! {}
+                }}
+
+                <div class="actions">
+                    <button class="button" type="submit" disabled={props.busy}>
+                        {if props.busy { "Входим..." } else { "Войти" }}
+                    </button>
+                    <button
+                        class="button button--ghost"
+                        type="button"
+                        disabled={props.busy}
+                        onclick={on_cancel}
+                    >
+                        {"Отмена"}
+                    </button>
+                </div>
+            </form>
+        </div>
+    }
+}
@@ -0,0 +1,744 @@
+//! Отрисовка Markdown‑ответов в Yew.
+//!
+//! GigaChat отвечает в Markdown: заголовки, списки, таблицы, блоки кода.
+//! Модуль работает в два шага:
+//! 1) `parse` разбирает текст в дерево (AST) из `Block` и `Inline`;
+//! 2) `render_markdown` превращает дерево в `Html`.
+//!
+//! Безопасность: сырой HTML не интерпретируется — он попадает в дерево как
+//! обычный текст, а Yew экранирует текст при выводе. Ссылки допускаются
+//! только со схемами `http`, `https` и `mailto`.
+//!
+//! Поддерживается распространённое подмножество Markdown (без вложенных
+//! HTML‑блоков и ссылок‑сносок). Одиночный перевод строки внутри абзаца
+//! отображается как перенос — так ответы чат‑моделей читаются естественнее.
+use yew::prelude::*;
+
+use super::code_block::CodeBlock;
+use crate::domain::snippet::{fence_open, is_fence_close};
+use crate::domain::{CodeLanguage, CodeSnippet};
+
+/// Блочный элемент документа.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    Heading {
+        level: u8,
+        content: Vec<Inline>,
+    },
+    Paragraph(Vec<Inline>),
+    Code(CodeSnippet),
+    List {
+        ordered: bool,
+        start: u32,
+        items: Vec<Vec<Block>>,
+    },
+    Quote(Vec<Block>),
+    Table {
+        alignments: Vec<Alignment>,
+        header: Vec<Vec<Inline>>,
+        rows: Vec<Vec<Vec<Inline>>>,
+    },
+    Rule,
+}
+
+/// Выравнивание столбца таблицы.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+/// Строчный элемент текста.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inline {
+    Text(String),
+    Strong(Vec<Inline>),
+    Emphasis(Vec<Inline>),
+    Strikethrough(Vec<Inline>),
+    Code(String),
+    Link { content: Vec<Inline>, href: String },
+    LineBreak,
+}
+
+/// Разбирает Markdown‑текст в список блоков.
+pub fn parse(source: &str) -> Vec<Block> {
+    let normalized = source.replace("\r\n", "\n");
+    let lines: Vec<&str> = normalized.split('\n').collect();
+    parse_blocks(&lines)
+}
+
+/// Разбирает Markdown‑текст и отрисовывает его.
+pub fn render_markdown(source: &str) -> Html {
+    html! {
+        <div class="markdown">
+            {for parse(source).iter().map(render_block)}
+        </div>
+    }
+}
+
+fn parse_blocks(lines: &[&str]) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim_start();
+
+        if trimmed.is_empty() {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            index += 1;
+            continue;
+        }
+
+        if let Some((fence, tag)) = fence_open(trimmed) {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            let mut code_lines = Vec::new();
+            index += 1;
+            while index < lines.len() && !is_fence_close(lines[index], &fence) {
+                code_lines.push(lines[index]);
+                index += 1;
+            }
+            index += 1;
+            blocks.push(Block::Code(CodeSnippet::new(
+                CodeLanguage::from_tag(tag),
+                code_lines.join("\n"),
+            )));
+            continue;
+        }
+
+        if let Some((level, text)) = heading(trimmed) {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            blocks.push(Block::Heading {
+                level,
+                content: parse_inlines(text),
+            });
+            index += 1;
+            continue;
+        }
+
+        if is_rule(trimmed) {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            blocks.push(Block::Rule);
+            index += 1;
+            continue;
+        }
+
+        if trimmed.starts_with('>') {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            let mut quoted = Vec::new();
+            while index < lines.len() {
+                let Some(rest) = lines[index].trim_start().strip_prefix('>') else {
+                    break;
+                };
+                quoted.push(rest.strip_prefix(' ').unwrap_or(rest));
+                index += 1;
+            }
+            blocks.push(Block::Quote(parse_blocks(&quoted)));
+            continue;
+        }
+
+        if let Some(marker) = list_marker(line) {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            let (block, next) = parse_list(lines, index, marker);
+            blocks.push(block);
+            index = next;
+            continue;
+        }
+
+        if paragraph.is_empty() && index + 1 < lines.len() && line.contains('|') {
+            if let Some(alignments) = table_delimiter(lines[index + 1]) {
+                let header = split_row(line);
+                if header.len() == alignments.len() {
+                    let mut rows = Vec::new();
+                    index += 2;
+                    while index < lines.len()
+                        && lines[index].contains('|')
+                        && !lines[index].trim().is_empty()
+                    {
+                        let mut cells = split_row(lines[index]);
+                        cells.resize(alignments.len(), String::new());
+                        rows.push(cells.iter().map(|cell| parse_inlines(cell)).collect());
+                        index += 1;
+                    }
+                    blocks.push(Block::Table {
+                        alignments,
+                        header: header.iter().map(|cell| parse_inlines(cell)).collect(),
+                        rows,
+                    });
+                    continue;
+                }
+            }
+        }
+
+        paragraph.push(line.trim());
+        index += 1;
+    }
+
+    flush_paragraph(&mut paragraph, &mut blocks);
+    blocks
+}
+
+fn flush_paragraph(paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>) {
+    if paragraph.is_empty() {
+        return;
+    }
+    blocks.push(Block::Paragraph(parse_inlines(&paragraph.join("\n"))));
+    paragraph.clear();
+}
+
+fn heading(line: &str) -> Option<(u8, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some((level as u8, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|marker| compact.chars().all(|c| c == *marker))
+}
+
+/// Маркер элемента списка.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ListMarker {
+    ordered: bool,
+    number: u32,
+    /// Отступ маркера от начала строки.
+    indent: usize,
+    /// Позиция, с которой начинается текст элемента.
+    content_offset: usize,
+}
+
+/// Отступ строки в байтах. Считаются только пробелы и табуляции: другие
+/// пробельные символы (неразрывный пробел и т.п.) многобайтовые, и срез
+/// по такому отступу мог бы попасть внутрь символа.
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches([' ', '\t']).len()
+}
+
+fn list_marker(line: &str) -> Option<ListMarker> {
+    let indent = indent_of(line);
+    let rest = &line[indent..];
+    if let Some(after) = rest
+        .strip_prefix("- ")
+        .or_else(|| rest.strip_prefix("* "))
+        .or_else(|| rest.strip_prefix("+ "))
+    {
+        return Some(ListMarker {
+            ordered: false,
+            number: 1,
+            indent,
+            content_offset: line.len() - after.len(),
+        });
+    }
+    let digits = rest.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 || digits > 9 {
+        return None;
+    }
+    let after = rest[digits..]
+        .strip_prefix(". ")
+        .or_else(|| rest[digits..].strip_prefix(") "))?;
+    Some(ListMarker {
+        ordered: true,
+        number: rest[..digits].parse().ok()?,
+        indent,
+        content_offset: line.len() - after.len(),
+    })
+}
+
+/// Разбирает список, начинающийся со строки `start`.
+///
+/// Возвращает блок и индекс первой строки после списка. Строки с отступом
+/// больше, чем у маркера, относятся к текущему элементу (так получаются
+/// вложенные списки и многострочные элементы).
+fn parse_list(lines: &[&str], start: usize, first: ListMarker) -> (Block, usize) {
+    let mut items: Vec<Vec<Block>> = Vec::new();
+    let mut index = start;
+
+    while index < lines.len() {
+        let Some(marker) = list_marker(lines[index]) else {
+            break;
+        };
+        if marker.ordered != first.ordered || marker.indent != first.indent {
+            break;
+        }
+
+        let mut item_lines: Vec<&str> = vec![&lines[index][marker.content_offset..]];
+        index += 1;
+        while index < lines.len() {
+            let line = lines[index];
+            let indent = indent_of(line);
+            if line.trim().is_empty() {
+                let continues = lines
+                    .get(index + 1)
+                    .is_some_and(|next| indent_of(next) > first.indent);
+                if !continues {
+                    break;
+                }
+                item_lines.push("");
+            } else if indent > first.indent {
+                item_lines.push(&line[indent.min(marker.content_offset)..]);
+            } else {
+                break;
+            }
+            index += 1;
+        }
+        items.push(parse_blocks(&item_lines));
+
+        // Пустая строка между элементами одного списка не завершает список.
+        if index < lines.len()
+            && lines[index].trim().is_empty()
+            && lines
+                .get(index + 1)
+                .and_then(|next| list_marker(next))
+                .is_some_and(|next| next.ordered == first.ordered && next.indent == first.indent)
+        {
+            index += 1;
+        }
+    }
+
+    let block = Block::List {
+        ordered: first.ordered,
+        start: first.number,
+        items,
+    };
+    (block, index)
+}
+
+/// Разбирает строку‑разделитель таблицы вида `| --- | :---: |`.
+fn table_delimiter(line: &str) -> Option<Vec<Alignment>> {
+    let cells = split_row(line);
+    if cells.is_empty() {
+        return None;
+    }
+    cells
+        .iter()
+        .map(|cell| {
+            let cell = cell.trim();
+            let left = cell.starts_with(':');
+            let right = cell.ends_with(':');
+            let dashes = cell.trim_matches(':');
+            if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+                return None;
+            }
+            Some(match (left, right) {
+                (true, true) => Alignment::Center,
+                (true, false) => Alignment::Left,
+                (false, true) => Alignment::Right,
+                (false, false) => Alignment::None,
+            })
+        })
+        .collect()
+}
+
+fn split_row(line: &str) -> Vec<String> {
+    let trimmed = line.trim();
+    let trimmed = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let trimmed = trimmed.strip_suffix('|').unwrap_or(trimmed);
+    trimmed
+        .split('|')
+        .map(|cell| cell.trim().to_string())
+        .collect()
+}
+
+/// Разбирает строчную разметку: `**жирный**`, `*курсив*`, `` `код` ``,
+/// `~~зачёркнутый~~`, `[ссылка](url)`, экранирование `\`.
+fn parse_inlines(text: &str) -> Vec<Inline> {
+    let mut result = Vec::new();
+    let mut buffer = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let parsed = match c {
+            '\\' => rest[1..]
+                .chars()
+                .next()
+                .filter(|next| next.is_ascii_punctuation())
+                .map(|next| (Inline::Text(next.to_string()), 1 + next.len_utf8())),
+            '`' => code_span(rest),
+            '*' | '_' if rest[1..].starts_with(c) => delimited(rest, &rest[..2], |content| {
+                Inline::Strong(parse_inlines(content))
+            }),
+            '*' | '_' => emphasis(rest, c, buffer.chars().last()),
+            '~' if rest.starts_with("~~") => delimited(rest, "~~", |content| {
+                Inline::Strikethrough(parse_inlines(content))
+            }),
+            '[' => link(rest),
+            '\n' => Some((Inline::LineBreak, 1)),
+            _ => None,
+        };
+
+        match parsed {
+            Some((inline, consumed)) => {
+                if let Inline::Text(text) = inline {
+                    buffer.push_str(&text);
+                } else {
+                    if !buffer.is_empty() {
+                        result.push(Inline::Text(std::mem::take(&mut buffer)));
+                    }
+                    result.push(inline);
+                }
+                rest = &rest[consumed..];
+            }
+            None => {
+                buffer.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    if !buffer.is_empty() {
+        result.push(Inline::Text(buffer));
+    }
+    result
+}
+
+fn code_span(text: &str) -> Option<(Inline, usize)> {
+    let ticks = text.chars().take_while(|c| *c == '`').count();
+    let fence = &text[..ticks];
+    let body = &text[ticks..];
+    let end = body.find(fence)?;
+    let code = body[..end].trim();
+    if code.is_empty() {
+        return None;
+    }
+    Some((Inline::Code(code.to_string()), ticks + end + ticks))
+}
+
+fn delimited(
+    text: &str,
+    delimiter: &str,
+    build: impl FnOnce(&str) -> Inline,
+) -> Option<(Inline, usize)> {
+    let body = &text[delimiter.len()..];
+    let end = body.find(delimiter)?;
+    let content = &body[..end];
+    if content.is_empty() || content.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some((build(content), delimiter.len() * 2 + end))
+}
+
+fn emphasis(text: &str, marker: char, previous: Option<char>) -> Option<(Inline, usize)> {
+    // `_` внутри слова (snake_case) не считается разметкой.
+    if marker == '_' && previous.is_some_and(char::is_alphanumeric) {
+        return None;
+    }
+    let body = &text[1..];
+    let end = body
+        .char_indices()
+        .find(|(index, c)| *c == marker && !body[index + 1..].starts_with(marker))
+        .map(|(index, _)| index)?;
+    let content = &body[..end];
+    if content.is_empty()
+        || content.starts_with(char::is_whitespace)
+        || content.ends_with(char::is_whitespace)
+    {
+        return None;
+    }
+    Some((Inline::Emphasis(parse_inlines(content)), end + 2))
+}
+
+fn link(text: &str) -> Option<(Inline, usize)> {
+    let label_end = text.find("](")?;
+    let label = &text[1..label_end];
+    if label.contains('\n') {
+        return None;
+    }
+    let target = &text[label_end + 2..];
+    // Скобки внутри адреса допускаются, если они сбалансированы.
+    let mut depth = 0usize;
+    let target_end = target.char_indices().find_map(|(index, c)| match c {
+        '(' => {
+            depth += 1;
+            None
+        }
+        ')' if depth == 0 => Some(index),
+        ')' => {
+            depth -= 1;
+            None
+        }
+        _ => None,
+    })?;
+    let href = target[..target_end].trim();
+    let consumed = label_end + 2 + target_end + 1;
+    if !is_safe_href(href) {
+        return Some((Inline::Text(label.to_string()), consumed));
+    }
+    Some((
+        Inline::Link {
+            content: parse_inlines(label),
+            href: href.to_string(),
+        },
+        consumed,
+    ))
+}
+
+/// Разрешены только внешние ссылки с безопасными схемами.
+fn is_safe_href(href: &str) -> bool {
+    let lower = href.to_ascii_lowercase();
+    !href.contains(char::is_whitespace)
+        && ["http://", "https://", "mailto:"]
+            .iter()
+            .any(|scheme| lower.starts_with(scheme))
+}
+
+fn render_block(block: &Block) -> Html {
+    match block {
+        Block::Heading { level, content } => {
+            let inner = render_inlines(content);
+            match level {
+                1 => html! { <h3>{inner}</h3> },
+                2 => html! { <h4>{inner}</h4> },
+                3 => html! { <h5>{inner}</h5> },
+                _ => html! { <h6>{inner}</h6> },
+            }
+        }
+        Block::Paragraph(content) => html! { <p>{render_inlines(content)}</p> },
+        Block::Code(snippet) => html! { <CodeBlock snippet={snippet.clone()} /> },
+        Block::List {
+            ordered,
+            start,
+            items,
+        } => {
+            let items = items.iter().map(|item| {
+                html! { <li>{for item.iter().map(render_block)}</li> }
+            });
+            if *ordered {
+                html! { <ol start={start.to_string()}>{for items}</ol> }
+            } else {
+                html! { <ul>{for items}</ul> }
+            }
+        }
+        Block::Quote(blocks) => html! {
+            <blockquote>{for blocks.iter().map(render_block)}</blockquote>
+        },
+        Block::Table {
+            alignments,
+            header,
+            rows,
+        } => html! {
+            <div class="markdown__table">
+                <table>
+                    <thead>
+                        <tr>
+                            {for header.iter().zip(alignments).map(|(cell, alignment)| html! {
+                                <th style={alignment_style(*alignment)}>{render_inlines(cell)}</th>
+                            })}
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {for rows.iter().map(|row| html! {
+                            <tr>
+                                {for row.iter().zip(alignments).map(|(cell, alignment)| html! {
+                                    <td style={alignment_style(*alignment)}>{render_inlines(cell)}</td>
+                                })}
+                            </tr>
+                        })}
+                    </tbody>
+                </table>
+            </div>
+        },
+        Block::Rule => html! { <hr /> },
+    }
+}
+
+fn alignment_style(alignment: Alignment) -> Option<&'static str> {
+    match alignment {
+        Alignment::None => None,
+        Alignment::Left => Some("text-align: left"),
+        Alignment::Center => Some("text-align: center"),
+        Alignment::Right => Some("text-align: right"),
+    }
+}
+
+fn render_inlines(inlines: &[Inline]) -> Html {
+    html! { <>{for inlines.iter().map(render_inline)}</> }
+}
+
+fn render_inline(inline: &Inline) -> Html {
+    match inline {
+        Inline::Text(text) => html! { {text.clone()} },
+        Inline::Strong(content) => html! { <strong>{render_inlines(content)}</strong> },
+        Inline::Emphasis(content) => html! { <em>{render_inlines(content)}</em> },
+        Inline::Strikethrough(content) => html! { <s>{render_inlines(content)}</s> },
+        Inline::Code(code) => html! { <code>{code.clone()}</code> },
+        Inline::Link { content, href } => html! {
+            <a href={href.clone()} target="_blank" rel="noopener noreferrer">
+                {render_inlines(content)}
+            </a>
+        },
+        Inline::LineBreak => html! { <br /> },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> Inline {
+        Inline::Text(value.to_string())
+    }
+
+    #[test]
+    fn parses_typical_answer() {
+        let answer = "## Rocket\n\
+                      Rocket — **веб‑фреймворк** для *Rust*.\n\
+                      \n\
+                      Преимущества:\n\
+                      - типобезопасная маршрутизация;\n\
+                      - удобные `guards`.\n\
+                      \n\
+                      1. Установите Rust\n\
+                      2. Добавьте зависимость";
+        let blocks = parse(answer);
+
+        assert_eq!(
+            blocks[0],
+            Block::Heading {
+                level: 2,
+                content: vec![text("Rocket")],
+            }
+        );
+        assert_eq!(
+            blocks[1],
+            Block::Paragraph(vec![
+                text("Rocket — "),
+                Inline::Strong(vec![text("веб‑фреймворк")]),
+                text(" для "),
+                Inline::Emphasis(vec![text("Rust")]),
+                text("."),
+            ])
+        );
+        assert_eq!(blocks[2], Block::Paragraph(vec![text("Преимущества:")]));
+        assert_eq!(
+            blocks[3],
+            Block::List {
+                ordered: false,
+                start: 1,
+                items: vec![
+                    vec![Block::Paragraph(vec![text(
+                        "типобезопасная маршрутизация;"
+                    )])],
+                    vec![Block::Paragraph(vec![
+                        text("удобные "),
+                        Inline::Code("guards".to_string()),
+                        text("."),
+                    ])],
+                ],
+            }
+        );
+        assert!(matches!(
+            &blocks[4],
+            Block::List { ordered: true, start: 1, items } if items.len() == 2
+        ));
+        assert_eq!(blocks.len(), 5);
+    }
+
+    #[test]
+    fn parses_fenced_code_without_inline_markup() {
+        let blocks =
+            parse("Пример:\n```Rust\nfn main() {\n    println!(\"**hi**\");\n}\n```\nГотово.");
+        assert_eq!(
+            blocks[1],
+            Block::Code(CodeSnippet::new(
+                CodeLanguage::Rust,
+                "fn main() {\n    println!(\"**hi**\");\n}",
+            ))
+        );
+        assert_eq!(blocks[2], Block::Paragraph(vec![text("Готово.")]));
+    }
+
+    #[test]
+    fn unclosed_fence_runs_to_the_end() {
+        let blocks = parse("```\nlet x = 1;");
+        assert_eq!(
+            blocks,
+            vec![Block::Code(CodeSnippet::new(
+                CodeLanguage::PlainText,
+                "let x = 1;",
+            ))]
+        );
+    }
+
+    #[test]
+    fn parses_table_with_alignment() {
+        let blocks = parse("| Язык | Год |\n|:-----|----:|\n| Rust | 2015 |\n| Go | 2009 |");
+        let Block::Table {
+            alignments,
+            header,
+            rows,
+        } = &blocks[0]
+        else {
+            panic!("ожидалась таблица: {blocks:?}");
+        };
+        assert_eq!(alignments, &vec![Alignment::Left, Alignment::Right]);
+        assert_eq!(header[0], vec![text("Язык")]);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1][1], vec![text("2009")]);
+    }
+
+    #[test]
+    fn nested_list_and_quote() {
+        let blocks = parse("- внешний\n  - вложенный\n> цитата\n> **важно**");
+        let Block::List { items, .. } = &blocks[0] else {
+            panic!("ожидался список: {blocks:?}");
+        };
+        assert!(matches!(items[0][1], Block::List { .. }));
+        assert_eq!(
+            blocks[1],
+            Block::Quote(vec![Block::Paragraph(vec![
+                text("цитата"),
+                Inline::LineBreak,
+                Inline::Strong(vec![text("важно")]),
+            ])])
+        );
+    }
+
+    #[test]
+    fn raw_html_and_unsafe_links_stay_text() {
+        let blocks = parse(
+            "<script>alert(1)</script> [клик](javascript:alert(1)) [сайт](https://rust-lang.org)",
+        );
+        assert_eq!(
+            blocks[0],
+            Block::Paragraph(vec![
+                text("<script>alert(1)</script> клик "),
+                Inline::Link {
+                    content: vec![text("сайт")],
+                    href: "https://rust-lang.org".to_string(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn snake_case_and_escapes_are_not_markup() {
+        assert_eq!(
+            parse("вызовите my_func_name и \\*звёздочки\\*"),
+            vec![Block::Paragraph(vec![text(
+                "вызовите my_func_name и *звёздочки*"
+            )])]
+        );
+    }
+
+    #[test]
+    fn unicode_spaces_are_not_list_indent() {
+        for answer in ["1. пункт\n\u{a0}\u{a0}продолжение", "- a\n\u{3000}b"] {
+            let blocks = parse(answer);
+            assert!(matches!(&blocks[0], Block::List { items, .. } if items.len() == 1));
+            assert_eq!(blocks.len(), 2, "{answer:?}: {blocks:?}");
+        }
+    }
+}
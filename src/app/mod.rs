@@ -0,0 +1,2197 @@
+//! UI‑слой приложения на Yew.
+//!
+//! В этом модуле собраны:
+//! - состояние экрана (поле вопроса, диалог, результат запроса, состояние здоровья API);
+//! - обработчики пользовательских действий (ввод, отправка, сохранение URL);
+//! - визуальные компоненты (через `html!`).
+//!
+//! Самостоятельные блоки со своим состоянием вынесены в подмодули
+//! (например, `templates` — шаблоны вопросов, `outbox` — очередь
+//! неотправленных вопросов).
+//!
+//! В учебных целях код оставлен линейным и читаемым. Он показывает:
+//! - как разделять состояние на несколько частей;
+//! - как вызывать асинхронные use‑cases через `spawn_local`;
+//! - как отображать состояния **loading / error / ready**.
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use gloo_timers::callback::Timeout;
+use js_sys::Date;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlInputElement, HtmlTextAreaElement, InputEvent};
+use yew::prelude::*;
+
+use crate::application::circuit_breaker::CircuitObserver;
+use crate::application::ports::{
+    ApiError, ApiErrorCode, CancellationToken, ChatGateway, GatewayError, SessionRepository,
+    StreamingChatGateway,
+};
+use crate::application::retry::RetryObserver;
+use crate::application::{
+    AnswerCache, AskQuestionUseCase, AuthSettings, BackendKind, BackendProfile, CachingChatGateway,
+    CheckHealthUseCase, CircuitBreaker, CircuitBreakerGateway, CircuitBreakerPolicy, CircuitState,
+    ContinueConversationUseCase, DeduplicatingGateway, GatewayPair, InFlightRequests,
+    ManageHistoryUseCase, OutboxUseCase, RateLimitedGateway, RateLimiter, RecordExchangeUseCase,
+    RetryAttempt, RetryPolicy, RetryingGateway, UseCaseError,
+};
+use crate::config::AppConfig;
+use crate::domain::{
+    AnswerSource, AskResult, Conversation, DomainError, GenerationSettings, HealthStatus,
+    HeuristicTokenEstimator, HistoryEntry, HistoryId, MaxTokens, Message, MessageRole, ModelName,
+    OutboxId, PendingQuestion, QuestionPolicy, ServiceState, SystemPrompt, SystemPromptPreset,
+    Temperature, Timestamp, TokenBudget, TokenEstimator, TopP,
+};
+use crate::infrastructure::{
+    BrowserClock, BrowserSleeper, LocalStorageAnswerCache, LocalStorageHistoryRepository,
+    LocalStorageOutboxRepository, SessionStorageCredentials, SessionStorageSessionRepository,
+};
+
+mod auth;
+mod backend;
+mod code_block;
+mod highlight;
+mod history;
+mod login;
+mod markdown;
+mod outbox;
+mod templates;
+
+use auth::{session_usecase, AuthSettingsPanel, AuthState};
+use backend::{gateways as profile_gateways, supported_kinds, ProfilesState};
+use history::HistoryPanel;
+use login::LoginScreen;
+use markdown::render_markdown;
+use outbox::OutboxPanel;
+use templates::{TemplatePicker, TemplateSubmission};
+
+/// Общее состояние загрузки для любого блока UI.
+///
+/// Используется для того, чтобы явно показывать три ключевые фазы:
+/// 1) ожидание, 2) успех, 3) ошибка.
+#[derive(Clone, Debug, PartialEq)]
+enum LoadState<T> {
+    Idle,
+    Loading,
+    Ready(T),
+    Error(String),
+}
+
+impl<T> LoadState<T> {
+    fn is_loading(&self) -> bool {
+        matches!(self, LoadState::Loading)
+    }
+}
+
+/// Состояние блока "Статус API".
+///
+/// Дополнительно хранит время последней проверки, чтобы пользователь видел,
+/// когда именно было получено значение.
+#[derive(Clone, Debug, PartialEq)]
+struct HealthViewState {
+    state: LoadState<HealthStatus>,
+    last_checked: Option<String>,
+}
+
+impl HealthViewState {
+    fn idle() -> Self {
+        Self {
+            state: LoadState::Idle,
+            last_checked: None,
+        }
+    }
+}
+
+/// Состояние декораторов шлюза, общее для всех запросов: кэш ответов,
+/// лимит частоты, незавершённые запросы и автоматы защиты.
+///
+/// Ключи кэша и незавершённых запросов включают название профиля,
+/// а автомат защиты у каждого профиля свой: ответы и сбои одного
+/// backend не переносятся на другой.
+#[derive(Clone)]
+struct SharedGateways {
+    answer_cache: AnswerCache,
+    rate_limiter: RateLimiter,
+    in_flight: InFlightRequests,
+    circuit_breakers: Rc<RefCell<HashMap<String, CircuitBreaker>>>,
+    circuit_policy: CircuitBreakerPolicy,
+    on_circuit_change: CircuitObserver,
+}
+
+impl SharedGateways {
+    fn new(config: &AppConfig, on_circuit_change: CircuitObserver) -> Self {
+        let answer_cache = if config.persist_answer_cache {
+            AnswerCache::persistent(config.cache_policy, LocalStorageAnswerCache)
+        } else {
+            AnswerCache::new(config.cache_policy)
+        };
+        Self {
+            answer_cache,
+            rate_limiter: RateLimiter::new(config.rate_limit),
+            in_flight: InFlightRequests::new(),
+            circuit_breakers: Rc::default(),
+            circuit_policy: config.circuit_breaker,
+            on_circuit_change,
+        }
+    }
+
+    /// Автомат защиты профиля; создаётся при первом обращении.
+    fn circuit_breaker(&self, profile: &BackendProfile) -> CircuitBreaker {
+        self.circuit_breakers
+            .borrow_mut()
+            .entry(profile.name().to_string())
+            .or_insert_with(|| {
+                CircuitBreaker::new(self.circuit_policy)
+                    .with_observer(self.on_circuit_change.clone())
+            })
+            .clone()
+    }
+}
+
+/// Состояние панели истории: записи (от новых к старым) и последняя ошибка.
+#[derive(Clone, Debug, PartialEq, Default)]
+struct HistoryViewState {
+    entries: Vec<HistoryEntry>,
+    error: Option<String>,
+}
+
+impl HistoryViewState {
+    /// Читает историю из хранилища браузера.
+    fn load() -> Self {
+        match ManageHistoryUseCase::new(LocalStorageHistoryRepository).list() {
+            Ok(entries) => Self {
+                entries,
+                error: None,
+            },
+            Err(error) => Self {
+                entries: Vec::new(),
+                error: Some(error_message(error)),
+            },
+        }
+    }
+
+    /// Те же записи, но с ошибкой последней операции.
+    fn with_error(&self, error: UseCaseError) -> Self {
+        Self {
+            entries: self.entries.clone(),
+            error: Some(error_message(error)),
+        }
+    }
+}
+
+/// Состояние очереди неотправленных вопросов.
+#[derive(Clone, Debug, PartialEq, Default)]
+struct OutboxViewState {
+    items: Vec<PendingQuestion>,
+    error: Option<String>,
+    notice: Option<String>,
+    sending: bool,
+}
+
+impl OutboxViewState {
+    /// Читает очередь из хранилища браузера.
+    fn load() -> Self {
+        match OutboxUseCase::new(LocalStorageOutboxRepository, BrowserClock).list() {
+            Ok(items) => Self {
+                items,
+                ..Self::default()
+            },
+            Err(error) => Self {
+                error: Some(error_message(error)),
+                ..Self::default()
+            },
+        }
+    }
+
+    /// Те же записи в процессе отправки.
+    fn sending(&self) -> Self {
+        Self {
+            items: self.items.clone(),
+            sending: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// Вопрос, который отправляет пользователь.
+enum Submission {
+    /// Вопрос из поля ввода: уходит с контекстом и дописывается в диалог.
+    Dialog(String),
+    /// Вопрос по шаблону: уходит отдельно, без контекста диалога.
+    Template(String),
+}
+
+/// Параметры запроса, общие для обычного вопроса и вопроса по шаблону.
+struct AskOptions {
+    generation: GenerationSettings,
+    system_prompt: Option<SystemPrompt>,
+}
+
+/// Введённые пользователем параметры генерации (сырые строки из полей).
+///
+/// Пустое поле означает «не передавать параметр».
+#[derive(Clone, Debug, Default, PartialEq)]
+struct GenerationInputs {
+    temperature: String,
+    top_p: String,
+    max_tokens: String,
+    model: String,
+}
+
+impl GenerationInputs {
+    /// Преобразует строки из полей в доменные параметры генерации.
+    fn parse(&self) -> Result<GenerationSettings, String> {
+        Ok(GenerationSettings {
+            temperature: optional_field(&self.temperature, |raw| {
+                Temperature::try_new(parse_decimal(raw, "Температура")?).map_err(|e| e.to_string())
+            })?,
+            top_p: optional_field(&self.top_p, |raw| {
+                TopP::try_new(parse_decimal(raw, "top_p")?).map_err(|e| e.to_string())
+            })?,
+            max_tokens: optional_field(&self.max_tokens, |raw| {
+                let value = raw
+                    .parse::<u32>()
+                    .map_err(|_| "Максимум токенов: ожидается целое число".to_string())?;
+                MaxTokens::try_new(value).map_err(|e| e.to_string())
+            })?,
+            model: optional_field(&self.model, |raw| {
+                ModelName::try_new(raw).map_err(|e| e.to_string())
+            })?,
+        })
+    }
+}
+
+/// Главный компонент приложения.
+///
+/// Он управляет всеми сценариями UI:
+/// - ввод и отправка вопроса;
+/// - ведение диалога (предыдущие сообщения уходят в API как контекст);
+/// - отображение ответа;
+/// - настройка базового URL API;
+/// - проверка состояния backend‑сервера.
+///
+/// Компонент использует use‑cases из слоя `application`, поэтому UI не знает
+/// деталей сетевого обмена.
+#[function_component(App)]
+pub fn app() -> Html {
+    let config = AppConfig::load();
+    let question_policy_state = use_state(|| config.question_policy.clone());
+    let question_policy = (*question_policy_state).clone();
+    let question_policy_error = use_state(|| Option::<String>::None);
+    let retry_policy = config.retry_policy;
+    let request_timeout_ms = config.request_timeout_ms;
+
+    let question = use_state(String::new);
+    let ask_state = use_state(|| LoadState::Idle);
+    let ask_attempt = use_state(|| Option::<RetryAttempt>::None);
+    let partial_answer = use_state(|| Option::<String>::None);
+    let retry_question = use_state(|| Option::<String>::None);
+    // Последняя смена состояния любого автомата защиты: нужна, чтобы
+    // перерисовать панель статуса. Показывается автомат выбранного профиля.
+    let circuit_change = use_state(|| CircuitState::Closed { failures: 0 });
+    let gateways = {
+        let circuit_change = circuit_change.clone();
+        use_state(|| SharedGateways::new(&config, Rc::new(move |state| circuit_change.set(state))))
+    };
+    let bypass_cache = use_state(|| false);
+    let cached_answers = use_state(|| gateways.answer_cache.len());
+    let ask_cancellation = use_mut_ref(|| Option::<CancellationToken>::None);
+    let conversation = use_state(Conversation::new);
+    let answer_in_dialog = use_state(|| false);
+    let reopened_question = use_state(|| Option::<String>::None);
+    let history = use_state(HistoryViewState::load);
+    let outbox = use_state(OutboxViewState::load);
+    let outbox_busy = use_mut_ref(|| false);
+    let latest_replay = use_mut_ref(|| Option::<Rc<dyn Fn(Option<OutboxId>)>>::None);
+    let generation_inputs = use_state(GenerationInputs::default);
+    let system_prompt_text = use_state(String::new);
+    let prompt_presets = use_state(AppConfig::load_prompt_presets);
+    let preset_name = use_state(String::new);
+    let preset_error = use_state(|| Option::<String>::None);
+    let token_budget = use_state(|| config.token_budget);
+    let token_budget_input = use_state(|| config.token_budget.get().to_string());
+    let token_budget_error = use_state(|| Option::<String>::None);
+
+    let profiles = use_state(|| ProfilesState::load(&config));
+    let api_base_url_input = use_state(|| profiles.active().base_url().as_str().to_string());
+    let api_base_url_error = use_state(|| Option::<String>::None);
+    let api_base_url_notice = use_state(|| Option::<String>::None);
+    let new_profile_name = use_state(String::new);
+
+    let health_state = use_state(HealthViewState::idle);
+
+    // Учётные данные живут в `sessionStorage`, а не в `AppConfig`,
+    // у каждого профиля свои.
+    let auth = use_state(|| AuthState::load(profiles.active()));
+    let auth_prompt = use_state(|| Option::<String>::None);
+    let auth_error = use_state(|| Option::<String>::None);
+    let session = use_state(|| {
+        SessionStorageSessionRepository::for_base_url(profiles.active().base_url())
+            .load()
+            .ok()
+            .flatten()
+    });
+    let login_open = use_state(|| false);
+    let login_state = use_state(|| LoadState::<()>::Idle);
+
+    // Отправка очереди: всей (`None`) или одного вопроса. Повторный вызов,
+    // пока очередь отправляется, ничего не делает.
+    let replay_outbox: Rc<dyn Fn(Option<OutboxId>)> = {
+        let profiles = profiles.clone();
+        let gateways = gateways.clone();
+        let cached_answers = cached_answers.clone();
+        let history = history.clone();
+        let outbox = outbox.clone();
+        let outbox_busy = outbox_busy.clone();
+        let auth = auth.clone();
+        let auth_prompt = auth_prompt.clone();
+        let question_policy = question_policy.clone();
+        Rc::new(move |only: Option<OutboxId>| {
+            if *outbox_busy.borrow() {
+                return;
+            }
+            let usecase = OutboxUseCase::new(LocalStorageOutboxRepository, BrowserClock)
+                .with_policy(question_policy.clone());
+            let items = match usecase.list() {
+                Ok(items) => items,
+                Err(error) => {
+                    outbox.set(OutboxViewState {
+                        error: Some(error_message(error)),
+                        ..OutboxViewState::default()
+                    });
+                    return;
+                }
+            };
+            let items: Vec<PendingQuestion> = items
+                .into_iter()
+                .filter(|item| only.is_none_or(|id| item.id == id))
+                .collect();
+            let profile = profiles.active().clone();
+            let Ok(client) = profile_gateways(&profile, request_timeout_ms, &auth) else {
+                return;
+            };
+            if items.is_empty() {
+                return;
+            }
+
+            *outbox_busy.borrow_mut() = true;
+            outbox.set(outbox.sending());
+            let gateways = (*gateways).clone();
+            let cached_answers = cached_answers.clone();
+            let history = history.clone();
+            let outbox = outbox.clone();
+            let outbox_busy = outbox_busy.clone();
+            let auth_prompt = auth_prompt.clone();
+            let question_policy = question_policy.clone();
+            spawn_local(async move {
+                let gateway = ask_gateway(client, &profile, retry_policy, None, &gateways);
+                let outcome = match only {
+                    None => usecase.replay(&gateway).await.map(|report| report.sent),
+                    Some(_) => {
+                        let item = &items[0];
+                        usecase
+                            .send(&gateway, item)
+                            .await
+                            .map(|result| vec![(item.clone(), result)])
+                    }
+                };
+                cached_answers.set(gateways.answer_cache.len());
+                let mut state = OutboxViewState::load();
+                match outcome {
+                    Ok(sent) => {
+                        for (item, result) in &sent {
+                            record_history(
+                                &history,
+                                &question_policy,
+                                item.question.as_str(),
+                                result,
+                            );
+                        }
+                        if !sent.is_empty() {
+                            state.notice = Some(format!(
+                                "Отправлено из очереди: {}. Ответы сохранены в истории.",
+                                sent.len()
+                            ));
+                        }
+                    }
+                    Err(error) => {
+                        prompt_reauth(&auth_prompt, &error);
+                        state.error = Some(error_message(error));
+                    }
+                }
+                outbox.set(state);
+                *outbox_busy.borrow_mut() = false;
+            });
+        })
+    };
+    // Слушатель события `online` живёт дольше одного рендера, поэтому
+    // берёт актуальную версию `replay_outbox` отсюда.
+    *latest_replay.borrow_mut() = Some(replay_outbox.clone());
+
+    {
+        let latest_replay = latest_replay.clone();
+        use_effect_with((), move |_| {
+            let listener = Closure::<dyn Fn()>::new(move || {
+                let replay = latest_replay.borrow().clone();
+                if let Some(replay) = replay {
+                    replay(None);
+                }
+            });
+            let window = web_sys::window();
+            if let Some(window) = &window {
+                let _ = window
+                    .add_event_listener_with_callback("online", listener.as_ref().unchecked_ref());
+            }
+            move || {
+                if let Some(window) = &window {
+                    let _ = window.remove_event_listener_with_callback(
+                        "online",
+                        listener.as_ref().unchecked_ref(),
+                    );
+                }
+            }
+        });
+    }
+
+    let run_health_check: Rc<dyn Fn()> = {
+        let profiles = profiles.clone();
+        let health_state = health_state.clone();
+        let replay_outbox = replay_outbox.clone();
+        let auth = auth.clone();
+        let auth_prompt = auth_prompt.clone();
+        Rc::new(move || {
+            let profile = profiles.active().clone();
+            let health_state = health_state.clone();
+            let replay_outbox = replay_outbox.clone();
+            let auth = (*auth).clone();
+            let auth_prompt = auth_prompt.clone();
+            spawn_local(async move {
+                health_state.set(HealthViewState {
+                    state: LoadState::Loading,
+                    last_checked: None,
+                });
+
+                let client = match profile_gateways(&profile, request_timeout_ms, &auth) {
+                    Ok(client) => client,
+                    Err(error) => {
+                        health_state.set(HealthViewState {
+                            state: LoadState::Error(error),
+                            last_checked: None,
+                        });
+                        return;
+                    }
+                };
+
+                let usecase = CheckHealthUseCase::new(with_retries(client.health, retry_policy));
+                match usecase.execute().await {
+                    Ok(status) => {
+                        if status.status != ServiceState::Down {
+                            replay_outbox(None);
+                        }
+                        health_state.set(HealthViewState {
+                            state: LoadState::Ready(status),
+                            last_checked: Some(now_label()),
+                        });
+                    }
+                    Err(error) => {
+                        prompt_reauth(&auth_prompt, &error);
+                        health_state.set(HealthViewState {
+                            state: LoadState::Error(error_message(error)),
+                            last_checked: Some(now_label()),
+                        });
+                    }
+                }
+            });
+        })
+    };
+
+    {
+        let run_health_check = run_health_check.clone();
+        use_effect_with(profiles.active().clone(), move |_| {
+            run_health_check();
+            || ()
+        });
+    }
+
+    let on_question_input = {
+        let question = question.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlTextAreaElement = event.target_unchecked_into();
+            question.set(input.value());
+        })
+    };
+
+    let submit_question: Rc<dyn Fn(Submission)> = {
+        let question = question.clone();
+        let ask_state = ask_state.clone();
+        let ask_attempt = ask_attempt.clone();
+        let partial_answer = partial_answer.clone();
+        let retry_question = retry_question.clone();
+        let gateways = gateways.clone();
+        let cached_answers = cached_answers.clone();
+        let bypass_cache = bypass_cache.clone();
+        let outbox = outbox.clone();
+        let ask_cancellation = ask_cancellation.clone();
+        let conversation = conversation.clone();
+        let answer_in_dialog = answer_in_dialog.clone();
+        let reopened_question = reopened_question.clone();
+        let history = history.clone();
+        let profiles = profiles.clone();
+        let auth = auth.clone();
+        let auth_prompt = auth_prompt.clone();
+        let question_policy = question_policy.clone();
+        let generation_inputs = generation_inputs.clone();
+        let system_prompt_text = system_prompt_text.clone();
+        Rc::new(move |submission: Submission| {
+            let question = question.clone();
+            let ask_state = ask_state.clone();
+            let ask_attempt = ask_attempt.clone();
+            let partial_answer = partial_answer.clone();
+            let retry_question = retry_question.clone();
+            let gateways = (*gateways).clone();
+            let cached_answers = cached_answers.clone();
+            let bypass_cache = *bypass_cache;
+            let outbox = outbox.clone();
+            let cancellation = CancellationToken::new();
+            *ask_cancellation.borrow_mut() = Some(cancellation.clone());
+            let conversation = conversation.clone();
+            let answer_in_dialog = answer_in_dialog.clone();
+            let history = history.clone();
+            let profile = profiles.active().clone();
+            let auth = (*auth).clone();
+            let auth_prompt = auth_prompt.clone();
+            let question_policy = question_policy.clone();
+            let options = parse_ask_options(&generation_inputs, &system_prompt_text);
+            reopened_question.set(None);
+            spawn_local(async move {
+                ask_state.set(LoadState::Loading);
+                ask_attempt.set(None);
+                retry_question.set(None);
+
+                let options = match options {
+                    Ok(options) => options,
+                    Err(error) => {
+                        ask_state.set(LoadState::Error(error));
+                        return;
+                    }
+                };
+
+                let client = match profile_gateways(&profile, request_timeout_ms, &auth) {
+                    Ok(client) => client,
+                    Err(error) => {
+                        ask_state.set(LoadState::Error(error));
+                        return;
+                    }
+                };
+
+                let client = ask_gateway(
+                    client,
+                    &profile,
+                    retry_policy,
+                    Some(attempt_observer(&ask_attempt)),
+                    &gateways,
+                );
+                partial_answer.set(None);
+                let (question_value, outcome, dialog) = match submission {
+                    Submission::Dialog(question_value) => {
+                        let usecase = ContinueConversationUseCase::new(client, BrowserClock)
+                            .with_policy(question_policy.clone())
+                            .with_generation(options.generation)
+                            .with_system_prompt(options.system_prompt)
+                            .with_cancellation(cancellation)
+                            .with_cache_bypass(bypass_cache);
+                        let mut dialog = (*conversation).clone();
+                        let on_progress = {
+                            let partial_answer = partial_answer.clone();
+                            move |text: &str| partial_answer.set(Some(text.to_string()))
+                        };
+                        let outcome = usecase
+                            .execute_streaming(&mut dialog, question_value.clone(), on_progress)
+                            .await;
+                        (question_value, outcome, Some(dialog))
+                    }
+                    Submission::Template(question_value) => {
+                        let usecase = AskQuestionUseCase::new(client)
+                            .with_policy(question_policy.clone())
+                            .with_generation(options.generation)
+                            .with_system_prompt(options.system_prompt)
+                            .with_cancellation(cancellation)
+                            .with_cache_bypass(bypass_cache);
+                        let outcome = usecase.execute(question_value.clone()).await;
+                        (question_value, outcome, None)
+                    }
+                };
+                partial_answer.set(None);
+                cached_answers.set(gateways.answer_cache.len());
+                match outcome {
+                    Ok(result) => {
+                        record_history(&history, &question_policy, &question_value, &result);
+                        answer_in_dialog.set(dialog.is_some());
+                        if let Some(dialog) = dialog {
+                            conversation.set(dialog);
+                            question.set(String::new());
+                        }
+                        ask_state.set(LoadState::Ready(result));
+                    }
+                    Err(error) => {
+                        prompt_reauth(&auth_prompt, &error);
+                        match queue_if_unreachable(
+                            &outbox,
+                            &question_policy,
+                            &question_value,
+                            &error,
+                        ) {
+                            Some(message) => ask_state.set(LoadState::Error(message)),
+                            None => {
+                                let retry = dialog.is_some() && offers_retry(&error);
+                                retry_question.set(retry.then_some(question_value));
+                                ask_state.set(LoadState::Error(error_message(error)));
+                            }
+                        }
+                    }
+                }
+            });
+        })
+    };
+
+    let on_submit = {
+        let question = question.clone();
+        let submit_question = submit_question.clone();
+        Callback::from(move |_| submit_question(Submission::Dialog((*question).clone())))
+    };
+
+    let on_template_submit = {
+        let ask_state = ask_state.clone();
+        let question_policy = question_policy.clone();
+        let submit_question = submit_question.clone();
+        Callback::from(move |(template, values): TemplateSubmission| {
+            match template.render(&values, &question_policy) {
+                Ok(question) => {
+                    submit_question(Submission::Template(question.as_str().to_string()))
+                }
+                Err(error) => {
+                    ask_state.set(LoadState::Error(error_message(UseCaseError::Domain(error))))
+                }
+            }
+        })
+    };
+
+    let on_retry = {
+        let retry_question = retry_question.clone();
+        let submit_question = submit_question.clone();
+        Callback::from(move |_| {
+            if let Some(question_value) = (*retry_question).clone() {
+                submit_question(Submission::Dialog(question_value));
+            }
+        })
+    };
+
+    let on_bypass_cache_toggle = {
+        let bypass_cache = bypass_cache.clone();
+        Callback::from(move |event: Event| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            bypass_cache.set(input.checked());
+        })
+    };
+
+    let on_clear_cache = {
+        let gateways = gateways.clone();
+        let cached_answers = cached_answers.clone();
+        Callback::from(move |_| {
+            gateways.answer_cache.clear();
+            cached_answers.set(0);
+        })
+    };
+
+    let on_outbox_send = {
+        let replay_outbox = replay_outbox.clone();
+        Callback::from(move |id: OutboxId| replay_outbox(Some(id)))
+    };
+
+    let on_outbox_cancel = {
+        let outbox = outbox.clone();
+        Callback::from(move |id: OutboxId| {
+            let usecase = OutboxUseCase::new(LocalStorageOutboxRepository, BrowserClock);
+            match usecase.cancel(id) {
+                Ok(()) => outbox.set(OutboxViewState::load()),
+                Err(error) => outbox.set(OutboxViewState {
+                    error: Some(error_message(error)),
+                    ..OutboxViewState::load()
+                }),
+            }
+        })
+    };
+
+    let on_stop = {
+        let ask_cancellation = ask_cancellation.clone();
+        Callback::from(move |_| {
+            if let Some(cancellation) = ask_cancellation.borrow().as_ref() {
+                cancellation.cancel();
+            }
+        })
+    };
+
+    let on_history_reopen = {
+        let ask_state = ask_state.clone();
+        let answer_in_dialog = answer_in_dialog.clone();
+        let reopened_question = reopened_question.clone();
+        Callback::from(move |entry: HistoryEntry| {
+            answer_in_dialog.set(false);
+            reopened_question.set(Some(entry.question.as_str().to_string()));
+            ask_state.set(LoadState::Ready(entry.result));
+        })
+    };
+
+    let on_history_reask = {
+        let question = question.clone();
+        let submit_question = submit_question.clone();
+        Callback::from(move |entry: HistoryEntry| {
+            let text = entry.question.as_str().to_string();
+            question.set(text.clone());
+            submit_question(Submission::Dialog(text));
+        })
+    };
+
+    let on_history_delete = {
+        let history = history.clone();
+        Callback::from(move |id: HistoryId| {
+            match ManageHistoryUseCase::new(LocalStorageHistoryRepository).delete(id) {
+                Ok(()) => history.set(HistoryViewState::load()),
+                Err(error) => history.set(history.with_error(error)),
+            }
+        })
+    };
+
+    let on_temperature_input = generation_input(&generation_inputs, |inputs, value| {
+        inputs.temperature = value
+    });
+    let on_top_p_input = generation_input(&generation_inputs, |inputs, value| inputs.top_p = value);
+    let on_max_tokens_input = generation_input(&generation_inputs, |inputs, value| {
+        inputs.max_tokens = value
+    });
+    let on_model_input = generation_input(&generation_inputs, |inputs, value| inputs.model = value);
+
+    let on_token_budget_input = {
+        let token_budget = token_budget.clone();
+        let token_budget_input = token_budget_input.clone();
+        let token_budget_error = token_budget_error.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            let value = input.value();
+            token_budget_input.set(value.clone());
+            // Корректное значение сохраняется сразу, как и остальные настройки.
+            match TokenBudget::parse(&value) {
+                Ok(budget) => match AppConfig::save_token_budget(budget) {
+                    Ok(()) => {
+                        token_budget.set(budget);
+                        token_budget_error.set(None);
+                    }
+                    Err(error) => token_budget_error.set(Some(error)),
+                },
+                Err(error) => token_budget_error.set(Some(error.to_string())),
+            }
+        })
+    };
+
+    let on_system_prompt_input = {
+        let system_prompt_text = system_prompt_text.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlTextAreaElement = event.target_unchecked_into();
+            system_prompt_text.set(input.value());
+        })
+    };
+
+    let on_preset_name_input = {
+        let preset_name = preset_name.clone();
+        let preset_error = preset_error.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            preset_name.set(input.value());
+            preset_error.set(None);
+        })
+    };
+
+    let on_preset_save = {
+        let system_prompt_text = system_prompt_text.clone();
+        let prompt_presets = prompt_presets.clone();
+        let preset_name = preset_name.clone();
+        let preset_error = preset_error.clone();
+        Callback::from(move |_| {
+            let preset = SystemPrompt::try_new((*system_prompt_text).clone())
+                .and_then(|prompt| SystemPromptPreset::try_new((*preset_name).clone(), prompt));
+            let preset = match preset {
+                Ok(preset) => preset,
+                Err(error) => {
+                    preset_error.set(Some(error.to_string()));
+                    return;
+                }
+            };
+            let mut presets = (*prompt_presets).clone();
+            match presets.iter_mut().find(|item| item.name() == preset.name()) {
+                Some(existing) => *existing = preset,
+                None => presets.push(preset),
+            }
+            if let Err(error) = AppConfig::save_prompt_presets(&presets) {
+                preset_error.set(Some(error));
+                return;
+            }
+            prompt_presets.set(presets);
+            preset_name.set(String::new());
+            preset_error.set(None);
+        })
+    };
+
+    let on_preset_select = {
+        let system_prompt_text = system_prompt_text.clone();
+        Callback::from(move |preset: SystemPromptPreset| {
+            system_prompt_text.set(preset.prompt().as_str().to_string());
+        })
+    };
+
+    let on_preset_delete = {
+        let prompt_presets = prompt_presets.clone();
+        let preset_error = preset_error.clone();
+        Callback::from(move |name: String| {
+            let mut presets = (*prompt_presets).clone();
+            presets.retain(|preset| preset.name() != name);
+            if let Err(error) = AppConfig::save_prompt_presets(&presets) {
+                preset_error.set(Some(error));
+                return;
+            }
+            prompt_presets.set(presets);
+        })
+    };
+
+    let on_new_conversation = {
+        let conversation = conversation.clone();
+        let ask_state = ask_state.clone();
+        Callback::from(move |_| {
+            conversation.set(Conversation::new());
+            ask_state.set(LoadState::Idle);
+        })
+    };
+
+    let on_max_chars_input = question_limit_input(
+        &question_policy_state,
+        &question_policy_error,
+        |policy, value| policy.max_chars = value,
+    );
+    let on_max_lines_input = question_limit_input(
+        &question_policy_state,
+        &question_policy_error,
+        |policy, value| policy.max_lines = value,
+    );
+
+    let on_base_url_input = {
+        let api_base_url_input = api_base_url_input.clone();
+        let api_base_url_error = api_base_url_error.clone();
+        let api_base_url_notice = api_base_url_notice.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            api_base_url_input.set(input.value());
+            api_base_url_error.set(None);
+            api_base_url_notice.set(None);
+        })
+    };
+
+    // Провайдер учётных данных пересобирается: от адреса зависит
+    // OAuth‑эндпоинт GigaChat.
+    let on_base_url_save = {
+        let api_base_url_input = api_base_url_input.clone();
+        let profiles = profiles.clone();
+        let auth = auth.clone();
+        let api_base_url_error = api_base_url_error.clone();
+        let api_base_url_notice = api_base_url_notice.clone();
+        Callback::from(move |_| {
+            let value = (*api_base_url_input).clone();
+            match AppConfig::parse_base_url(&value) {
+                Ok(parsed) => {
+                    let next = profiles
+                        .update_active(profiles.active().clone().with_base_url(parsed.clone()));
+                    if let Err(error) = next.save() {
+                        api_base_url_error.set(Some(error));
+                        api_base_url_notice.set(None);
+                        return;
+                    }
+                    api_base_url_input.set(parsed.as_str().to_string());
+                    auth.set(AuthState::new(auth.settings.clone(), next.active()));
+                    profiles.set(next);
+                    api_base_url_error.set(None);
+                    api_base_url_notice.set(Some("Базовый URL сохранен".to_string()));
+                }
+                Err(error) => {
+                    api_base_url_error.set(Some(error.to_string()));
+                    api_base_url_notice.set(None);
+                }
+            }
+        })
+    };
+
+    // Тип сервера влияет и на учётные данные: ключ API для GigaChat —
+    // это ключ авторизации, по которому выдаётся токен.
+    let on_backend_kind = |kind: BackendKind| {
+        let profiles = profiles.clone();
+        let auth = auth.clone();
+        let api_base_url_error = api_base_url_error.clone();
+        let api_base_url_notice = api_base_url_notice.clone();
+        Callback::from(move |_: Event| {
+            let profile = profiles.active().clone().with_kind(kind);
+            let next = profiles.update_active(profile.clone());
+            if let Err(error) = next.save() {
+                api_base_url_error.set(Some(error));
+                return;
+            }
+            api_base_url_notice.set(None);
+            auth.set(AuthState::new(auth.settings.clone(), &profile));
+            profiles.set(next);
+        })
+    };
+
+    // Переключение профиля подставляет его адрес и учётные данные.
+    let switch_profiles: Rc<dyn Fn(ProfilesState, Option<String>)> = {
+        let profiles = profiles.clone();
+        let auth = auth.clone();
+        let auth_prompt = auth_prompt.clone();
+        let api_base_url_input = api_base_url_input.clone();
+        let api_base_url_error = api_base_url_error.clone();
+        let api_base_url_notice = api_base_url_notice.clone();
+        Rc::new(move |next: ProfilesState, notice: Option<String>| {
+            if let Err(error) = next.save() {
+                api_base_url_error.set(Some(error));
+                api_base_url_notice.set(None);
+                return;
+            }
+            api_base_url_input.set(next.active().base_url().as_str().to_string());
+            api_base_url_error.set(None);
+            api_base_url_notice.set(notice);
+            auth.set(AuthState::load(next.active()));
+            auth_prompt.set(None);
+            profiles.set(next);
+        })
+    };
+
+    let on_profile_select = |index: usize| {
+        let profiles = profiles.clone();
+        let switch_profiles = switch_profiles.clone();
+        Callback::from(move |_: Event| switch_profiles(profiles.select(index), None))
+    };
+
+    let on_profile_name_input = {
+        let new_profile_name = new_profile_name.clone();
+        let api_base_url_error = api_base_url_error.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            new_profile_name.set(input.value());
+            api_base_url_error.set(None);
+        })
+    };
+
+    // Новый профиль начинается с типа и адреса выбранного.
+    let on_profile_add = {
+        let profiles = profiles.clone();
+        let new_profile_name = new_profile_name.clone();
+        let api_base_url_error = api_base_url_error.clone();
+        let switch_profiles = switch_profiles.clone();
+        Callback::from(move |_| {
+            let active = profiles.active();
+            let added = BackendProfile::try_new(
+                (*new_profile_name).clone(),
+                active.kind(),
+                active.base_url().clone(),
+            )
+            .map_err(|error| error.to_string())
+            .and_then(|profile| profiles.add(profile));
+            match added {
+                Ok(next) => {
+                    new_profile_name.set(String::new());
+                    switch_profiles(next, Some("Профиль добавлен".to_string()));
+                }
+                Err(error) => api_base_url_error.set(Some(error)),
+            }
+        })
+    };
+
+    let on_profile_delete = {
+        let profiles = profiles.clone();
+        let switch_profiles = switch_profiles.clone();
+        Callback::from(move |_| {
+            if let Some(next) = profiles.remove_active() {
+                SessionStorageCredentials::for_profile(profiles.active().name()).clear();
+                switch_profiles(next, Some("Профиль удален".to_string()));
+            }
+        })
+    };
+
+    let on_auth_save = {
+        let profiles = profiles.clone();
+        let auth = auth.clone();
+        let auth_prompt = auth_prompt.clone();
+        let auth_error = auth_error.clone();
+        let run_health_check = run_health_check.clone();
+        Callback::from(move |settings: AuthSettings| {
+            let profile = profiles.active();
+            let storage = SessionStorageCredentials::for_profile(profile.name());
+            if let Err(error) = storage.save(&settings) {
+                auth_error.set(Some(error.to_string()));
+                return;
+            }
+            auth.set(AuthState::new(settings, profile));
+            auth_prompt.set(None);
+            auth_error.set(None);
+            run_health_check();
+        })
+    };
+
+    let on_auth_clear = {
+        let profiles = profiles.clone();
+        let auth = auth.clone();
+        let auth_error = auth_error.clone();
+        Callback::from(move |_| {
+            let profile = profiles.active();
+            SessionStorageCredentials::for_profile(profile.name()).clear();
+            auth.set(AuthState::new(AuthSettings::None, profile));
+            auth_error.set(None);
+        })
+    };
+
+    // Сессия выдана конкретным backend: при смене адреса подставляется
+    // сессия нового.
+    {
+        let session = session.clone();
+        use_effect_with(profiles.active().base_url().clone(), move |base_url| {
+            let current = SessionStorageSessionRepository::for_base_url(base_url)
+                .load()
+                .ok()
+                .flatten();
+            if *session != current {
+                session.set(current);
+            }
+        });
+    }
+
+    // Сессия продлевается заранее, даже если пользователь ничего не
+    // спрашивает. Если её уже продлил запрос, `active_session` лишь
+    // вернёт сохранённую.
+    {
+        let session = session.clone();
+        let deps = (profiles.active().base_url().clone(), (*session).clone());
+        use_effect_with(deps, move |(base_url, current)| {
+            let timer = current.as_ref().and_then(|current| {
+                let usecase = session_usecase(base_url.clone(), request_timeout_ms);
+                let delay = usecase.refresh_delay_ms(current)?;
+                let delay = u32::try_from(delay).unwrap_or(u32::MAX);
+                Some(Timeout::new(delay, move || {
+                    spawn_local(async move {
+                        match usecase.active_session().await {
+                            Ok(renewed) => session.set(renewed),
+                            Err(UseCaseError::Gateway(error)) if !error.requires_auth() => {}
+                            Err(_) => session.set(None),
+                        }
+                    });
+                }))
+            });
+            move || drop(timer)
+        });
+    }
+
+    let on_login = {
+        let profiles = profiles.clone();
+        let session = session.clone();
+        let login_open = login_open.clone();
+        let login_state = login_state.clone();
+        let auth_prompt = auth_prompt.clone();
+        let run_health_check = run_health_check.clone();
+        Callback::from(move |(username, password): (String, String)| {
+            let base_url = profiles.active().base_url().clone();
+            let session = session.clone();
+            let login_open = login_open.clone();
+            let login_state = login_state.clone();
+            let auth_prompt = auth_prompt.clone();
+            let run_health_check = run_health_check.clone();
+            spawn_local(async move {
+                login_state.set(LoadState::Loading);
+                let usecase = session_usecase(base_url, request_timeout_ms);
+                match usecase.login(&username, &password).await {
+                    Ok(current) => {
+                        session.set(Some(current));
+                        login_state.set(LoadState::Idle);
+                        login_open.set(false);
+                        auth_prompt.set(None);
+                        run_health_check();
+                    }
+                    Err(UseCaseError::Gateway(error)) if error.requires_auth() => {
+                        login_state.set(LoadState::Error("Неверный логин или пароль.".to_string()));
+                    }
+                    Err(error) => login_state.set(LoadState::Error(error_message(error))),
+                }
+            });
+        })
+    };
+
+    let on_login_open = {
+        let login_open = login_open.clone();
+        Callback::from(move |_| login_open.set(true))
+    };
+
+    let on_login_cancel = {
+        let login_open = login_open.clone();
+        let login_state = login_state.clone();
+        let auth_prompt = auth_prompt.clone();
+        Callback::from(move |_| {
+            login_open.set(false);
+            login_state.set(LoadState::Idle);
+            auth_prompt.set(None);
+        })
+    };
+
+    let on_logout = {
+        let profiles = profiles.clone();
+        let session = session.clone();
+        Callback::from(move |_| {
+            let session = session.clone();
+            let base_url = profiles.active().base_url().clone();
+            spawn_local(async move {
+                let _ = session_usecase(base_url, request_timeout_ms).logout().await;
+                session.set(None);
+            });
+        })
+    };
+
+    // Вход по логину есть только у backend курса.
+    let uses_session = profiles.active().kind() == BackendKind::Course;
+
+    // Экран входа нужен, когда backend отверг запрос, а ключ или токен
+    // не заданы: тогда учётные данные — это сессия пользователя.
+    let show_login =
+        uses_session && (*login_open || (auth_prompt.is_some() && !auth.settings.is_configured()));
+
+    let default_login_endpoint = profiles.active().base_url().join("/auth/login");
+
+    let on_base_url_reset = {
+        let api_base_url_input = api_base_url_input.clone();
+        let profiles = profiles.clone();
+        let auth = auth.clone();
+        let api_base_url_error = api_base_url_error.clone();
+        let api_base_url_notice = api_base_url_notice.clone();
+        Callback::from(move |_| {
+            let value = AppConfig::default_base_url();
+            api_base_url_input.set(value.clone());
+            match AppConfig::parse_base_url(&value) {
+                Ok(parsed) => {
+                    let next =
+                        profiles.update_active(profiles.active().clone().with_base_url(parsed));
+                    let _ = next.save();
+                    auth.set(AuthState::new(auth.settings.clone(), next.active()));
+                    profiles.set(next);
+                    api_base_url_error.set(None);
+                    api_base_url_notice.set(Some("URL сброшен к значению по умолчанию".to_string()));
+                }
+                Err(error) => {
+                    api_base_url_error.set(Some(error.to_string()));
+                    api_base_url_notice.set(None);
+                }
+            }
+        })
+    };
+
+    let on_health_refresh = {
+        let run_health_check = run_health_check.clone();
+        Callback::from(move |_| {
+            run_health_check();
+        })
+    };
+
+    let circuit_state = gateways.circuit_breaker(profiles.active()).state();
+
+    let question_metrics = question_policy.measure(&question);
+    let question_validation = question_policy.apply(question.as_str());
+    let question_is_valid = question_validation.is_ok();
+    let question_error = match question_validation {
+        Err(error) if !question.trim().is_empty() => Some(error.to_string()),
+        _ => None,
+    };
+    let token_estimate =
+        HeuristicTokenEstimator::default().estimate(&question, conversation.messages());
+    let token_excess = token_budget.excess(&token_estimate);
+    let generation_error = generation_inputs.parse().err();
+    let system_prompt_error = parse_system_prompt(&system_prompt_text)
+        .err()
+        .map(|error| error.to_string());
+    let system_prompt_chars = system_prompt_text.trim().chars().count();
+    let earlier_messages = earlier_messages(
+        &conversation,
+        *answer_in_dialog && matches!(*ask_state, LoadState::Ready(_)),
+    );
+
+    html! {
+        <div class="app">
+            <header class="app__header">
+                <div class="app__title">
+                    <p class="app__eyebrow">{"Учебный UI / Rust + Yew"}</p>
+                    <h1>{"GigaChat Webapp"}</h1>
+                </div>
+                <p class="app__subtitle">
+                    {"Клиент к API "}
+                    <span class="app__mono">{"edu-rust-gigachat-app-backend"}</span>
+                    {". Используйте два процесса на разных портах."}
+                </p>
+            </header>
+
+            {if show_login {
+                html! {
+                    <LoginScreen
+                        reason={(*auth_prompt).clone()}
+                        busy={login_state.is_loading()}
+                        error={match &*login_state {
+                            LoadState::Error(message) => Some(message.clone()),
+                            _ => None,
+                        }}
+                        on_login={on_login}
+                        on_cancel={on_login_cancel}
+                    />
+                }
+            } else {
+                html! {}
+            }}
+
+            <main class="app__main">
+                <section class="panel panel--ask" aria-live="polite">
+                    <div class="panel__header">
+                        <h2>{"Задать вопрос"}</h2>
+                        <p>{"Введите вопрос и отправьте его в API."}</p>
+                    </div>
+
+                    <label class="field" for="question">
+                        <span class="field__label">{"Ваш вопрос"}</span>
+                        <textarea
+                            id="question"
+                            class="field__input"
+                            rows="4"
+                            value={(*question).clone()}
+                            placeholder="Например: Что такое Rocket?"
+                            oninput={on_question_input}
+                        />
+                        <span class="field__hint">{
+                            format!(
+                                "Символов: {} из {} · строк: {} из {}. Пустые вопросы не отправляются.",
+                                question_metrics.chars,
+                                question_policy.max_chars,
+                                question_metrics.lines,
+                                question_policy.max_lines,
+                            )
+                        }</span>
+                        <span class="field__hint">{
+                            format!(
+                                "≈ {} токенов: вопрос {}, контекст диалога {}. Бюджет: {}.",
+                                token_estimate.total(),
+                                token_estimate.question,
+                                token_estimate.context,
+                                token_budget.get(),
+                            )
+                        }</span>
+                        {if let Some(excess) = token_excess {
+                            html! {
+                                <span class="field__hint field__hint--warning">{
+                                    format!("Запрос превышает бюджет примерно на {excess} токенов — сократите вопрос или начните новый диалог.")
+                                }</span>
+                            }
+                        } else {
+                            html! {}
+                        }}
+                        {if let Some(message) = &question_error {
+                            html! { <span class="field__hint field__hint--error">{message.clone()}</span> }
+                        } else {
+                            html! {}
+                        }}
+                    </label>
+
+                    <div class="actions actions--compact">
+                        <label class="field" for="question-max-chars">
+                            <span class="field__label">{"Лимит символов в вопросе"}</span>
+                            <input
+                                id="question-max-chars"
+                                class="field__input"
+                                type="text"
+                                inputmode="numeric"
+                                value={question_policy.max_chars.to_string()}
+                                oninput={on_max_chars_input}
+                            />
+                        </label>
+                        <label class="field" for="question-max-lines">
+                            <span class="field__label">{"Лимит строк в вопросе"}</span>
+                            <input
+                                id="question-max-lines"
+                                class="field__input"
+                                type="text"
+                                inputmode="numeric"
+                                value={question_policy.max_lines.to_string()}
+                                oninput={on_max_lines_input}
+                            />
+                        </label>
+                    </div>
+                    {if let Some(message) = &*question_policy_error {
+                        html! { <span class="field__hint field__hint--error">{message.clone()}</span> }
+                    } else {
+                        html! {}
+                    }}
+
+                    <TemplatePicker
+                        policy={question_policy.clone()}
+                        disabled={ask_state.is_loading()}
+                        on_submit={on_template_submit}
+                    />
+
+                    <details class="settings">
+                        <summary>{"Параметры генерации"}</summary>
+                        <div class="settings__grid">
+                            <label class="field" for="temperature">
+                                <span class="field__label">{"Температура (0–2)"}</span>
+                                <input
+                                    id="temperature"
+                                    class="field__input"
+                                    type="text"
+                                    inputmode="decimal"
+                                    placeholder="по умолчанию"
+                                    value={generation_inputs.temperature.clone()}
+                                    oninput={on_temperature_input}
+                                />
+                            </label>
+                            <label class="field" for="top-p">
+                                <span class="field__label">{"top_p (0–1)"}</span>
+                                <input
+                                    id="top-p"
+                                    class="field__input"
+                                    type="text"
+                                    inputmode="decimal"
+                                    placeholder="по умолчанию"
+                                    value={generation_inputs.top_p.clone()}
+                                    oninput={on_top_p_input}
+                                />
+                            </label>
+                            <label class="field" for="max-tokens">
+                                <span class="field__label">{"Максимум токенов"}</span>
+                                <input
+                                    id="max-tokens"
+                                    class="field__input"
+                                    type="text"
+                                    inputmode="numeric"
+                                    placeholder="по умолчанию"
+                                    value={generation_inputs.max_tokens.clone()}
+                                    oninput={on_max_tokens_input}
+                                />
+                            </label>
+                            <label class="field" for="model">
+                                <span class="field__label">{"Модель"}</span>
+                                <input
+                                    id="model"
+                                    class="field__input"
+                                    type="text"
+                                    placeholder="по умолчанию"
+                                    value={generation_inputs.model.clone()}
+                                    oninput={on_model_input}
+                                />
+                            </label>
+                            <label class="field" for="token-budget">
+                                <span class="field__label">{"Бюджет токенов на запрос"}</span>
+                                <input
+                                    id="token-budget"
+                                    class="field__input"
+                                    type="text"
+                                    inputmode="numeric"
+                                    value={(*token_budget_input).clone()}
+                                    oninput={on_token_budget_input}
+                                />
+                            </label>
+                        </div>
+                        <span class="field__hint">{"Пустые поля не отправляются — backend использует свои значения. Бюджет токенов только предупреждает о большом запросе и хранится в браузере."}</span>
+                        {if let Some(message) = &*token_budget_error {
+                            html! { <span class="field__hint field__hint--error">{message.clone()}</span> }
+                        } else {
+                            html! {}
+                        }}
+                        {if let Some(message) = &generation_error {
+                            html! { <span class="field__hint field__hint--error">{message.clone()}</span> }
+                        } else {
+                            html! {}
+                        }}
+                        <div class="actions actions--compact">
+                            <label class="checkbox">
+                                <input
+                                    type="checkbox"
+                                    checked={*bypass_cache}
+                                    onchange={on_bypass_cache_toggle}
+                                />
+                                {"Не брать ответ из кэша"}
+                            </label>
+                            <button
+                                class="button button--ghost button--small"
+                                disabled={*cached_answers == 0}
+                                onclick={on_clear_cache}
+                            >
+                                {format!("Очистить кэш ({})", *cached_answers)}
+                            </button>
+                        </div>
+                    </details>
+
+                    <details class="settings">
+                        <summary>{
+                            if system_prompt_text.trim().is_empty() {
+                                "Системный промпт"
+                            } else {
+                                "Системный промпт (задан)"
+                            }
+                        }</summary>
+                        <div class="presets">
+                            {for prompt_presets.iter().map(|preset| {
+                                view_prompt_preset(preset, &on_preset_select, &on_preset_delete)
+                            })}
+                        </div>
+                        <label class="field" for="system-prompt">
+                            <span class="field__label">{"Текст промпта"}</span>
+                            <textarea
+                                id="system-prompt"
+                                class="field__input"
+                                rows="3"
+                                value={(*system_prompt_text).clone()}
+                                placeholder="Пусто — используется промпт backend"
+                                oninput={on_system_prompt_input}
+                            />
+                            <span class="field__hint">{
+                                format!("Символов: {} из {}", system_prompt_chars, SystemPrompt::MAX_CHARS)
+                            }</span>
+                            {if let Some(message) = &system_prompt_error {
+                                html! { <span class="field__hint field__hint--error">{message.clone()}</span> }
+                            } else {
+                                html! {}
+                            }}
+                        </label>
+                        <div class="actions actions--compact">
+                            <input
+                                class="field__input"
+                                type="text"
+                                placeholder="Название пресета"
+                                value={(*preset_name).clone()}
+                                oninput={on_preset_name_input}
+                            />
+                            <button class="button button--ghost" onclick={on_preset_save}>
+                                {"Сохранить как пресет"}
+                            </button>
+                        </div>
+                        {if let Some(message) = &*preset_error {
+                            html! { <div class="message message--error">{message.clone()}</div> }
+                        } else {
+                            html! {}
+                        }}
+                    </details>
+
+                    <div class="actions">
+                        <button
+                            class="button"
+                            disabled={!question_is_valid
+                                || generation_error.is_some()
+                                || system_prompt_error.is_some()
+                                || ask_state.is_loading()}
+                            onclick={on_submit}
+                        >
+                            { if ask_state.is_loading() { "Отправка..." } else { "Отправить" } }
+                        </button>
+                        {if ask_state.is_loading() {
+                            html! {
+                                <button class="button button--ghost" onclick={on_stop}>
+                                    {"Остановить"}
+                                </button>
+                            }
+                        } else {
+                            html! {}
+                        }}
+                        <button
+                            class="button button--ghost"
+                            disabled={conversation.is_empty() || ask_state.is_loading()}
+                            onclick={on_new_conversation}
+                        >
+                            {"Новый диалог"}
+                        </button>
+                        <span class="actions__note">{
+                            format!("API: {} ({})", profiles.active().base_url().as_str(), profiles.active().name())
+                        }</span>
+                    </div>
+
+                    {if earlier_messages.is_empty() {
+                        html! {}
+                    } else {
+                        html! {
+                            <ol class="dialog">
+                                {for earlier_messages.iter().map(view_message)}
+                            </ol>
+                        }
+                    }}
+
+                    <div class="response">
+                        {match &*ask_state {
+                            LoadState::Idle => html! {
+                                <p class="muted">{"Ответ появится здесь."}</p>
+                            },
+                            LoadState::Loading => html! {
+                                <>
+                                    <div class="loading">
+                                        <span class="spinner" aria-hidden="true"></span>
+                                        <span>{
+                                            if partial_answer.is_some() {
+                                                "Ответ поступает..."
+                                            } else {
+                                                "Ожидание ответа от сервера..."
+                                            }
+                                        }</span>
+                                        {if let Some(attempt) = &*ask_attempt {
+                                            html! {
+                                                <span class="loading__attempt">
+                                                    {retry_label(attempt)}
+                                                </span>
+                                            }
+                                        } else {
+                                            html! {}
+                                        }}
+                                    </div>
+                                    {if let Some(text) = &*partial_answer {
+                                        html! {
+                                            <div class="answer answer--streaming">
+                                                <div class="answer__text">{render_markdown(text)}</div>
+                                            </div>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }}
+                                </>
+                            },
+                            LoadState::Ready(result) => html! {
+                                <div class="answer fade-in">
+                                    {if let Some(text) = &*reopened_question {
+                                        html! { <p class="muted">{format!("Из истории: {text}")}</p> }
+                                    } else {
+                                        html! {}
+                                    }}
+                                    <div class="answer__text">{render_markdown(&result.answer)}</div>
+                                    <div class="answer__meta">
+                                        <span>
+                                            {format!("Источник: {}", source_label(&result.source))}
+                                            {if result.cached {
+                                                html! { <span class="badge">{"из кэша"}</span> }
+                                            } else {
+                                                html! {}
+                                            }}
+                                        </span>
+                                        <span>{format!("Системный промпт применен: {}", yes_no(result.system_prompt_applied))}</span>
+                                        {match result.code_snippets().len() {
+                                            0 => html! {},
+                                            count => html! { <span>{format!("Фрагментов кода: {count}")}</span> },
+                                        }}
+                                    </div>
+                                </div>
+                            },
+                            LoadState::Error(error) => html! {
+                                <div class="message message--error fade-in">
+                                    <strong>{"Ошибка"}</strong>
+                                    <span>{error.clone()}</span>
+                                    {if retry_question.is_some() {
+                                        html! {
+                                            <button class="button button--ghost button--small" onclick={on_retry}>
+                                                {"Повторить"}
+                                            </button>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }}
+                                </div>
+                            },
+                        }}
+                    </div>
+                </section>
+
+                <OutboxPanel
+                    items={outbox.items.clone()}
+                    error={outbox.error.clone()}
+                    notice={outbox.notice.clone()}
+                    sending={outbox.sending}
+                    on_send={on_outbox_send}
+                    on_cancel={on_outbox_cancel}
+                />
+
+                <HistoryPanel
+                    entries={history.entries.clone()}
+                    error={history.error.clone()}
+                    disabled={ask_state.is_loading()}
+                    on_reopen={on_history_reopen}
+                    on_reask={on_history_reask}
+                    on_delete={on_history_delete}
+                />
+
+                <section class="panel panel--status" aria-live="polite">
+                    <div class="panel__header">
+                        <h2>{"Статус API"}</h2>
+                        <p>{"Проверка доступности и режима работы сервера."}</p>
+                    </div>
+
+                    <div class="actions actions--compact" role="radiogroup" aria-label="Профиль backend">
+                        {for profiles.all().iter().enumerate().map(|(index, profile)| html! {
+                            <label class="checkbox">
+                                <input
+                                    type="radio"
+                                    name="backend-profile"
+                                    checked={profiles.active_index() == index}
+                                    onchange={on_profile_select(index)}
+                                />
+                                {profile.name()}
+                            </label>
+                        })}
+                    </div>
+
+                    <div class="actions actions--compact">
+                        <input
+                            class="field__input"
+                            type="text"
+                            placeholder="Название нового профиля"
+                            aria-label="Название нового профиля"
+                            value={(*new_profile_name).clone()}
+                            oninput={on_profile_name_input}
+                        />
+                        <button class="button button--ghost" onclick={on_profile_add}>{"Добавить профиль"}</button>
+                        <button
+                            class="button button--ghost"
+                            disabled={profiles.all().len() < 2}
+                            onclick={on_profile_delete}
+                        >
+                            {"Удалить профиль"}
+                        </button>
+                    </div>
+
+                    <label class="field" for="api-base-url">
+                        <span class="field__label">{"Базовый URL API"}</span>
+                        <input
+                            id="api-base-url"
+                            class="field__input"
+                            type="text"
+                            value={(*api_base_url_input).clone()}
+                            oninput={on_base_url_input}
+                        />
+                        <span class="field__hint">{"Можно изменить без пересборки — значение хранится в браузере."}</span>
+                    </label>
+
+                    <div class="actions actions--compact" role="radiogroup" aria-label="Тип сервера">
+                        {for supported_kinds().into_iter().map(|kind| html! {
+                            <label class="checkbox">
+                                <input
+                                    type="radio"
+                                    name="backend-kind"
+                                    checked={profiles.active().kind() == kind}
+                                    onchange={on_backend_kind(kind)}
+                                />
+                                {kind.label()}
+                            </label>
+                        })}
+                    </div>
+
+                    <div class="actions actions--compact">
+                        <button class="button button--ghost" onclick={on_base_url_save}>{"Сохранить"}</button>
+                        <button class="button button--ghost" onclick={on_base_url_reset}>{"Сбросить"}</button>
+                        <button class="button" onclick={on_health_refresh}>{"Проверить"}</button>
+                    </div>
+
+                    {if let Some(message) = &*api_base_url_error {
+                        html! { <div class="message message--error">{message.clone()}</div> }
+                    } else {
+                        html! {}
+                    }}
+
+                    {if let Some(message) = &*api_base_url_notice {
+                        html! { <div class="message message--success">{message.clone()}</div> }
+                    } else {
+                        html! {}
+                    }}
+
+                    <AuthSettingsPanel
+                        settings={auth.settings.clone()}
+                        default_login_endpoint={default_login_endpoint}
+                        prompt={(*auth_prompt).clone()}
+                        error={(*auth_error).clone()}
+                        on_save={on_auth_save}
+                        on_clear={on_auth_clear}
+                    />
+
+                    {if auth.settings.is_configured() || !uses_session {
+                        html! {}
+                    } else if let Some(current) = &*session {
+                        html! {
+                            <div class="status__row">
+                                <span>{format!("Вы вошли как {}", current.username)}</span>
+                                <button class="button button--ghost button--small" onclick={on_logout}>
+                                    {"Выйти"}
+                                </button>
+                            </div>
+                        }
+                    } else {
+                        html! {
+                            <div class="status__row">
+                                <span class="muted">{"Вход не выполнен."}</span>
+                                <button class="button button--ghost button--small" onclick={on_login_open}>
+                                    {"Войти"}
+                                </button>
+                            </div>
+                        }
+                    }}
+
+                    <div class="status">
+                        {match &health_state.state {
+                            LoadState::Idle => html! {
+                                <p class="muted">{"Статус еще не запрошен."}</p>
+                            },
+                            LoadState::Loading => html! {
+                                <div class="loading">
+                                    <span class="spinner" aria-hidden="true"></span>
+                                    <span>{"Проверяем..."}</span>
+                                </div>
+                            },
+                            LoadState::Ready(status) => html! {
+                                <div class="status__content fade-in">
+                                    <div class="status__row">
+                                        <span class={status_class(&status.status)}>{status_label(&status.status)}</span>
+                                        <span>{match profiles.active().kind() {
+                                            BackendKind::Course | BackendKind::Mock => format!("Версия: {}", status.version),
+                                            BackendKind::OpenAiCompatible | BackendKind::GigaChatDirect => {
+                                                format!("Модель: {}", status.version)
+                                            }
+                                        }}</span>
+                                    </div>
+                                    <div class="status__row">
+                                        {match profiles.active().kind() {
+                                            BackendKind::Course => html! {
+                                                <span>{format!("Режим: {}", source_label(&status.answer_source()))}</span>
+                                            },
+                                            kind => html! { <span>{format!("Режим: {}", kind.label())}</span> },
+                                        }}
+                                    </div>
+                                </div>
+                            },
+                            LoadState::Error(error) => html! {
+                                <div class="message message--error fade-in">
+                                    <strong>{"Ошибка"}</strong>
+                                    <span>{error.clone()}</span>
+                                </div>
+                            },
+                        }}
+
+                        {if let Some(checked) = &health_state.last_checked {
+                            html! { <p class="muted">{format!("Последняя проверка: {}", checked)}</p> }
+                        } else {
+                            html! {}
+                        }}
+
+                        <div class="status__row">
+                            <span class={circuit_class(&circuit_state)}>{circuit_label(&circuit_state)}</span>
+                        </div>
+                    </div>
+                </section>
+            </main>
+        </div>
+    }
+}
+
+/// Ставит вопрос в очередь, если backend недоступен.
+///
+/// Возвращает сообщение для пользователя, если вопрос оказался в очереди.
+fn queue_if_unreachable(
+    outbox: &UseStateHandle<OutboxViewState>,
+    policy: &QuestionPolicy,
+    question: &str,
+    error: &UseCaseError,
+) -> Option<String> {
+    if !matches!(error, UseCaseError::Gateway(error) if error.is_unreachable()) {
+        return None;
+    }
+    let usecase =
+        OutboxUseCase::new(LocalStorageOutboxRepository, BrowserClock).with_policy(policy.clone());
+    match usecase.enqueue(question) {
+        Ok(_) => {
+            outbox.set(OutboxViewState::load());
+            Some(
+                "Нет связи с сервером. Вопрос сохранён в очереди и будет отправлен, когда связь восстановится."
+                    .to_string(),
+            )
+        }
+        Err(storage_error) => {
+            outbox.set(OutboxViewState {
+                error: Some(error_message(storage_error)),
+                ..OutboxViewState::load()
+            });
+            None
+        }
+    }
+}
+
+/// Просит пользователя заново указать учётные данные, если backend
+/// их отверг.
+fn prompt_reauth(auth_prompt: &UseStateHandle<Option<String>>, error: &UseCaseError) {
+    if matches!(error, UseCaseError::Gateway(error) if error.requires_auth()) {
+        auth_prompt.set(Some(
+            "Сервер не принял учётные данные. Укажите ключ, токен или войдите заново.".to_string(),
+        ));
+    }
+}
+
+/// Записывает обмен в историю и обновляет панель истории.
+///
+/// Ошибка записи не отменяет полученный ответ — она показывается в панели.
+fn record_history(
+    history: &UseStateHandle<HistoryViewState>,
+    policy: &QuestionPolicy,
+    question: &str,
+    result: &AskResult,
+) {
+    let usecase = RecordExchangeUseCase::new(LocalStorageHistoryRepository, BrowserClock)
+        .with_policy(policy.clone());
+    match usecase.execute(question, result) {
+        Ok(_) => history.set(HistoryViewState::load()),
+        Err(error) => history.set(history.with_error(error)),
+    }
+}
+
+/// Сообщения диалога, которые показываются над блоком ответа.
+///
+/// Если последний ответ уже показан в блоке ответа, последний обмен
+/// (вопрос и ответ) из списка исключается, чтобы не дублировать его.
+fn earlier_messages(conversation: &Conversation, latest_shown: bool) -> &[Message] {
+    let messages = conversation.messages();
+    if latest_shown && messages.len() >= 2 {
+        &messages[..messages.len() - 2]
+    } else {
+        messages
+    }
+}
+
+/// Отрисовка одного сообщения диалога.
+fn view_message(message: &Message) -> Html {
+    let (class, author) = match message.role {
+        MessageRole::User => ("dialog__message dialog__message--user", "Вы"),
+        MessageRole::Assistant => ("dialog__message dialog__message--assistant", "Ассистент"),
+        MessageRole::System => ("dialog__message dialog__message--system", "Система"),
+    };
+    html! {
+        <li class={class}>
+            <div class="dialog__meta">
+                <strong>{author}</strong>
+                <span>{time_label(message.timestamp)}</span>
+                {if let Some(source) = &message.source {
+                    html! { <span>{format!("Источник: {}", source_label(source))}</span> }
+                } else {
+                    html! {}
+                }}
+            </div>
+            {if message.role == MessageRole::Assistant {
+                html! { <div class="dialog__text">{render_markdown(&message.content)}</div> }
+            } else {
+                html! { <p class="dialog__text dialog__text--plain">{message.content.clone()}</p> }
+            }}
+        </li>
+    }
+}
+
+/// Разбирает параметры генерации и системный промпт из полей формы.
+fn parse_ask_options(
+    generation_inputs: &GenerationInputs,
+    system_prompt_text: &str,
+) -> Result<AskOptions, String> {
+    Ok(AskOptions {
+        generation: generation_inputs.parse()?,
+        system_prompt: parse_system_prompt(system_prompt_text).map_err(|e| e.to_string())?,
+    })
+}
+
+/// Разбирает текст системного промпта: пустой текст означает «без промпта».
+fn parse_system_prompt(text: &str) -> Result<Option<SystemPrompt>, DomainError> {
+    if text.trim().is_empty() {
+        Ok(None)
+    } else {
+        SystemPrompt::try_new(text).map(Some)
+    }
+}
+
+/// Отрисовка пресета системного промпта: выбор и удаление.
+fn view_prompt_preset(
+    preset: &SystemPromptPreset,
+    on_select: &Callback<SystemPromptPreset>,
+    on_delete: &Callback<String>,
+) -> Html {
+    let select = {
+        let preset = preset.clone();
+        on_select.reform(move |_: MouseEvent| preset.clone())
+    };
+    let delete = {
+        let name = preset.name().to_string();
+        on_delete.reform(move |_: MouseEvent| name.clone())
+    };
+    html! {
+        <span class="preset">
+            <button class="preset__name" title={preset.prompt().as_str().to_string()} onclick={select}>
+                {preset.name()}
+            </button>
+            <button class="preset__delete" aria-label="Удалить пресет" onclick={delete}>{"×"}</button>
+        </span>
+    }
+}
+
+/// Создаёт обработчик ввода для одного поля параметров генерации.
+fn generation_input(
+    state: &UseStateHandle<GenerationInputs>,
+    update: fn(&mut GenerationInputs, String),
+) -> Callback<InputEvent> {
+    let state = state.clone();
+    Callback::from(move |event: InputEvent| {
+        let input: HtmlInputElement = event.target_unchecked_into();
+        let mut inputs = (*state).clone();
+        update(&mut inputs, input.value());
+        state.set(inputs);
+    })
+}
+
+/// Обработчик поля лимита вопроса: корректное значение сразу сохраняется.
+fn question_limit_input(
+    policy: &UseStateHandle<QuestionPolicy>,
+    error: &UseStateHandle<Option<String>>,
+    update: fn(&mut QuestionPolicy, usize),
+) -> Callback<InputEvent> {
+    let policy = policy.clone();
+    let error = error.clone();
+    Callback::from(move |event: InputEvent| {
+        let input: HtmlInputElement = event.target_unchecked_into();
+        let value = match input.value().trim().parse::<usize>() {
+            Ok(value) if value > 0 => value,
+            _ => {
+                error.set(Some(
+                    "Лимит вопроса должен быть целым числом больше нуля".to_string(),
+                ));
+                return;
+            }
+        };
+        let mut next = (*policy).clone();
+        update(&mut next, value);
+        match AppConfig::save_question_policy(&next) {
+            Ok(()) => {
+                policy.set(next);
+                error.set(None);
+            }
+            Err(message) => error.set(Some(message)),
+        }
+    })
+}
+
+/// Разбирает необязательное поле формы: пустая строка означает `None`.
+fn optional_field<T>(
+    raw: &str,
+    parse: impl FnOnce(&str) -> Result<T, String>,
+) -> Result<Option<T>, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        Ok(None)
+    } else {
+        parse(raw).map(Some)
+    }
+}
+
+/// Разбирает десятичное число; допускает запятую как разделитель.
+fn parse_decimal(raw: &str, label: &str) -> Result<f64, String> {
+    raw.replace(',', ".")
+        .parse::<f64>()
+        .map_err(|_| format!("{}: ожидается число", label))
+}
+
+/// Форматирование ошибок use‑case в строку для UI.
+///
+/// Сообщение об ошибке backend выбирается по её коду и HTTP‑статусу,
+/// а не повторяет текст ответа сервера.
+fn error_message(error: UseCaseError) -> String {
+    match error {
+        UseCaseError::Gateway(GatewayError::Api(error)) => api_error_message(&error),
+        UseCaseError::Gateway(GatewayError::Unauthorized(_)) => {
+            "Сервер требует авторизацию. Войдите или укажите учётные данные в разделе «Доступ к API».".to_string()
+        }
+        UseCaseError::NotAuthenticated => "Сессия истекла. Войдите снова.".to_string(),
+        UseCaseError::Gateway(GatewayError::Forbidden(error)) => format!(
+            "Доступ запрещён: {}. Проверьте учётные данные в разделе «Доступ к API».",
+            error.message
+        ),
+        UseCaseError::Gateway(GatewayError::Cancelled) => "Запрос остановлен.".to_string(),
+        UseCaseError::Gateway(GatewayError::Throttled(retry_after_ms)) => format!(
+            "Запрос не отправлен: слишком частые запросы. Повторите через {:.1} с.",
+            retry_after_ms as f64 / 1000.0
+        ),
+        UseCaseError::Gateway(GatewayError::CircuitOpen(remaining_ms)) => format!(
+            "Сервер недавно не отвечал, запросы временно не отправляются. Следующая проверка через {:.0} с.",
+            (remaining_ms as f64 / 1000.0).ceil()
+        ),
+        other => other.to_string(),
+    }
+}
+
+/// Понятное сообщение об ошибке backend.
+fn api_error_message(error: &ApiError) -> String {
+    match (&error.code, error.status) {
+        (Some(ApiErrorCode::EmptyQuestion), _) => {
+            "Сервер получил пустой вопрос. Введите текст вопроса и отправьте снова.".to_string()
+        }
+        (Some(ApiErrorCode::RateLimited), _) | (_, Some(429)) => {
+            "Слишком много запросов. Подождите немного и повторите.".to_string()
+        }
+        (Some(ApiErrorCode::InvalidRequest), _) | (_, Some(400 | 422)) => format!(
+            "Сервер отклонил запрос: {}. Проверьте параметры генерации и системный промпт.",
+            error.message
+        ),
+        (_, Some(status @ 502..=504)) => {
+            format!("Сервер временно недоступен (HTTP {status}). Повторите запрос позже.")
+        }
+        (Some(ApiErrorCode::Internal), _) | (_, Some(500..=599)) => {
+            format!("Ошибка на сервере: {}.", error.message)
+        }
+        _ => format!("Ошибка API: {error}"),
+    }
+}
+
+/// Стоит ли предложить пользователю повторить запрос.
+fn offers_retry(error: &UseCaseError) -> bool {
+    match error {
+        UseCaseError::Gateway(GatewayError::Api(error)) => {
+            error.retryable() || error.is_server_error()
+        }
+        UseCaseError::Gateway(GatewayError::Throttled(_) | GatewayError::CircuitOpen(_)) => true,
+        UseCaseError::Gateway(error) => error.retryable(),
+        _ => false,
+    }
+}
+
+/// Клиент сервера с повтором запросов при временных ошибках.
+fn with_retries<G>(client: G, policy: RetryPolicy) -> RetryingGateway<G, BrowserSleeper> {
+    RetryingGateway::new(client, BrowserSleeper)
+        .with_policy(policy)
+        .with_random(js_sys::Math::random)
+}
+
+/// Шлюз для вопросов. Снаружи внутрь: кэш ответов, склейка одинаковых
+/// одновременных запросов, автомат защиты, лимит частоты и повторы поверх
+/// HTTP‑клиента. Ответ из кэша, ожидание чужого запроса и отказ
+/// разомкнутого автомата не расходуют лимит; серия повторов считается
+/// автоматом за один сбой.
+fn ask_gateway(
+    client: GatewayPair,
+    profile: &BackendProfile,
+    retry_policy: RetryPolicy,
+    observer: Option<RetryObserver>,
+    gateways: &SharedGateways,
+) -> impl ChatGateway + StreamingChatGateway {
+    let probe = client.health;
+    let mut client = with_retries(client.chat, retry_policy);
+    if let Some(observer) = observer {
+        client = client.with_observer(observer);
+    }
+    let client = RateLimitedGateway::new(client, gateways.rate_limiter.clone(), BrowserClock);
+    let client = CircuitBreakerGateway::new(
+        client,
+        probe,
+        gateways.circuit_breaker(profile),
+        BrowserClock,
+    );
+    let client =
+        DeduplicatingGateway::new(client, gateways.in_flight.clone()).with_scope(profile.name());
+    CachingChatGateway::new(client, gateways.answer_cache.clone(), BrowserClock)
+        .with_scope(profile.name())
+}
+
+/// Наблюдатель, показывающий номер очередной попытки в состоянии загрузки.
+fn attempt_observer(attempt: &UseStateHandle<Option<RetryAttempt>>) -> RetryObserver {
+    let attempt = attempt.clone();
+    Rc::new(move |next: &RetryAttempt| attempt.set(Some(next.clone())))
+}
+
+/// Подпись повтора: «Попытка 2 из 3 …» с причиной предыдущей неудачи.
+fn retry_label(attempt: &RetryAttempt) -> String {
+    format!(
+        "Попытка {} из {} (через {:.1} с): {}",
+        attempt.attempt,
+        attempt.max_attempts,
+        attempt.delay_ms as f64 / 1000.0,
+        attempt.error
+    )
+}
+
+/// Преобразование булевого значения в русскую метку для интерфейса.
+fn yes_no(value: bool) -> &'static str {
+    if value { "да" } else { "нет" }
+}
+
+/// Текстовая метка источника ответа (или режима сервера).
+fn source_label(source: &AnswerSource) -> &str {
+    match source {
+        AnswerSource::GigaChat => "GigaChat",
+        AnswerSource::Mock => "mock",
+        AnswerSource::Unknown(raw) => raw,
+    }
+}
+
+/// Подпись состояния автомата защиты для панели «Статус API».
+fn circuit_label(state: &CircuitState) -> String {
+    match state {
+        CircuitState::Closed { failures: 0 } => "Запросы: отправляются".to_string(),
+        CircuitState::Closed { failures } => {
+            format!("Запросы: отправляются (сбоев подряд: {failures})")
+        }
+        CircuitState::Open { .. } => {
+            let now = Timestamp::from_millis(Date::now() as u64);
+            format!(
+                "Запросы: приостановлены, проверка сервера через {:.0} с",
+                (state.remaining_ms(now) as f64 / 1000.0).ceil()
+            )
+        }
+        CircuitState::HalfOpen => "Запросы: проверяем, ожил ли сервер".to_string(),
+    }
+}
+
+/// CSS‑класс для метки автомата защиты.
+fn circuit_class(state: &CircuitState) -> &'static str {
+    match state {
+        CircuitState::Closed { failures: 0 } => "pill pill--success",
+        CircuitState::Closed { .. } | CircuitState::HalfOpen => "pill pill--warning",
+        CircuitState::Open { .. } => "pill pill--danger",
+    }
+}
+
+/// Текстовая метка состояния API.
+fn status_label(status: &ServiceState) -> &str {
+    match status {
+        ServiceState::Ok => "ok",
+        ServiceState::Degraded => "degraded",
+        ServiceState::Down => "down",
+        ServiceState::Unknown(raw) => raw,
+    }
+}
+
+/// CSS‑класс для статуса API.
+///
+/// "ok" — зелёная метка, "down" — красная, остальное — предупреждение.
+fn status_class(status: &ServiceState) -> &'static str {
+    match status {
+        ServiceState::Ok => "pill pill--success",
+        ServiceState::Down => "pill pill--danger",
+        ServiceState::Degraded | ServiceState::Unknown(_) => "pill pill--warning",
+    }
+}
+
+/// Человекочитаемая метка текущего времени для UI.
+fn now_label() -> String {
+    Date::new_0().to_string().into()
+}
+
+/// Человекочитаемая метка времени сообщения (только часы и минуты).
+fn time_label(timestamp: Timestamp) -> String {
+    let date = Date::new(&(timestamp.as_millis() as f64).into());
+    format!("{:02}:{:02}", date.get_hours(), date.get_minutes())
+}
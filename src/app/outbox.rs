@@ -0,0 +1,117 @@
+//! Панель очереди неотправленных вопросов.
+//!
+//! Вопросы, заданные без связи с backend, ждут здесь отправки. Очередь
+//! отправляется сама, когда связь восстанавливается; панель позволяет
+//! отправить вопрос сразу или убрать его из очереди. Состояние очереди
+//! хранит родитель.
+use yew::prelude::*;
+
+use super::history::date_time_label;
+use crate::domain::{OutboxId, PendingQuestion};
+
+/// Свойства компонента `OutboxPanel`.
+#[derive(Properties, PartialEq)]
+pub struct OutboxPanelProps {
+    /// Записи в порядке очереди.
+    pub items: Vec<PendingQuestion>,
+    /// Ошибка хранилища или последней отправки.
+    pub error: Option<String>,
+    /// Итог последней отправки очереди.
+    pub notice: Option<String>,
+    /// Идёт отправка очереди.
+    pub sending: bool,
+    /// Отправить вопрос сейчас.
+    pub on_send: Callback<OutboxId>,
+    /// Убрать вопрос из очереди.
+    pub on_cancel: Callback<OutboxId>,
+}
+
+/// Панель с вопросами, ожидающими отправки. Пустая очередь без сообщений
+/// не показывается.
+#[function_component(OutboxPanel)]
+pub fn outbox_panel(props: &OutboxPanelProps) -> Html {
+    if props.items.is_empty() && props.error.is_none() && props.notice.is_none() {
+        return html! {};
+    }
+
+    html! {
+        <section class="panel panel--outbox" aria-live="polite">
+            <div class="panel__header">
+                <h2>{"Очередь отправки"}</h2>
+                <p>{
+                    if props.sending {
+                        "Отправляем вопросы из очереди..."
+                    } else {
+                        "Вопросы, заданные без связи с сервером, отправятся, когда связь вернётся."
+                    }
+                }</p>
+            </div>
+
+            {if let Some(message) = &props.error {
+                html! { <div class="message message--error">{message.clone()}</div> }
+            } else {
+                html! {}
+            }}
+            {if let Some(message) = &props.notice {
+                html! { <div class="message message--success">{message.clone()}</div> }
+            } else {
+                html! {}
+            }}
+
+            {if props.items.is_empty() {
+                html! {}
+            } else {
+                html! {
+                    <ul class="history">
+                        {for props.items.iter().map(|item| view_item(item, props))}
+                    </ul>
+                }
+            }}
+        </section>
+    }
+}
+
+/// Один вопрос очереди с действиями.
+fn view_item(item: &PendingQuestion, props: &OutboxPanelProps) -> Html {
+    let send = {
+        let id = item.id;
+        props.on_send.reform(move |_: MouseEvent| id)
+    };
+    let cancel = {
+        let id = item.id;
+        props.on_cancel.reform(move |_: MouseEvent| id)
+    };
+    html! {
+        <li class="history__item">
+            <p class="history__question">{item.question.as_str().to_string()}</p>
+            <div class="history__meta">
+                <span>{format!("В очереди с {}", date_time_label(item.queued_at))}</span>
+                {match item.attempts {
+                    0 => html! { <span>{"Ожидает отправки"}</span> },
+                    attempts => html! { <span>{format!("Попыток отправки: {attempts}")}</span> },
+                }}
+                {if let Some(error) = &item.last_error {
+                    html! { <span>{format!("Последняя ошибка: {error}")}</span> }
+                } else {
+                    html! {}
+                }}
+            </div>
+            <div class="actions actions--compact">
+                <button
+                    class="button button--ghost button--small"
+                    disabled={props.sending}
+                    onclick={send}
+                >
+                    {"Отправить сейчас"}
+                </button>
+                <button
+                    class="button button--ghost button--small"
+                    disabled={props.sending}
+                    onclick={cancel}
+                >
+                    {"Отменить"}
+                </button>
+            </div>
+        </li>
+    }
+}
@@ -0,0 +1,252 @@
+//! Выбор шаблона вопроса и форма для его переменных.
+//!
+//! Компонент хранит список шаблонов (через `AppConfig`), показывает поле
+//! для каждой переменной выбранного шаблона и предпросмотр итогового вопроса.
+//! Отправку выполняет родитель: компонент лишь передаёт шаблон и значения.
+use std::collections::BTreeMap;
+
+use web_sys::{HtmlInputElement, HtmlTextAreaElement, InputEvent};
+use yew::prelude::*;
+
+use crate::config::AppConfig;
+use crate::domain::{PromptTemplate, QuestionPolicy};
+
+/// Выбранный шаблон и значения его переменных.
+pub type TemplateSubmission = (PromptTemplate, BTreeMap<String, String>);
+
+/// Свойства компонента `TemplatePicker`.
+#[derive(Properties, PartialEq)]
+pub struct TemplatePickerProps {
+    /// Политика вопросов: та же, что и для обычного поля ввода.
+    pub policy: QuestionPolicy,
+    /// Блокирует отправку (например, пока идёт другой запрос).
+    pub disabled: bool,
+    /// Вызывается с выбранным шаблоном и значениями переменных.
+    pub on_submit: Callback<TemplateSubmission>,
+}
+
+/// Панель шаблонов вопросов.
+#[function_component(TemplatePicker)]
+pub fn template_picker(props: &TemplatePickerProps) -> Html {
+    let templates = use_state(AppConfig::load_prompt_templates);
+    let selected = use_state(|| Option::<PromptTemplate>::None);
+    let values = use_state(BTreeMap::<String, String>::new);
+    let new_name = use_state(String::new);
+    let new_body = use_state(String::new);
+    let edit_error = use_state(|| Option::<String>::None);
+
+    let on_select = {
+        let selected = selected.clone();
+        let values = values.clone();
+        Callback::from(move |template: PromptTemplate| {
+            let empty = template
+                .variables()
+                .into_iter()
+                .map(|name| (name.to_string(), String::new()))
+                .collect();
+            values.set(empty);
+            selected.set(Some(template));
+        })
+    };
+
+    let on_delete = {
+        let templates = templates.clone();
+        let selected = selected.clone();
+        let edit_error = edit_error.clone();
+        Callback::from(move |name: String| {
+            let mut list = (*templates).clone();
+            list.retain(|template| template.name() != name);
+            if let Err(error) = AppConfig::save_prompt_templates(&list) {
+                edit_error.set(Some(error));
+                return;
+            }
+            if selected
+                .as_ref()
+                .is_some_and(|template| template.name() == name)
+            {
+                selected.set(None);
+            }
+            templates.set(list);
+        })
+    };
+
+    let on_value_input = {
+        let values = values.clone();
+        Callback::from(move |(name, value): (String, String)| {
+            let mut map = (*values).clone();
+            map.insert(name, value);
+            values.set(map);
+        })
+    };
+
+    let on_new_name_input = {
+        let new_name = new_name.clone();
+        let edit_error = edit_error.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            new_name.set(input.value());
+            edit_error.set(None);
+        })
+    };
+
+    let on_new_body_input = {
+        let new_body = new_body.clone();
+        let edit_error = edit_error.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlTextAreaElement = event.target_unchecked_into();
+            new_body.set(input.value());
+            edit_error.set(None);
+        })
+    };
+
+    let on_save = {
+        let templates = templates.clone();
+        let new_name = new_name.clone();
+        let new_body = new_body.clone();
+        let edit_error = edit_error.clone();
+        Callback::from(move |_| {
+            let template = match PromptTemplate::try_new((*new_name).clone(), (*new_body).clone()) {
+                Ok(template) => template,
+                Err(error) => {
+                    edit_error.set(Some(error.to_string()));
+                    return;
+                }
+            };
+            let mut list = (*templates).clone();
+            match list.iter_mut().find(|item| item.name() == template.name()) {
+                Some(existing) => *existing = template,
+                None => list.push(template),
+            }
+            if let Err(error) = AppConfig::save_prompt_templates(&list) {
+                edit_error.set(Some(error));
+                return;
+            }
+            templates.set(list);
+            new_name.set(String::new());
+            new_body.set(String::new());
+            edit_error.set(None);
+        })
+    };
+
+    let preview = selected
+        .as_ref()
+        .map(|template| template.render(&values, &props.policy));
+
+    let on_submit = {
+        let selected = selected.clone();
+        let values = values.clone();
+        let on_submit = props.on_submit.clone();
+        Callback::from(move |_| {
+            if let Some(template) = &*selected {
+                on_submit.emit((template.clone(), (*values).clone()));
+            }
+        })
+    };
+
+    html! {
+        <details class="settings">
+            <summary>{"Шаблоны вопросов"}</summary>
+            <div class="presets">
+                {for templates.iter().map(|template| view_template(template, &on_select, &on_delete))}
+            </div>
+
+            {if let Some(template) = &*selected {
+                html! {
+                    <div class="template-form">
+                        <p class="muted">{format!("Шаблон: {}", template.body())}</p>
+                        {for template.variables().into_iter().map(|name| {
+                            let value = values.get(name).cloned().unwrap_or_default();
+                            view_variable_input(name, value, &on_value_input)
+                        })}
+                        {match &preview {
+                            Some(Ok(question)) => html! {
+                                <p class="template-form__preview">{question.as_str().to_string()}</p>
+                            },
+                            Some(Err(error)) => html! {
+                                <span class="field__hint field__hint--error">{error.to_string()}</span>
+                            },
+                            None => html! {},
+                        }}
+                        <div class="actions actions--compact">
+                            <button
+                                class="button"
+                                disabled={props.disabled || !matches!(preview, Some(Ok(_)))}
+                                onclick={on_submit}
+                            >
+                                {"Отправить по шаблону"}
+                            </button>
+                        </div>
+                    </div>
+                }
+            } else {
+                html! { <p class="muted">{"Выберите шаблон, чтобы заполнить его переменные."}</p> }
+            }}
+
+            <div class="template-form">
+                <input
+                    class="field__input"
+                    type="text"
+                    placeholder="Название нового шаблона"
+                    value={(*new_name).clone()}
+                    oninput={on_new_name_input}
+                />
+                <textarea
+                    class="field__input"
+                    rows="2"
+                    placeholder="Текст с переменными, например: Объясни ошибку: {{error}}"
+                    value={(*new_body).clone()}
+                    oninput={on_new_body_input}
+                />
+                <div class="actions actions--compact">
+                    <button class="button button--ghost" onclick={on_save}>{"Сохранить шаблон"}</button>
+                </div>
+                {if let Some(message) = &*edit_error {
+                    html! { <div class="message message--error">{message.clone()}</div> }
+                } else {
+                    html! {}
+                }}
+            </div>
+        </details>
+    }
+}
+
+/// Кнопка шаблона: выбор и удаление.
+fn view_template(
+    template: &PromptTemplate,
+    on_select: &Callback<PromptTemplate>,
+    on_delete: &Callback<String>,
+) -> Html {
+    let select = {
+        let template = template.clone();
+        on_select.reform(move |_: MouseEvent| template.clone())
+    };
+    let delete = {
+        let name = template.name().to_string();
+        on_delete.reform(move |_: MouseEvent| name.clone())
+    };
+    html! {
+        <span class="preset">
+            <button class="preset__name" title={template.body().to_string()} onclick={select}>
+                {template.name()}
+            </button>
+            <button class="preset__delete" aria-label="Удалить шаблон" onclick={delete}>{"×"}</button>
+        </span>
+    }
+}
+
+/// Поле ввода для одной переменной шаблона.
+fn view_variable_input(name: &str, value: String, on_input: &Callback<(String, String)>) -> Html {
+    let oninput = {
+        let name = name.to_string();
+        on_input.reform(move |event: InputEvent| {
+            let input: HtmlTextAreaElement = event.target_unchecked_into();
+            (name.clone(), input.value())
+        })
+    };
+    html! {
+        <label class="field">
+            <span class="field__label">{name.to_string()}</span>
+            <textarea class="field__input" rows="2" {value} {oninput} />
+        </label>
+    }
+}
@@ -0,0 +1,314 @@
+//! Учётные данные для запросов к backend.
+//!
+//! Реализации порта `AuthProvider`:
+//! - `NoAuth` — запросы уходят без учётных данных;
+//! - `StaticAuth` — статический ключ API или токен, введённый пользователем;
+//! - `RefreshingTokenAuth` — токен, полученный у эндпоинта входа через
+//!   `TokenSource`. Токен запоминается и запрашивается заново, когда срок
+//!   его действия подходит к концу или backend ответил 401;
+//! - `SessionAuth` — токен сессии пользователя из `AuthenticateUseCase`.
+//!
+//! Что именно выбрал пользователь, описывает `AuthSettings`. Эти настройки
+//! содержат секреты, поэтому в конфигурацию приложения не попадают.
+use std::cell::RefCell;
+use std::fmt;
+
+use async_trait::async_trait;
+
+use crate::application::ports::{
+    AccessToken, ApiError, AuthProvider, CancellationToken, Clock, Credentials, GatewayError,
+    SessionGateway, SessionRepository, TokenSource,
+};
+use crate::application::usecases::{AuthenticateUseCase, UseCaseError};
+
+/// Способ авторизации, выбранный пользователем.
+#[derive(Clone, PartialEq, Eq, Default)]
+pub enum AuthSettings {
+    /// Без учётных данных.
+    #[default]
+    None,
+    /// Статический ключ API.
+    ApiKey(String),
+    /// Готовый токен доступа.
+    Bearer(String),
+    /// Вход по логину и паролю: токен выдаёт эндпоинт `endpoint`.
+    Login {
+        endpoint: String,
+        username: String,
+        password: String,
+    },
+}
+
+impl AuthSettings {
+    /// Учётные данные заданы (выбран не `None`).
+    pub fn is_configured(&self) -> bool {
+        !matches!(self, AuthSettings::None)
+    }
+
+    /// Короткое описание способа для UI.
+    pub fn label(&self) -> &'static str {
+        match self {
+            AuthSettings::None => "без авторизации",
+            AuthSettings::ApiKey(_) => "ключ API",
+            AuthSettings::Bearer(_) => "токен доступа",
+            AuthSettings::Login { .. } => "вход по логину",
+        }
+    }
+}
+
+/// Секреты в отладочный вывод не попадают.
+impl fmt::Debug for AuthSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthSettings::None => f.write_str("None"),
+            AuthSettings::ApiKey(_) => f.write_str("ApiKey(***)"),
+            AuthSettings::Bearer(_) => f.write_str("Bearer(***)"),
+            AuthSettings::Login {
+                endpoint, username, ..
+            } => f
+                .debug_struct("Login")
+                .field("endpoint", endpoint)
+                .field("username", username)
+                .field("password", &"***")
+                .finish(),
+        }
+    }
+}
+
+/// Запросы без учётных данных.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoAuth;
+
+#[async_trait(?Send)]
+impl AuthProvider for NoAuth {
+    async fn credentials(
+        &self,
+        _cancellation: &CancellationToken,
+    ) -> Result<Option<Credentials>, GatewayError> {
+        Ok(None)
+    }
+}
+
+/// Одни и те же учётные данные для всех запросов.
+#[derive(Debug, Clone)]
+pub struct StaticAuth(Credentials);
+
+impl StaticAuth {
+    pub fn new(credentials: Credentials) -> Self {
+        Self(credentials)
+    }
+}
+
+#[async_trait(?Send)]
+impl AuthProvider for StaticAuth {
+    async fn credentials(
+        &self,
+        _cancellation: &CancellationToken,
+    ) -> Result<Option<Credentials>, GatewayError> {
+        Ok(Some(self.0.clone()))
+    }
+}
+
+/// Токен доступа, который обновляется через `TokenSource`.
+pub struct RefreshingTokenAuth<S, C> {
+    source: S,
+    clock: C,
+    token: RefCell<Option<AccessToken>>,
+    refresh_margin_ms: u64,
+}
+
+impl<S, C> RefreshingTokenAuth<S, C> {
+    /// За сколько миллисекунд до истечения токен обновляется заранее.
+    pub const DEFAULT_REFRESH_MARGIN_MS: u64 = 30_000;
+
+    pub fn new(source: S, clock: C) -> Self {
+        Self {
+            source,
+            clock,
+            token: RefCell::new(None),
+            refresh_margin_ms: Self::DEFAULT_REFRESH_MARGIN_MS,
+        }
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn with_refresh_margin(mut self, margin_ms: u64) -> Self {
+        self.refresh_margin_ms = margin_ms;
+        self
+    }
+}
+
+impl<S: TokenSource, C: Clock> RefreshingTokenAuth<S, C> {
+    /// Запомненный токен, если он ещё не истекает.
+    fn fresh_token(&self) -> Option<String> {
+        let now = self.clock.now().as_millis();
+        let token = self.token.borrow();
+        let token = token.as_ref()?;
+        let margin = self.refresh_margin_ms;
+        let refresh_at = token
+            .expires_at
+            .map(|expires_at| expires_at.as_millis().saturating_sub(margin));
+        match refresh_at {
+            Some(refresh_at) if refresh_at <= now => None,
+            _ => Some(token.token.clone()),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<S: TokenSource, C: Clock> AuthProvider for RefreshingTokenAuth<S, C> {
+    async fn credentials(
+        &self,
+        cancellation: &CancellationToken,
+    ) -> Result<Option<Credentials>, GatewayError> {
+        if let Some(token) = self.fresh_token() {
+            return Ok(Some(Credentials::Bearer(token)));
+        }
+        let token = self.source.fetch_token(cancellation).await?;
+        let credentials = Credentials::Bearer(token.token.clone());
+        *self.token.borrow_mut() = Some(token);
+        Ok(Some(credentials))
+    }
+
+    /// Забывает отвергнутый токен: следующий запрос получит новый.
+    async fn invalidate(&self) -> bool {
+        self.token.borrow_mut().take();
+        true
+    }
+}
+
+/// Токен сессии вошедшего пользователя.
+///
+/// Без сессии запросы уходят без учётных данных. Сессия, которую нельзя
+/// продлить, даёт `Unauthorized` — UI по нему показывает экран входа.
+pub struct SessionAuth<G: SessionGateway, R: SessionRepository, C: Clock> {
+    usecase: AuthenticateUseCase<G, R, C>,
+}
+
+impl<G: SessionGateway, R: SessionRepository, C: Clock> SessionAuth<G, R, C> {
+    pub fn new(usecase: AuthenticateUseCase<G, R, C>) -> Self {
+        Self { usecase }
+    }
+}
+
+#[async_trait(?Send)]
+impl<G: SessionGateway, R: SessionRepository, C: Clock> AuthProvider for SessionAuth<G, R, C> {
+    async fn credentials(
+        &self,
+        _cancellation: &CancellationToken,
+    ) -> Result<Option<Credentials>, GatewayError> {
+        match self.usecase.active_session().await {
+            Ok(session) => Ok(session.map(|session| Credentials::Bearer(session.access_token))),
+            Err(UseCaseError::Gateway(error)) => Err(error),
+            Err(UseCaseError::NotAuthenticated) => Err(GatewayError::Unauthorized(ApiError {
+                status: None,
+                code: None,
+                message: "сессия истекла".to_string(),
+                body: String::new(),
+            })),
+            // Недоступное хранилище не мешает запросу без учётных данных.
+            Err(UseCaseError::Domain(_) | UseCaseError::Storage(_)) => Ok(None),
+        }
+    }
+
+    /// Backend отверг токен сессии — пробуем продлить её.
+    async fn invalidate(&self) -> bool {
+        self.usecase.refresh().await.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::domain::Timestamp;
+
+    struct ManualClock(Rc<Cell<u64>>);
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Timestamp {
+            Timestamp::from_millis(self.0.get())
+        }
+    }
+
+    /// Эндпоинт входа, выдающий токены `token-1`, `token-2`, … на минуту.
+    struct CountingSource {
+        issued: Cell<u32>,
+        time: Rc<Cell<u64>>,
+    }
+
+    #[async_trait(?Send)]
+    impl TokenSource for CountingSource {
+        async fn fetch_token(
+            &self,
+            cancellation: &CancellationToken,
+        ) -> Result<AccessToken, GatewayError> {
+            if cancellation.is_cancelled() {
+                return Err(GatewayError::Cancelled);
+            }
+            self.issued.set(self.issued.get() + 1);
+            Ok(AccessToken {
+                token: format!("token-{}", self.issued.get()),
+                expires_at: Some(Timestamp::from_millis(self.time.get() + 60_000)),
+            })
+        }
+    }
+
+    #[test]
+    fn token_is_reused_until_it_nearly_expires() {
+        let time = Rc::new(Cell::new(1_000));
+        let auth = RefreshingTokenAuth::new(
+            CountingSource {
+                issued: Cell::new(0),
+                time: time.clone(),
+            },
+            ManualClock(time.clone()),
+        )
+        .with_refresh_margin(10_000);
+        let bearer = |token: &str| Some(Credentials::Bearer(token.to_string()));
+        let request = CancellationToken::new();
+        let credentials = || block_on(auth.credentials(&request)).unwrap();
+
+        assert_eq!(credentials(), bearer("token-1"));
+        time.set(40_000);
+        assert_eq!(credentials(), bearer("token-1"));
+
+        // До истечения меньше запаса — токен обновляется заранее.
+        time.set(52_000);
+        assert_eq!(credentials(), bearer("token-2"));
+
+        // Отвергнутый backend токен запрашивается заново.
+        assert!(block_on(auth.invalidate()));
+        assert_eq!(credentials(), bearer("token-3"));
+        assert_eq!(auth.source.issued.get(), 3);
+
+        // Отмена запроса прерывает и получение токена.
+        assert!(block_on(auth.invalidate()));
+        request.cancel();
+        assert_eq!(
+            block_on(auth.credentials(&request)),
+            Err(GatewayError::Cancelled)
+        );
+    }
+
+    #[test]
+    fn secrets_are_hidden_from_debug_output() {
+        let settings = AuthSettings::Login {
+            endpoint: "https://example.com/login".to_string(),
+            username: "student".to_string(),
+            password: "secret".to_string(),
+        };
+        let shown = format!(
+            "{settings:?} {:?}",
+            Credentials::ApiKey("secret".to_string())
+        );
+        assert!(!shown.contains("secret"));
+        assert!(shown.contains("student"));
+        assert!(!block_on(
+            StaticAuth::new(Credentials::ApiKey("k".to_string())).invalidate()
+        ));
+    }
+}
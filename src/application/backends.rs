@@ -0,0 +1,254 @@
+//! Профили backend и реестр шлюзов.
+//!
+//! Профиль (`BackendProfile`) — именованная запись «какой сервер и где»:
+//! тип сервера и базовый URL. Учётные данные к профилю прикладывает
+//! `AuthProvider`, который UI собирает по настройкам этого профиля.
+//!
+//! `GatewayRegistry` по типу сервера находит фабрику и собирает пару
+//! шлюзов: для вопросов и для проверки связи. Фабрики регистрирует
+//! корень композиции (UI), поэтому слой application не знает
+//! о конкретных адаптерах.
+use std::rc::Rc;
+
+use crate::application::ports::{AuthProvider, ChatBackend, HealthGateway};
+use crate::domain::{ApiBaseUrl, DomainError};
+
+/// Тип сервера.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BackendKind {
+    /// `edu-rust-gigachat-app-backend` (`POST /ask`, `GET /health`).
+    #[default]
+    Course,
+    /// Ответы‑заглушки без сети.
+    Mock,
+    /// OpenAI‑совместимый сервер (`POST /v1/chat/completions`).
+    OpenAiCompatible,
+    /// GigaChat API напрямую, с токеном по ключу авторизации.
+    GigaChatDirect,
+}
+
+impl BackendKind {
+    pub const ALL: [BackendKind; 4] = [
+        BackendKind::Course,
+        BackendKind::Mock,
+        BackendKind::OpenAiCompatible,
+        BackendKind::GigaChatDirect,
+    ];
+
+    /// Название для UI.
+    pub fn label(self) -> &'static str {
+        match self {
+            BackendKind::Course => "Backend курса",
+            BackendKind::Mock => "Mock",
+            BackendKind::OpenAiCompatible => "OpenAI‑совместимый сервер",
+            BackendKind::GigaChatDirect => "GigaChat API напрямую",
+        }
+    }
+
+    /// Строковое имя для хранения.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BackendKind::Course => "course",
+            BackendKind::Mock => "mock",
+            BackendKind::OpenAiCompatible => "openai_compatible",
+            BackendKind::GigaChatDirect => "gigachat_direct",
+        }
+    }
+
+    /// Тип по строковому имени.
+    pub fn parse(raw: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == raw)
+    }
+}
+
+/// Именованный профиль backend.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackendProfile {
+    name: String,
+    kind: BackendKind,
+    base_url: ApiBaseUrl,
+}
+
+impl BackendProfile {
+    /// Создаёт профиль; название не должно быть пустым.
+    pub fn try_new(
+        name: impl Into<String>,
+        kind: BackendKind,
+        base_url: ApiBaseUrl,
+    ) -> Result<Self, DomainError> {
+        let name = name.into().trim().to_string();
+        if name.is_empty() {
+            return Err(DomainError::EmptyProfileName);
+        }
+        Ok(Self {
+            name,
+            kind,
+            base_url,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> BackendKind {
+        self.kind
+    }
+
+    pub fn base_url(&self) -> &ApiBaseUrl {
+        &self.base_url
+    }
+
+    /// Тот же профиль с другим типом сервера.
+    pub fn with_kind(mut self, kind: BackendKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Тот же профиль с другим адресом.
+    pub fn with_base_url(mut self, base_url: ApiBaseUrl) -> Self {
+        self.base_url = base_url;
+        self
+    }
+}
+
+/// Шлюзы одного backend: вопросы и проверка связи.
+pub struct GatewayPair {
+    pub chat: Box<dyn ChatBackend>,
+    pub health: Box<dyn HealthGateway>,
+}
+
+/// Фабрика шлюзов одного типа сервера.
+pub type GatewayFactory = Rc<dyn Fn(&BackendProfile, Rc<dyn AuthProvider>) -> GatewayPair>;
+
+/// Фабрики шлюзов по типу сервера.
+#[derive(Clone, Default)]
+pub struct GatewayRegistry {
+    factories: Vec<(BackendKind, GatewayFactory)>,
+}
+
+impl GatewayRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Регистрирует фабрику; прежняя фабрика того же типа заменяется.
+    pub fn register(
+        mut self,
+        kind: BackendKind,
+        factory: impl Fn(&BackendProfile, Rc<dyn AuthProvider>) -> GatewayPair + 'static,
+    ) -> Self {
+        self.factories.retain(|(registered, _)| *registered != kind);
+        self.factories.push((kind, Rc::new(factory)));
+        self
+    }
+
+    /// Зарегистрированные типы в порядке регистрации.
+    pub fn kinds(&self) -> Vec<BackendKind> {
+        self.factories.iter().map(|(kind, _)| *kind).collect()
+    }
+
+    /// Шлюзы для профиля; `None`, если его тип не зарегистрирован.
+    pub fn build(
+        &self,
+        profile: &BackendProfile,
+        auth: Rc<dyn AuthProvider>,
+    ) -> Option<GatewayPair> {
+        self.factories
+            .iter()
+            .find(|(kind, _)| *kind == profile.kind)
+            .map(|(_, factory)| factory(profile, auth))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::application::ports::{
+        AnswerStream, AskRequest, ChatGateway, GatewayError, StreamingChatGateway,
+    };
+    use crate::application::NoAuth;
+    use crate::domain::{AnswerSource, AskResult, HealthStatus, Question, ServiceState};
+
+    /// Шлюз, отвечающий адресом своего профиля.
+    struct EchoGateway(String);
+
+    #[async_trait(?Send)]
+    impl ChatGateway for EchoGateway {
+        async fn ask(&self, _request: AskRequest) -> Result<AskResult, GatewayError> {
+            Ok(AskResult {
+                answer: self.0.clone(),
+                source: AnswerSource::Mock,
+                system_prompt_applied: false,
+                cached: false,
+            })
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl StreamingChatGateway for EchoGateway {
+        async fn ask_stream(&self, _request: AskRequest) -> Result<AnswerStream, GatewayError> {
+            Err(GatewayError::StreamingUnsupported)
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl HealthGateway for EchoGateway {
+        async fn health(&self) -> Result<HealthStatus, GatewayError> {
+            Ok(HealthStatus {
+                status: ServiceState::Ok,
+                version: self.0.clone(),
+                gigachat_enabled: false,
+            })
+        }
+    }
+
+    fn profile(kind: BackendKind, url: &str) -> BackendProfile {
+        BackendProfile::try_new("Тест", kind, ApiBaseUrl::try_new(url).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn registry_builds_gateways_for_registered_kind() {
+        let registry = GatewayRegistry::new().register(BackendKind::Mock, |profile, _auth| {
+            let url = profile.base_url().as_str().to_string();
+            GatewayPair {
+                chat: Box::new(EchoGateway(url.clone())),
+                health: Box::new(EchoGateway(url)),
+            }
+        });
+        assert_eq!(registry.kinds(), vec![BackendKind::Mock]);
+
+        let pair = registry
+            .build(
+                &profile(BackendKind::Mock, "http://localhost:9000"),
+                Rc::new(NoAuth),
+            )
+            .unwrap();
+        let question = Question::try_new("Привет").unwrap();
+        let answer = block_on(pair.chat.ask(AskRequest::new(question))).unwrap();
+        assert_eq!(answer.answer, "http://localhost:9000");
+        assert_eq!(
+            block_on(pair.health.health()).unwrap().version,
+            "http://localhost:9000"
+        );
+
+        let course = profile(BackendKind::Course, "http://localhost:8000");
+        assert!(registry.build(&course, Rc::new(NoAuth)).is_none());
+    }
+
+    #[test]
+    fn profile_requires_name_and_kind_round_trips() {
+        let url = ApiBaseUrl::try_new("http://localhost:8000").unwrap();
+        assert_eq!(
+            BackendProfile::try_new("  ", BackendKind::Course, url),
+            Err(DomainError::EmptyProfileName)
+        );
+        for kind in BackendKind::ALL {
+            assert_eq!(BackendKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(BackendKind::parse("unknown"), None);
+    }
+}
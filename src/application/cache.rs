@@ -0,0 +1,481 @@
+//! Кэш ответов на повторяющиеся вопросы.
+//!
+//! `CachingChatGateway` — декоратор над `ChatGateway`/`StreamingChatGateway`:
+//! если такой же запрос уже задавался и ответ ещё не устарел, ответ берётся
+//! из `AnswerCache`, а backend не вызывается. Ответ из кэша помечается
+//! флагом `AskResult::cached`.
+//!
+//! Ключ кэша строится по области (обычно это профиль backend: разные
+//! серверы отвечают по‑разному), вопросу в том виде, в каком его
+//! нормализовала `QuestionPolicy` (регистр важен: «RC» и «Rc» — разные
+//! вопросы), контексту диалога, параметрам генерации и системному промпту.
+//! Кэш ограничен по времени жизни записи и по числу записей; при
+//! переполнении вытесняется давно не использованная запись (LRU).
+//! Запрос с `bypass_cache` кэш не читает, но свежий ответ в него кладёт.
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+
+use crate::application::ports::{
+    AnswerCacheStore, AnswerChunk, AnswerStream, AskRequest, CachedAnswer, ChatGateway, Clock,
+    GatewayError, StreamingChatGateway,
+};
+use crate::domain::{AskResult, Timestamp};
+
+/// Ограничения кэша.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachePolicy {
+    /// Сколько живёт запись с момента сохранения.
+    pub ttl_ms: u64,
+    /// Максимальное число записей.
+    pub max_entries: usize,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self {
+            ttl_ms: 30 * 60 * 1_000,
+            max_entries: 100,
+        }
+    }
+}
+
+/// Ключ кэша для запроса в области `scope`.
+pub fn cache_key(scope: &str, request: &AskRequest) -> String {
+    let question = request.question.as_str();
+    let generation = &request.generation;
+    let mut key = format!(
+        "{scope}\u{1e}{question}\u{1e}t={:?};p={:?};n={:?};m={:?}\u{1e}{:?}",
+        generation.temperature.map(|value| value.value()),
+        generation.top_p.map(|value| value.value()),
+        generation.max_tokens.map(|value| value.value()),
+        generation.model.as_ref().map(|model| model.as_str()),
+        request.system_prompt.as_ref().map(|prompt| prompt.as_str()),
+    );
+    for message in &request.context {
+        key.push('\u{1e}');
+        key.push_str(message.role.as_str());
+        key.push(':');
+        key.push_str(&message.content);
+    }
+    key
+}
+
+struct CacheState {
+    policy: CachePolicy,
+    /// Записи от давно не использованных к недавним.
+    entries: Vec<CachedAnswer>,
+    store: Option<Rc<dyn AnswerCacheStore>>,
+}
+
+impl CacheState {
+    fn evict_expired(&mut self, now: Timestamp) {
+        let ttl_ms = self.policy.ttl_ms;
+        self.entries
+            .retain(|entry| now.as_millis().saturating_sub(entry.stored_at.as_millis()) < ttl_ms);
+    }
+
+    /// Сохраняет записи в хранилище. Кэш — лишь ускорение, поэтому
+    /// ошибка хранилища не мешает ответу и молча пропускается.
+    fn persist(&self) {
+        if let Some(store) = &self.store {
+            let _ = store.save(&self.entries);
+        }
+    }
+}
+
+/// Общий кэш ответов.
+///
+/// Клоны разделяют одни и те же записи, поэтому кэш можно создать один раз
+/// и передавать в декораторы, которые UI собирает на каждый запрос.
+#[derive(Clone)]
+pub struct AnswerCache {
+    state: Rc<RefCell<CacheState>>,
+}
+
+impl AnswerCache {
+    /// Кэш только в памяти.
+    pub fn new(policy: CachePolicy) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(CacheState {
+                policy,
+                entries: Vec::new(),
+                store: None,
+            })),
+        }
+    }
+
+    /// Кэш, сохраняемый в хранилище. Нечитаемое хранилище даёт пустой кэш.
+    pub fn persistent(policy: CachePolicy, store: impl AnswerCacheStore + 'static) -> Self {
+        let mut entries = store.load().unwrap_or_default();
+        let overflow = entries.len().saturating_sub(policy.max_entries);
+        entries.drain(..overflow);
+        Self {
+            state: Rc::new(RefCell::new(CacheState {
+                policy,
+                entries,
+                store: Some(Rc::new(store)),
+            })),
+        }
+    }
+
+    /// Ответ по ключу, если он есть и не устарел; запись становится недавней.
+    pub fn get(&self, key: &str, now: Timestamp) -> Option<AskResult> {
+        let mut state = self.state.borrow_mut();
+        let before = state.entries.len();
+        state.evict_expired(now);
+        let position = state.entries.iter().position(|entry| entry.key == key);
+        let result = position.map(|position| {
+            let entry = state.entries.remove(position);
+            let result = entry.result.clone();
+            state.entries.push(entry);
+            result
+        });
+        if result.is_some() || state.entries.len() != before {
+            state.persist();
+        }
+        result
+    }
+
+    /// Кладёт ответ в кэш, вытесняя давно не использованные записи.
+    pub fn put(&self, key: String, result: AskResult, now: Timestamp) {
+        let mut state = self.state.borrow_mut();
+        state.evict_expired(now);
+        state.entries.retain(|entry| entry.key != key);
+        state.entries.push(CachedAnswer {
+            key,
+            result: AskResult {
+                cached: false,
+                ..result
+            },
+            stored_at: now,
+        });
+        let overflow = state
+            .entries
+            .len()
+            .saturating_sub(state.policy.max_entries.max(1));
+        state.entries.drain(..overflow);
+        state.persist();
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.borrow().entries.len()
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        let mut state = self.state.borrow_mut();
+        state.entries.clear();
+        state.persist();
+    }
+}
+
+/// Декоратор шлюза, отвечающий из кэша на повторные запросы.
+pub struct CachingChatGateway<G, C> {
+    inner: G,
+    cache: AnswerCache,
+    clock: C,
+    scope: String,
+}
+
+impl<G, C: Clock> CachingChatGateway<G, C> {
+    pub fn new(inner: G, cache: AnswerCache, clock: C) -> Self {
+        Self {
+            inner,
+            cache,
+            clock,
+            scope: String::new(),
+        }
+    }
+
+    /// Задаёт область ключей: ответы из разных областей не смешиваются.
+    pub fn with_scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = scope.into();
+        self
+    }
+
+    fn lookup(&self, request: &AskRequest, key: &str) -> Option<AskResult> {
+        if request.bypass_cache {
+            return None;
+        }
+        self.cache
+            .get(key, self.clock.now())
+            .map(|result| AskResult {
+                cached: true,
+                ..result
+            })
+    }
+}
+
+#[async_trait(?Send)]
+impl<G, C> ChatGateway for CachingChatGateway<G, C>
+where
+    G: ChatGateway,
+    C: Clock,
+{
+    async fn ask(&self, request: AskRequest) -> Result<AskResult, GatewayError> {
+        let key = cache_key(&self.scope, &request);
+        if let Some(result) = self.lookup(&request, &key) {
+            return Ok(result);
+        }
+        let result = self.inner.ask(request).await?;
+        self.cache.put(key, result.clone(), self.clock.now());
+        Ok(result)
+    }
+}
+
+#[async_trait(?Send)]
+impl<G, C> StreamingChatGateway for CachingChatGateway<G, C>
+where
+    G: StreamingChatGateway,
+    C: Clock,
+{
+    /// Ответ из кэша отдаётся потоком из одного фрагмента; свежий поток
+    /// собирается по ходу чтения и кэшируется после `done`.
+    async fn ask_stream(&self, request: AskRequest) -> Result<AnswerStream, GatewayError> {
+        let key = cache_key(&self.scope, &request);
+        if let Some(result) = self.lookup(&request, &key) {
+            let chunks = vec![
+                Ok(AnswerChunk::Delta(result.answer)),
+                Ok(AnswerChunk::Done {
+                    source: result.source,
+                    system_prompt_applied: result.system_prompt_applied,
+                    cached: true,
+                }),
+            ];
+            return Ok(futures::stream::iter(chunks).boxed_local());
+        }
+
+        let stream = self.inner.ask_stream(request).await?;
+        let cache = self.cache.clone();
+        let asked_at = self.clock.now();
+        let mut answer = String::new();
+        Ok(stream
+            .inspect(move |chunk| match chunk {
+                Ok(AnswerChunk::Delta(text)) => answer.push_str(text),
+                Ok(AnswerChunk::Done {
+                    source,
+                    system_prompt_applied,
+                    ..
+                }) => cache.put(
+                    key.clone(),
+                    AskResult {
+                        answer: std::mem::take(&mut answer),
+                        source: source.clone(),
+                        system_prompt_applied: *system_prompt_applied,
+                        cached: false,
+                    },
+                    asked_at,
+                ),
+                Err(_) => {}
+            })
+            .boxed_local())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::application::ports::StorageError;
+    use crate::domain::{AnswerSource, GenerationSettings, QuestionPolicy, Temperature};
+
+    /// Шлюз, считающий вызовы и отвечающий номером вызова.
+    #[derive(Default)]
+    struct CountingGateway {
+        calls: Cell<u32>,
+    }
+
+    #[async_trait(?Send)]
+    impl ChatGateway for CountingGateway {
+        async fn ask(&self, _request: AskRequest) -> Result<AskResult, GatewayError> {
+            self.calls.set(self.calls.get() + 1);
+            Ok(AskResult {
+                answer: format!("ответ {}", self.calls.get()),
+                source: AnswerSource::Mock,
+                system_prompt_applied: false,
+                cached: false,
+            })
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl StreamingChatGateway for CountingGateway {
+        async fn ask_stream(&self, _request: AskRequest) -> Result<AnswerStream, GatewayError> {
+            self.calls.set(self.calls.get() + 1);
+            let chunks = vec![
+                Ok(AnswerChunk::Delta("по".to_string())),
+                Ok(AnswerChunk::Delta("ток".to_string())),
+                Ok(AnswerChunk::Done {
+                    source: AnswerSource::GigaChat,
+                    system_prompt_applied: false,
+                    cached: false,
+                }),
+            ];
+            Ok(futures::stream::iter(chunks).boxed_local())
+        }
+    }
+
+    struct ManualClock(Rc<Cell<u64>>);
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Timestamp {
+            Timestamp::from_millis(self.0.get())
+        }
+    }
+
+    #[derive(Default, Clone)]
+    struct MemoryStore {
+        entries: Rc<RefCell<Vec<CachedAnswer>>>,
+    }
+
+    impl AnswerCacheStore for MemoryStore {
+        fn load(&self) -> Result<Vec<CachedAnswer>, StorageError> {
+            Ok(self.entries.borrow().clone())
+        }
+
+        fn save(&self, entries: &[CachedAnswer]) -> Result<(), StorageError> {
+            *self.entries.borrow_mut() = entries.to_vec();
+            Ok(())
+        }
+    }
+
+    fn request(question: &str) -> AskRequest {
+        AskRequest::new(QuestionPolicy::default().apply(question).unwrap())
+    }
+
+    fn policy(ttl_ms: u64, max_entries: usize) -> CachePolicy {
+        CachePolicy {
+            ttl_ms,
+            max_entries,
+        }
+    }
+
+    #[test]
+    fn repeated_question_is_answered_from_cache() {
+        let time = Rc::new(Cell::new(0));
+        let gateway = CachingChatGateway::new(
+            CountingGateway::default(),
+            AnswerCache::new(CachePolicy::default()),
+            ManualClock(time.clone()),
+        );
+
+        let first = block_on(gateway.ask(request("Что такое Rust?"))).unwrap();
+        let second = block_on(gateway.ask(request("  Что такое Rust?  \n"))).unwrap();
+        assert!(!first.cached);
+        assert!(second.cached);
+        assert_eq!(second.answer, "ответ 1");
+        assert_eq!(gateway.inner.calls.get(), 1);
+
+        // Вопрос в другом регистре — другой вопрос.
+        let other_case = block_on(gateway.ask(request("что такое RUST?"))).unwrap();
+        assert!(!other_case.cached);
+        assert_eq!(other_case.answer, "ответ 2");
+
+        // Другие параметры генерации — другой ключ.
+        let tuned = request("Что такое Rust?").with_generation(GenerationSettings {
+            temperature: Some(Temperature::try_new(0.1).unwrap()),
+            ..GenerationSettings::default()
+        });
+        assert!(!block_on(gateway.ask(tuned)).unwrap().cached);
+
+        // Обход кэша получает и сохраняет свежий ответ.
+        let fresh = block_on(gateway.ask(request("Что такое Rust?").with_cache_bypass(true)));
+        assert_eq!(fresh.unwrap().answer, "ответ 4");
+        let cached = block_on(gateway.ask(request("Что такое Rust?"))).unwrap();
+        assert_eq!(cached.answer, "ответ 4");
+        assert!(cached.cached);
+    }
+
+    #[test]
+    fn scopes_do_not_share_answers() {
+        let time = Rc::new(Cell::new(0));
+        let cache = AnswerCache::new(CachePolicy::default());
+        let scoped = |scope: &str| {
+            CachingChatGateway::new(
+                CountingGateway::default(),
+                cache.clone(),
+                ManualClock(time.clone()),
+            )
+            .with_scope(scope)
+        };
+        let mock = scoped("Mock");
+        let course = scoped("Backend курса");
+
+        block_on(mock.ask(request("Что такое Rust?"))).unwrap();
+        assert!(
+            !block_on(course.ask(request("Что такое Rust?")))
+                .unwrap()
+                .cached
+        );
+        assert!(
+            block_on(mock.ask(request("Что такое Rust?")))
+                .unwrap()
+                .cached
+        );
+    }
+
+    #[test]
+    fn entries_expire_and_least_recently_used_is_evicted() {
+        let time = Rc::new(Cell::new(0));
+        let cache = AnswerCache::new(policy(1_000, 2));
+        let gateway = CachingChatGateway::new(
+            CountingGateway::default(),
+            cache.clone(),
+            ManualClock(time.clone()),
+        );
+        let ask = |question: &str| block_on(gateway.ask(request(question))).unwrap();
+
+        assert!(cache.is_empty());
+        ask("Первый");
+        ask("Второй");
+        assert!(ask("Первый").cached);
+        // «Второй» использовался давнее «Первого» и будет вытеснен.
+        ask("Третий");
+        assert_eq!(cache.len(), 2);
+        assert!(ask("Первый").cached);
+        assert!(!ask("Второй").cached);
+
+        time.set(5_000);
+        assert!(!ask("Первый").cached);
+    }
+
+    #[test]
+    fn streamed_answer_is_cached_and_persisted() {
+        let store = MemoryStore::default();
+        let time = Rc::new(Cell::new(0));
+        let gateway = CachingChatGateway::new(
+            CountingGateway::default(),
+            AnswerCache::persistent(CachePolicy::default(), store.clone()),
+            ManualClock(time.clone()),
+        );
+
+        let chunks: Vec<_> = block_on(
+            block_on(gateway.ask_stream(request("Поток")))
+                .unwrap()
+                .collect(),
+        );
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(store.entries.borrow()[0].result.answer, "поток");
+
+        // Новый кэш поднимает записи из хранилища.
+        let restored = CachingChatGateway::new(
+            CountingGateway::default(),
+            AnswerCache::persistent(CachePolicy::default(), store),
+            ManualClock(time),
+        );
+        let result = block_on(restored.ask(request("Поток"))).unwrap();
+        assert_eq!(result.answer, "поток");
+        assert!(result.cached);
+        assert_eq!(restored.inner.calls.get(), 0);
+    }
+}
@@ -0,0 +1,421 @@
+//! Автомат защиты (circuit breaker) для шлюза вопросов.
+//!
+//! Пока backend отвечает, автомат **замкнут** и пропускает запросы.
+//! После `failure_threshold` сбоев подряд он **размыкается**: запросы
+//! сразу завершаются ошибкой `GatewayError::CircuitOpen`, не дожидаясь
+//! сетевого таймаута. Когда пройдёт `cool_down_ms`, автомат переходит
+//! в **полуоткрытое** состояние: следующий вопрос сначала проверяет
+//! `HealthGateway::health`. Если backend жив, автомат замыкается и вопрос
+//! уходит на сервер; если нет — размыкается ещё на один период.
+//!
+//! Состояние хранит `CircuitBreaker`; его клоны разделяют одно состояние,
+//! поэтому UI может показывать его в панели «Статус API».
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+
+use crate::application::ports::{
+    AnswerStream, AskRequest, ChatGateway, Clock, GatewayError, HealthGateway, StreamingChatGateway,
+};
+use crate::domain::{AskResult, ServiceState, Timestamp};
+
+/// Настройки автомата.
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitBreakerPolicy {
+    /// Сколько сбоев подряд размыкают автомат.
+    pub failure_threshold: u32,
+    /// Сколько автомат остаётся разомкнутым до пробной проверки.
+    pub cool_down_ms: u64,
+    /// Какие ошибки считаются сбоем backend.
+    pub is_failure: fn(&GatewayError) -> bool,
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            cool_down_ms: 30_000,
+            is_failure: backend_failure,
+        }
+    }
+}
+
+/// Сбой backend: сеть, таймаут или ошибка 5xx. Ошибки в запросе (4xx),
+/// отмена и локальные ограничения о здоровье сервера ничего не говорят.
+pub fn backend_failure(error: &GatewayError) -> bool {
+    match error {
+        GatewayError::Network(_) | GatewayError::Timeout(_) => true,
+        GatewayError::Api(error) => error.is_server_error(),
+        _ => false,
+    }
+}
+
+/// Состояние автомата.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Запросы идут на backend; считаются сбои подряд.
+    Closed { failures: u32 },
+    /// Запросы не отправляются до момента `until`.
+    Open { until: Timestamp },
+    /// Идёт пробная проверка здоровья backend.
+    HalfOpen,
+}
+
+impl CircuitState {
+    /// Сколько ещё автомат будет разомкнут.
+    pub fn remaining_ms(&self, now: Timestamp) -> u64 {
+        match self {
+            CircuitState::Open { until } => until.as_millis().saturating_sub(now.as_millis()),
+            _ => 0,
+        }
+    }
+}
+
+/// Наблюдатель за сменой состояния автомата.
+pub type CircuitObserver = Rc<dyn Fn(CircuitState)>;
+
+struct BreakerState {
+    policy: CircuitBreakerPolicy,
+    state: CircuitState,
+    observer: Option<CircuitObserver>,
+}
+
+/// Что делать с очередным запросом.
+enum Admission {
+    Pass,
+    Probe,
+    Reject(u64),
+}
+
+/// Общее состояние автомата.
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    inner: Rc<RefCell<BreakerState>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(policy: CircuitBreakerPolicy) -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(BreakerState {
+                policy,
+                state: CircuitState::Closed { failures: 0 },
+                observer: None,
+            })),
+        }
+    }
+
+    /// Вызывается при каждой смене состояния.
+    pub fn with_observer(self, observer: CircuitObserver) -> Self {
+        self.inner.borrow_mut().observer = Some(observer);
+        self
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.inner.borrow().state
+    }
+
+    fn set(&self, state: CircuitState) {
+        let observer = {
+            let mut inner = self.inner.borrow_mut();
+            if inner.state == state {
+                return;
+            }
+            inner.state = state;
+            inner.observer.clone()
+        };
+        if let Some(observer) = observer {
+            observer(state);
+        }
+    }
+
+    fn open(&self, now: Timestamp) -> u64 {
+        let cool_down_ms = self.inner.borrow().policy.cool_down_ms;
+        self.set(CircuitState::Open {
+            until: Timestamp::from_millis(now.as_millis().saturating_add(cool_down_ms)),
+        });
+        cool_down_ms
+    }
+
+    fn admit(&self, now: Timestamp) -> Admission {
+        match self.state() {
+            CircuitState::Closed { .. } => Admission::Pass,
+            CircuitState::HalfOpen => Admission::Reject(0),
+            state @ CircuitState::Open { .. } => match state.remaining_ms(now) {
+                0 => {
+                    self.set(CircuitState::HalfOpen);
+                    Admission::Probe
+                }
+                remaining => Admission::Reject(remaining),
+            },
+        }
+    }
+
+    /// Учитывает исход запроса к backend.
+    fn record<T>(&self, outcome: &Result<T, GatewayError>, now: Timestamp) {
+        let (is_failure, threshold) = {
+            let inner = self.inner.borrow();
+            (inner.policy.is_failure, inner.policy.failure_threshold)
+        };
+        match outcome {
+            Ok(_) => self.set(CircuitState::Closed { failures: 0 }),
+            Err(error) if is_failure(error) => match self.state() {
+                CircuitState::Closed { failures } if failures + 1 < threshold.max(1) => {
+                    self.set(CircuitState::Closed {
+                        failures: failures + 1,
+                    })
+                }
+                CircuitState::Open { .. } => {}
+                _ => {
+                    self.open(now);
+                }
+            },
+            Err(_) => {}
+        }
+    }
+}
+
+/// Декоратор шлюза с автоматом защиты.
+///
+/// `probe` — шлюз проверки здоровья для полуоткрытого состояния.
+pub struct CircuitBreakerGateway<G, H, C> {
+    inner: G,
+    probe: H,
+    breaker: CircuitBreaker,
+    clock: C,
+}
+
+impl<G, H, C> CircuitBreakerGateway<G, H, C>
+where
+    H: HealthGateway,
+    C: Clock,
+{
+    pub fn new(inner: G, probe: H, breaker: CircuitBreaker, clock: C) -> Self {
+        Self {
+            inner,
+            probe,
+            breaker,
+            clock,
+        }
+    }
+
+    /// Пропускает запрос или отвечает ошибкой, пока автомат разомкнут.
+    async fn admit(&self) -> Result<(), GatewayError> {
+        match self.breaker.admit(self.clock.now()) {
+            Admission::Pass => Ok(()),
+            Admission::Reject(remaining_ms) => Err(circuit_open(remaining_ms)),
+            Admission::Probe => {
+                let _guard = ProbeGuard {
+                    breaker: &self.breaker,
+                    now: self.clock.now(),
+                };
+                let alive = matches!(
+                    self.probe.health().await,
+                    Ok(status) if status.status != ServiceState::Down
+                );
+                if alive {
+                    self.breaker.set(CircuitState::Closed { failures: 0 });
+                    Ok(())
+                } else {
+                    Err(circuit_open(self.breaker.open(self.clock.now())))
+                }
+            }
+        }
+    }
+}
+
+/// Если пробный запрос бросили, не дождавшись ответа, автомат снова
+/// размыкается, а не остаётся полуоткрытым навсегда.
+struct ProbeGuard<'a> {
+    breaker: &'a CircuitBreaker,
+    now: Timestamp,
+}
+
+impl Drop for ProbeGuard<'_> {
+    fn drop(&mut self) {
+        if self.breaker.state() == CircuitState::HalfOpen {
+            self.breaker.open(self.now);
+        }
+    }
+}
+
+fn circuit_open(remaining_ms: u64) -> GatewayError {
+    GatewayError::CircuitOpen(remaining_ms.min(u32::MAX as u64) as u32)
+}
+
+#[async_trait(?Send)]
+impl<G, H, C> ChatGateway for CircuitBreakerGateway<G, H, C>
+where
+    G: ChatGateway,
+    H: HealthGateway,
+    C: Clock,
+{
+    async fn ask(&self, request: AskRequest) -> Result<AskResult, GatewayError> {
+        self.admit().await?;
+        let outcome = self.inner.ask(request).await;
+        self.breaker.record(&outcome, self.clock.now());
+        outcome
+    }
+}
+
+#[async_trait(?Send)]
+impl<G, H, C> StreamingChatGateway for CircuitBreakerGateway<G, H, C>
+where
+    G: StreamingChatGateway,
+    H: HealthGateway,
+    C: Clock + Clone + 'static,
+{
+    /// Сбой посреди потока тоже учитывается автоматом.
+    async fn ask_stream(&self, request: AskRequest) -> Result<AnswerStream, GatewayError> {
+        self.admit().await?;
+        let outcome = self.inner.ask_stream(request).await;
+        self.breaker.record(&outcome, self.clock.now());
+        let breaker = self.breaker.clone();
+        let clock = self.clock.clone();
+        Ok(outcome?
+            .inspect(move |chunk| {
+                if chunk.is_err() {
+                    breaker.record(chunk, clock.now());
+                }
+            })
+            .boxed_local())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::application::ports::{ApiError, ApiErrorCode};
+    use crate::domain::{AnswerSource, HealthStatus, Question};
+
+    /// Шлюз, который «лежит», пока `down` выставлен.
+    #[derive(Default)]
+    struct Backend {
+        down: Cell<bool>,
+        asks: Cell<u32>,
+        probes: Cell<u32>,
+    }
+
+    #[async_trait(?Send)]
+    impl ChatGateway for &Backend {
+        async fn ask(&self, _request: AskRequest) -> Result<AskResult, GatewayError> {
+            self.asks.set(self.asks.get() + 1);
+            if self.down.get() {
+                return Err(GatewayError::Network("connection refused".to_string()));
+            }
+            Ok(AskResult {
+                answer: "ok".to_string(),
+                source: AnswerSource::Mock,
+                system_prompt_applied: false,
+                cached: false,
+            })
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl HealthGateway for &Backend {
+        async fn health(&self) -> Result<HealthStatus, GatewayError> {
+            self.probes.set(self.probes.get() + 1);
+            if self.down.get() {
+                return Err(GatewayError::Network("connection refused".to_string()));
+            }
+            Ok(HealthStatus {
+                status: ServiceState::Ok,
+                version: "1.0".to_string(),
+                gigachat_enabled: false,
+            })
+        }
+    }
+
+    #[derive(Clone)]
+    struct ManualClock(Rc<Cell<u64>>);
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Timestamp {
+            Timestamp::from_millis(self.0.get())
+        }
+    }
+
+    fn request() -> AskRequest {
+        AskRequest::new(Question::try_new("Что такое Rust?").unwrap())
+    }
+
+    #[test]
+    fn opens_after_threshold_and_closes_after_successful_probe() {
+        let backend = Backend::default();
+        let time = Rc::new(Cell::new(0));
+        let transitions = Rc::new(RefCell::new(Vec::new()));
+        let observed = transitions.clone();
+        let breaker = CircuitBreaker::new(CircuitBreakerPolicy {
+            failure_threshold: 2,
+            cool_down_ms: 1_000,
+            ..CircuitBreakerPolicy::default()
+        })
+        .with_observer(Rc::new(move |state| observed.borrow_mut().push(state)));
+        let gateway = CircuitBreakerGateway::new(
+            &backend,
+            &backend,
+            breaker.clone(),
+            ManualClock(time.clone()),
+        );
+
+        backend.down.set(true);
+        assert!(block_on(gateway.ask(request())).is_err());
+        assert!(block_on(gateway.ask(request())).is_err());
+        assert_eq!(
+            breaker.state(),
+            CircuitState::Open {
+                until: Timestamp::from_millis(1_000)
+            }
+        );
+
+        // Разомкнутый автомат не трогает backend.
+        time.set(400);
+        assert_eq!(
+            block_on(gateway.ask(request())),
+            Err(GatewayError::CircuitOpen(600))
+        );
+        assert_eq!(backend.asks.get(), 2);
+
+        // Проверка здоровья не прошла — ещё один период ожидания.
+        time.set(1_000);
+        assert_eq!(
+            block_on(gateway.ask(request())),
+            Err(GatewayError::CircuitOpen(1_000))
+        );
+        assert_eq!((backend.asks.get(), backend.probes.get()), (2, 1));
+
+        backend.down.set(false);
+        time.set(2_000);
+        assert!(block_on(gateway.ask(request())).is_ok());
+        assert_eq!(breaker.state(), CircuitState::Closed { failures: 0 });
+        assert_eq!(transitions.borrow().len(), 6);
+    }
+
+    #[test]
+    fn client_errors_do_not_open_the_circuit() {
+        assert!(backend_failure(&GatewayError::Timeout(1_000)));
+        assert!(!backend_failure(&GatewayError::Cancelled));
+        assert!(!backend_failure(&GatewayError::Api(ApiError {
+            status: Some(400),
+            code: Some(ApiErrorCode::EmptyQuestion),
+            message: "Question cannot be empty".to_string(),
+            body: String::new(),
+        })));
+
+        let breaker = CircuitBreaker::new(CircuitBreakerPolicy {
+            failure_threshold: 1,
+            ..CircuitBreakerPolicy::default()
+        });
+        let now = Timestamp::from_millis(0);
+        breaker.record::<()>(&Err(GatewayError::Cancelled), now);
+        assert_eq!(breaker.state(), CircuitState::Closed { failures: 0 });
+        breaker.record::<()>(&Err(GatewayError::Network("down".to_string())), now);
+        assert!(matches!(breaker.state(), CircuitState::Open { .. }));
+    }
+}
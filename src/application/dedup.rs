@@ -0,0 +1,390 @@
+//! Склейка одинаковых одновременных запросов.
+//!
+//! `DeduplicatingGateway` — декоратор над `ChatGateway`/`StreamingChatGateway`:
+//! если такой же вопрос (тот же ключ, что у кэша ответов, в той же
+//! области) уже отправлен и ответ ещё не пришёл, новый запрос не уходит
+//! на backend, а ждёт результата первого. Так двойной клик отправляет
+//! один запрос.
+//!
+//! Незавершённые запросы хранит `InFlightRequests`; его клоны разделяют
+//! одно состояние. Если первый запрос бросили, не дождавшись ответа
+//! (например, закрыли поток), или отменили, ожидающие отправляют свой
+//! запрос сами. Ожидающий запрос можно отменить его собственным токеном.
+//! Запрос в обход кэша склеивается только с такими же: он просит свежий
+//! ответ.
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use futures::channel::oneshot;
+use futures::future::{select, Either};
+use futures::StreamExt;
+
+use crate::application::cache::cache_key;
+use crate::application::ports::{
+    AnswerChunk, AnswerStream, AskRequest, CancellationToken, ChatGateway, GatewayError,
+    StreamingChatGateway,
+};
+use crate::domain::AskResult;
+
+type Outcome = Result<AskResult, GatewayError>;
+
+/// Запросы, ответа на которые ещё ждут.
+#[derive(Clone, Default)]
+pub struct InFlightRequests {
+    waiting: Rc<RefCell<HashMap<String, Vec<oneshot::Sender<Outcome>>>>>,
+}
+
+impl InFlightRequests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Сколько разных запросов сейчас в полёте.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn len(&self) -> usize {
+        self.waiting.borrow().len()
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Первый запрос с ключом становится ведущим, остальные ждут его.
+    fn join(&self, key: String) -> Slot {
+        let mut waiting = self.waiting.borrow_mut();
+        match waiting.get_mut(&key) {
+            Some(followers) => {
+                let (sender, receiver) = oneshot::channel();
+                followers.push(sender);
+                Slot::Follower(receiver)
+            }
+            None => {
+                waiting.insert(key.clone(), Vec::new());
+                Slot::Leader(LeaderGuard {
+                    requests: self.clone(),
+                    key: Some(key),
+                })
+            }
+        }
+    }
+}
+
+enum Slot {
+    Leader(LeaderGuard),
+    Follower(oneshot::Receiver<Outcome>),
+}
+
+/// Ключ склейки: ключ кэша и признак обхода кэша.
+fn dedup_key(scope: &str, request: &AskRequest) -> String {
+    format!(
+        "{}\u{1e}bypass={}",
+        cache_key(scope, request),
+        request.bypass_cache
+    )
+}
+
+/// Ждёт ответа ведущего запроса, пока не отменят свой.
+///
+/// `None` — ведущий запрос брошен или отменён, и ответа от него не будет:
+/// запрос нужно отправить заново.
+async fn follow(
+    receiver: oneshot::Receiver<Outcome>,
+    cancellation: &CancellationToken,
+) -> Option<Outcome> {
+    let (cancel, cancelled) = oneshot::channel();
+    cancellation.on_cancel(move || {
+        let _ = cancel.send(());
+    });
+    // Отмена проверяется первой: отменённому запросу ответ уже не нужен.
+    match select(cancelled, receiver).await {
+        Either::Left(_) => Some(Err(GatewayError::Cancelled)),
+        Either::Right((Ok(Err(GatewayError::Cancelled)) | Err(_), _)) => None,
+        Either::Right((Ok(outcome), _)) => Some(outcome),
+    }
+}
+
+/// Регистрация ведущего запроса. Без `finish` при удалении снимает запрос
+/// из списка, и ожидающие узнают об этом по закрытому каналу.
+struct LeaderGuard {
+    requests: InFlightRequests,
+    key: Option<String>,
+}
+
+impl LeaderGuard {
+    fn finish(&mut self, outcome: &Outcome) {
+        let Some(key) = self.key.take() else {
+            return;
+        };
+        let followers = self.requests.waiting.borrow_mut().remove(&key);
+        for follower in followers.into_iter().flatten() {
+            let _ = follower.send(outcome.clone());
+        }
+    }
+}
+
+impl Drop for LeaderGuard {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.requests.waiting.borrow_mut().remove(&key);
+        }
+    }
+}
+
+/// Декоратор шлюза, склеивающий одинаковые одновременные запросы.
+pub struct DeduplicatingGateway<G> {
+    inner: G,
+    in_flight: InFlightRequests,
+    scope: String,
+}
+
+impl<G> DeduplicatingGateway<G> {
+    pub fn new(inner: G, in_flight: InFlightRequests) -> Self {
+        Self {
+            inner,
+            in_flight,
+            scope: String::new(),
+        }
+    }
+
+    /// Задаёт область ключей: запросы из разных областей не склеиваются.
+    pub fn with_scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = scope.into();
+        self
+    }
+}
+
+#[async_trait(?Send)]
+impl<G: ChatGateway> ChatGateway for DeduplicatingGateway<G> {
+    async fn ask(&self, request: AskRequest) -> Result<AskResult, GatewayError> {
+        let key = dedup_key(&self.scope, &request);
+        loop {
+            match self.in_flight.join(key.clone()) {
+                Slot::Leader(mut guard) => {
+                    let outcome = self.inner.ask(request).await;
+                    guard.finish(&outcome);
+                    return outcome;
+                }
+                Slot::Follower(receiver) => {
+                    if let Some(outcome) = follow(receiver, &request.cancellation).await {
+                        return outcome;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<G: StreamingChatGateway> StreamingChatGateway for DeduplicatingGateway<G> {
+    /// Ведущий запрос отдаёт поток как есть и собирает ответ по ходу чтения;
+    /// ожидающие получают готовый ответ одним фрагментом.
+    async fn ask_stream(&self, request: AskRequest) -> Result<AnswerStream, GatewayError> {
+        let key = dedup_key(&self.scope, &request);
+        loop {
+            match self.in_flight.join(key.clone()) {
+                Slot::Leader(mut guard) => {
+                    let stream = match self.inner.ask_stream(request).await {
+                        Ok(stream) => stream,
+                        Err(error) => {
+                            guard.finish(&Err(error.clone()));
+                            return Err(error);
+                        }
+                    };
+                    let mut answer = String::new();
+                    return Ok(stream
+                        .inspect(move |chunk| match chunk {
+                            Ok(AnswerChunk::Delta(text)) => answer.push_str(text),
+                            Ok(AnswerChunk::Done {
+                                source,
+                                system_prompt_applied,
+                                cached,
+                            }) => guard.finish(&Ok(AskResult {
+                                answer: std::mem::take(&mut answer),
+                                source: source.clone(),
+                                system_prompt_applied: *system_prompt_applied,
+                                cached: *cached,
+                            })),
+                            Err(error) => guard.finish(&Err(error.clone())),
+                        })
+                        .boxed_local());
+                }
+                Slot::Follower(receiver) => {
+                    if let Some(outcome) = follow(receiver, &request.cancellation).await {
+                        let result = outcome?;
+                        let chunks = vec![
+                            Ok(AnswerChunk::Delta(result.answer)),
+                            Ok(AnswerChunk::Done {
+                                source: result.source,
+                                system_prompt_applied: result.system_prompt_applied,
+                                cached: result.cached,
+                            }),
+                        ];
+                        return Ok(futures::stream::iter(chunks).boxed_local());
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use futures::executor::block_on;
+    use futures::future::join;
+
+    use super::*;
+    use crate::domain::{AnswerSource, Question};
+
+    /// Шлюз, отвечающий только после того, как его «отпустят».
+    struct GatedGateway {
+        calls: Cell<u32>,
+        gate: RefCell<Option<oneshot::Receiver<()>>>,
+    }
+
+    #[async_trait(?Send)]
+    impl ChatGateway for GatedGateway {
+        async fn ask(&self, request: AskRequest) -> Result<AskResult, GatewayError> {
+            self.calls.set(self.calls.get() + 1);
+            let gate = self.gate.borrow_mut().take();
+            if let Some(gate) = gate {
+                let _ = gate.await;
+            }
+            if request.cancellation.is_cancelled() {
+                return Err(GatewayError::Cancelled);
+            }
+            Ok(AskResult {
+                answer: format!("ответ на «{}»", request.question.as_str()),
+                source: AnswerSource::Mock,
+                system_prompt_applied: false,
+                cached: false,
+            })
+        }
+    }
+
+    fn request(question: &str) -> AskRequest {
+        AskRequest::new(Question::try_new(question).unwrap())
+    }
+
+    #[test]
+    fn identical_concurrent_questions_share_one_request() {
+        let (release, gate) = oneshot::channel();
+        let in_flight = InFlightRequests::new();
+        let gateway = DeduplicatingGateway::new(
+            GatedGateway {
+                calls: Cell::new(0),
+                gate: RefCell::new(Some(gate)),
+            },
+            in_flight.clone(),
+        );
+
+        let first = gateway.ask(request("Что такое Rust?"));
+        let second = gateway.ask(request("Что такое Rust?"));
+        let releaser = async {
+            let _ = release.send(());
+        };
+        let ((first, second), ()) = block_on(join(join(first, second), releaser));
+
+        assert_eq!(first, second);
+        assert_eq!(gateway.inner.calls.get(), 1);
+        assert!(in_flight.is_empty());
+
+        // Завершённый запрос больше не склеивается с новыми.
+        block_on(gateway.ask(request("Что такое Rust?"))).unwrap();
+        assert_eq!(gateway.inner.calls.get(), 2);
+    }
+
+    #[test]
+    fn abandoned_leader_lets_followers_send_their_own_request() {
+        let in_flight = InFlightRequests::new();
+        let gateway = DeduplicatingGateway::new(
+            GatedGateway {
+                calls: Cell::new(0),
+                gate: RefCell::new(None),
+            },
+            in_flight.clone(),
+        );
+
+        let Slot::Leader(guard) = in_flight.join(dedup_key("", &request("Вопрос"))) else {
+            panic!("первый запрос должен стать ведущим");
+        };
+        let Slot::Follower(receiver) = in_flight.join(dedup_key("", &request("Вопрос")))
+        else {
+            panic!("второй запрос должен ждать первый");
+        };
+        drop(guard);
+        assert!(block_on(receiver).is_err());
+        assert!(in_flight.is_empty());
+
+        assert!(block_on(gateway.ask(request("Вопрос"))).is_ok());
+        assert_eq!(gateway.inner.calls.get(), 1);
+    }
+
+    fn gated(gate: oneshot::Receiver<()>) -> DeduplicatingGateway<GatedGateway> {
+        DeduplicatingGateway::new(
+            GatedGateway {
+                calls: Cell::new(0),
+                gate: RefCell::new(Some(gate)),
+            },
+            InFlightRequests::new(),
+        )
+    }
+
+    #[test]
+    fn waiting_request_is_cancelled_by_its_own_token() {
+        let (release, gate) = oneshot::channel();
+        let gateway = gated(gate);
+        let cancellation = CancellationToken::new();
+
+        let first = gateway.ask(request("Вопрос"));
+        let second = gateway.ask(request("Вопрос").with_cancellation(cancellation.clone()));
+        let canceller = async {
+            cancellation.cancel();
+            let _ = release.send(());
+        };
+        let ((first, second), ()) = block_on(join(join(first, second), canceller));
+
+        assert!(first.is_ok());
+        assert_eq!(second, Err(GatewayError::Cancelled));
+        assert_eq!(gateway.inner.calls.get(), 1);
+    }
+
+    #[test]
+    fn cancelled_leader_lets_followers_send_their_own_request() {
+        let (release, gate) = oneshot::channel();
+        let gateway = gated(gate);
+        let cancellation = CancellationToken::new();
+
+        let first = gateway.ask(request("Вопрос").with_cancellation(cancellation.clone()));
+        let second = gateway.ask(request("Вопрос"));
+        let canceller = async {
+            cancellation.cancel();
+            let _ = release.send(());
+        };
+        let ((first, second), ()) = block_on(join(join(first, second), canceller));
+
+        assert_eq!(first, Err(GatewayError::Cancelled));
+        assert!(second.is_ok());
+        assert_eq!(gateway.inner.calls.get(), 2);
+    }
+
+    #[test]
+    fn cache_bypass_is_not_served_by_regular_request() {
+        let (release, gate) = oneshot::channel();
+        let gateway = gated(gate);
+
+        let first = gateway.ask(request("Вопрос"));
+        let second = gateway.ask(request("Вопрос").with_cache_bypass(true));
+        let releaser = async {
+            let _ = release.send(());
+        };
+        let ((first, second), ()) = block_on(join(join(first, second), releaser));
+
+        assert!(first.is_ok() && second.is_ok());
+        assert_eq!(gateway.inner.calls.get(), 2);
+    }
+}
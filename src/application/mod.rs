@@ -2,8 +2,34 @@
 //!
 //! Здесь нет UI и нет сетевых деталей. В этом слое описываются:
 //! - **порты** (интерфейсы), через которые приложение общается с внешним миром;
-//! - **use‑cases** — конкретные сценарии вроде «задать вопрос» или «проверить статус».
+//! - **use‑cases** — конкретные сценарии вроде «задать вопрос» или «проверить статус»;
+//! - **декораторы шлюзов**: повтор запросов при временных ошибках, кэш
+//!   ответов, ограничение частоты, склейка одинаковых запросов и автомат
+//!   защиты от недоступного backend;
+//! - **учётные данные** для запросов: ключ API, токен, вход по логину
+//!   или сессия пользователя;
+//! - **профили backend** и реестр, собирающий по профилю шлюзы.
+pub mod auth;
+pub mod backends;
+pub mod cache;
+pub mod circuit_breaker;
+pub mod dedup;
 pub mod ports;
+pub mod rate_limit;
+pub mod retry;
 pub mod usecases;
 
-pub use usecases::{AskQuestionUseCase, CheckHealthUseCase, UseCaseError};
+pub use auth::{AuthSettings, NoAuth, RefreshingTokenAuth, SessionAuth, StaticAuth};
+pub use backends::{BackendKind, BackendProfile, GatewayPair, GatewayRegistry};
+pub use cache::{AnswerCache, CachePolicy, CachingChatGateway};
+pub use circuit_breaker::{
+    CircuitBreaker, CircuitBreakerGateway, CircuitBreakerPolicy, CircuitState,
+};
+pub use dedup::{DeduplicatingGateway, InFlightRequests};
+pub use rate_limit::{RateLimitPolicy, RateLimitedGateway, RateLimiter};
+pub use retry::{RetryAttempt, RetryPolicy, RetryingGateway};
+
+pub use usecases::{
+    AskQuestionUseCase, AuthenticateUseCase, CheckHealthUseCase, ContinueConversationUseCase,
+    ManageHistoryUseCase, OutboxUseCase, RecordExchangeUseCase, SearchHistoryUseCase, UseCaseError,
+};
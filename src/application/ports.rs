@@ -2,12 +2,117 @@
 //!
 //! Порт — это контракт, описывающий, что нужно приложению, не привязываясь
 //! к конкретной реализации. В учебном проекте такими портами являются:
-//! - отправка вопроса (`ChatGateway`);
-//! - проверка статуса (`HealthGateway`).
+//! - отправка вопроса (`ChatGateway`) и потоковое получение ответа
+//!   (`StreamingChatGateway`), вместе — `ChatBackend`;
+//! - проверка статуса (`HealthGateway`);
+//! - учётные данные для запросов (`AuthProvider`) и получение токена
+//!   доступа (`TokenSource`);
+//! - вход, продление и завершение сессии (`SessionGateway`) и хранение
+//!   текущей сессии (`SessionRepository`);
+//! - хранение истории вопросов (`HistoryRepository`), очереди
+//!   неотправленных вопросов (`OutboxRepository`) и кэша ответов
+//!   (`AnswerCacheStore`);
+//! - текущее время (`Clock`) и ожидание (`Sleeper`).
+//!
+//! Здесь же описан `CancellationToken` — признак отмены, который UI
+//! передаёт вместе с запросом.
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+
 use async_trait::async_trait;
+use futures::stream::LocalBoxStream;
 use thiserror::Error;
 
-use crate::domain::{AskResult, HealthStatus, Question};
+use crate::domain::{
+    AnswerSource, AskResult, GenerationSettings, HealthStatus, HistoryEntry, HistoryId,
+    LoginCredentials, Message, OutboxId, PendingQuestion, Question, Session, SystemPrompt,
+    Timestamp,
+};
+
+/// Машиночитаемый код ошибки backend (поле `code` в JSON ошибки).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ApiErrorCode {
+    /// `EMPTY_QUESTION` — вопрос пуст.
+    EmptyQuestion,
+    /// `INVALID_REQUEST` — некорректный JSON или параметры запроса.
+    InvalidRequest,
+    /// `RATE_LIMITED` — превышен лимит запросов.
+    RateLimited,
+    /// `INTERNAL_ERROR` — непредвиденная ошибка backend.
+    Internal,
+    /// Код, неизвестный этой версии UI.
+    Other(String),
+}
+
+impl ApiErrorCode {
+    /// Разбирает код из JSON ошибки; неизвестный код сохраняется как есть.
+    pub fn parse(raw: &str) -> Self {
+        match raw.trim().to_ascii_uppercase().as_str() {
+            "EMPTY_QUESTION" => ApiErrorCode::EmptyQuestion,
+            "INVALID_REQUEST" => ApiErrorCode::InvalidRequest,
+            "RATE_LIMITED" => ApiErrorCode::RateLimited,
+            "INTERNAL_ERROR" => ApiErrorCode::Internal,
+            _ => ApiErrorCode::Other(raw.to_string()),
+        }
+    }
+
+    /// Код в том виде, в каком его присылает backend.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ApiErrorCode::EmptyQuestion => "EMPTY_QUESTION",
+            ApiErrorCode::InvalidRequest => "INVALID_REQUEST",
+            ApiErrorCode::RateLimited => "RATE_LIMITED",
+            ApiErrorCode::Internal => "INTERNAL_ERROR",
+            ApiErrorCode::Other(raw) => raw,
+        }
+    }
+}
+
+impl fmt::Display for ApiErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Ошибка, которую вернул backend.
+///
+/// Хранит HTTP‑статус (его нет у ошибки внутри потока ответа), код
+/// из JSON ошибки, человекочитаемое сообщение и исходное тело ответа.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    pub status: Option<u16>,
+    pub code: Option<ApiErrorCode>,
+    pub message: String,
+    pub body: String,
+}
+
+impl ApiError {
+    /// Есть ли смысл повторить запрос: лимит запросов или временная
+    /// недоступность backend (429, 502–504).
+    pub fn retryable(&self) -> bool {
+        matches!(self.status, Some(429 | 502 | 503 | 504))
+            || self.code == Some(ApiErrorCode::RateLimited)
+    }
+
+    /// Ошибка на стороне сервера (5xx).
+    pub fn is_server_error(&self) -> bool {
+        matches!(self.status, Some(500..=599))
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(status) = self.status {
+            write!(f, "HTTP {status}: ")?;
+        }
+        f.write_str(&self.message)?;
+        if let Some(code) = &self.code {
+            write!(f, " (код: {code})")?;
+        }
+        Ok(())
+    }
+}
 
 /// Ошибки уровня шлюза (gateway).
 ///
@@ -17,15 +122,316 @@ pub enum GatewayError {
     #[error("Сетевая ошибка: {0}")]
     Network(String),
     #[error("Ошибка API: {0}")]
-    Api(String),
+    Api(ApiError),
+    /// HTTP 401: backend не принял учётные данные или их нет.
+    #[error("Требуется авторизация: {0}")]
+    Unauthorized(ApiError),
+    /// HTTP 403: учётные данные приняты, но доступа к ресурсу нет.
+    #[error("Доступ запрещён: {0}")]
+    Forbidden(ApiError),
+    #[error("Сервер не ответил за {0} мс")]
+    Timeout(u32),
+    #[error("Запрос отменён")]
+    Cancelled,
+    /// Запрос не отправлен: клиент превысил собственный лимит запросов.
+    /// Внутри — через сколько миллисекунд можно попробовать снова.
+    #[error("Запрос не отправлен: превышен лимит запросов, повторите через {0} мс")]
+    Throttled(u32),
+    /// Запрос не отправлен: backend недавно не отвечал и автомат защиты
+    /// разомкнут. Внутри — сколько миллисекунд до следующей проверки.
+    #[error("Backend недоступен, запросы приостановлены ещё на {0} мс")]
+    CircuitOpen(u32),
+    #[error("Backend не поддерживает потоковые ответы")]
+    StreamingUnsupported,
     #[error("Некорректный ответ API")]
     InvalidPayload,
 }
 
+impl GatewayError {
+    /// Временная ли это ошибка, после которой есть смысл повторить запрос.
+    ///
+    /// Повторяются сетевые сбои, таймауты и ошибки API, которые сам
+    /// backend считает временными (`ApiError::retryable`). Прочие ошибки API
+    /// и некорректные ответы повтор не исправит, а отменённый пользователем
+    /// запрос повторять нельзя. Отклонённый локальным лимитом запрос тоже
+    /// не повторяется автоматически — иначе лимит теряет смысл.
+    pub fn retryable(&self) -> bool {
+        match self {
+            GatewayError::Network(_) | GatewayError::Timeout(_) => true,
+            GatewayError::Api(error) => error.retryable(),
+            _ => false,
+        }
+    }
+
+    /// Не удалось достучаться до backend: нет сети или автомат защиты
+    /// разомкнут после серии сетевых сбоев. Такой вопрос стоит отложить
+    /// в очередь и отправить, когда связь вернётся.
+    pub fn is_unreachable(&self) -> bool {
+        matches!(
+            self,
+            GatewayError::Network(_) | GatewayError::CircuitOpen(_)
+        )
+    }
+
+    /// Backend отверг учётные данные: пользователю нужно войти заново
+    /// или указать другой ключ.
+    pub fn requires_auth(&self) -> bool {
+        matches!(
+            self,
+            GatewayError::Unauthorized(_) | GatewayError::Forbidden(_)
+        )
+    }
+}
+
+/// Ошибки хранилища (например, `localStorage` браузера).
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum StorageError {
+    #[error("Хранилище недоступно: {0}")]
+    Unavailable(String),
+    #[error("Данные в хранилище повреждены: {0}")]
+    Corrupted(String),
+}
+
+/// Признак отмены запроса.
+///
+/// Клоны токена разделяют одно состояние: UI держит у себя копию и вызывает
+/// `cancel`, а шлюз подписывается через `on_cancel`, чтобы прервать
+/// сетевой запрос. Отмена необратима.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    state: Rc<CancellationState>,
+}
+
+#[derive(Default)]
+struct CancellationState {
+    cancelled: Cell<bool>,
+    listeners: RefCell<Vec<Box<dyn FnOnce()>>>,
+}
+
+impl CancellationToken {
+    /// Создаёт неотменённый токен.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Отменяет запрос и уведомляет подписчиков (повторный вызов ничего не делает).
+    pub fn cancel(&self) {
+        if self.state.cancelled.replace(true) {
+            return;
+        }
+        let listeners = self.state.listeners.take();
+        for listener in listeners {
+            listener();
+        }
+    }
+
+    /// Проверяет, отменён ли запрос.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.get()
+    }
+
+    /// Подписывается на отмену; если токен уже отменён, вызывает сразу.
+    pub fn on_cancel(&self, listener: impl FnOnce() + 'static) {
+        if self.is_cancelled() {
+            listener();
+        } else {
+            self.state.listeners.borrow_mut().push(Box::new(listener));
+        }
+    }
+}
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// Токены равны, если это копии одного токена.
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl Eq for CancellationToken {}
+
+/// Запрос к `ChatGateway`.
+///
+/// Помимо вопроса содержит предыдущие сообщения диалога (контекст),
+/// параметры генерации, необязательный системный промпт, токен отмены
+/// и признак обхода кэша ответов. Для одиночного вопроса контекст пуст.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AskRequest {
+    pub question: Question,
+    pub context: Vec<Message>,
+    pub generation: GenerationSettings,
+    pub system_prompt: Option<SystemPrompt>,
+    pub cancellation: CancellationToken,
+    pub bypass_cache: bool,
+}
+
+impl AskRequest {
+    /// Создаёт запрос без контекста.
+    pub fn new(question: Question) -> Self {
+        Self {
+            question,
+            context: Vec::new(),
+            generation: GenerationSettings::default(),
+            system_prompt: None,
+            cancellation: CancellationToken::new(),
+            bypass_cache: false,
+        }
+    }
+
+    /// Добавляет к запросу контекст диалога.
+    pub fn with_context(mut self, context: Vec<Message>) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Задаёт параметры генерации.
+    pub fn with_generation(mut self, generation: GenerationSettings) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    /// Задаёт системный промпт, переопределяющий промпт backend.
+    pub fn with_system_prompt(mut self, system_prompt: Option<SystemPrompt>) -> Self {
+        self.system_prompt = system_prompt;
+        self
+    }
+
+    /// Задаёт токен, которым запрос можно отменить.
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Просит не брать ответ из кэша (свежий ответ всё равно кэшируется).
+    pub fn with_cache_bypass(mut self, bypass_cache: bool) -> Self {
+        self.bypass_cache = bypass_cache;
+        self
+    }
+}
+
+/// Учётные данные, которые клиент прикладывает к запросу.
+#[derive(Clone, PartialEq, Eq)]
+pub enum Credentials {
+    /// Статический ключ API (заголовок `X-API-Key`).
+    ApiKey(String),
+    /// Токен доступа (заголовок `Authorization: Bearer …`).
+    Bearer(String),
+}
+
+impl Credentials {
+    /// HTTP‑заголовок с учётными данными: имя и значение.
+    pub fn header(&self) -> (&'static str, String) {
+        match self {
+            Credentials::ApiKey(key) => ("X-API-Key", key.clone()),
+            Credentials::Bearer(token) => ("Authorization", format!("Bearer {token}")),
+        }
+    }
+}
+
+/// Секреты в отладочный вывод не попадают.
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Credentials::ApiKey(_) => f.write_str("ApiKey(***)"),
+            Credentials::Bearer(_) => f.write_str("Bearer(***)"),
+        }
+    }
+}
+
+/// Порт, выдающий учётные данные для запросов к backend.
+#[async_trait(?Send)]
+pub trait AuthProvider {
+    /// Учётные данные для очередного запроса; `None` — запрос без них.
+    /// Если за ними нужно сходить в сеть, отмена запроса (`cancellation`)
+    /// прерывает и это обращение.
+    async fn credentials(
+        &self,
+        cancellation: &CancellationToken,
+    ) -> Result<Option<Credentials>, GatewayError>;
+
+    /// Backend ответил 401 на выданные учётные данные. Провайдер, который
+    /// умеет получать новый токен, забывает старый и возвращает `true` —
+    /// тогда клиент один раз повторяет запрос.
+    async fn invalidate(&self) -> bool {
+        false
+    }
+}
+
+/// Токен доступа и момент, когда он перестанет действовать.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AccessToken {
+    pub token: String,
+    /// `None` — срок действия неизвестен.
+    pub expires_at: Option<Timestamp>,
+}
+
+impl fmt::Debug for AccessToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessToken")
+            .field("token", &"***")
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+/// Порт для получения токена доступа (например, у эндпоинта входа).
+#[async_trait(?Send)]
+pub trait TokenSource {
+    /// Новый токен; получение прерывается отменой `cancellation`.
+    async fn fetch_token(
+        &self,
+        cancellation: &CancellationToken,
+    ) -> Result<AccessToken, GatewayError>;
+}
+
+/// Порт для управления сессией пользователя на backend.
+#[async_trait(?Send)]
+pub trait SessionGateway {
+    /// Вход по логину и паролю; неверные данные дают `Unauthorized`.
+    async fn login(&self, credentials: &LoginCredentials) -> Result<Session, GatewayError>;
+    /// Новый токен доступа по токену обновления сессии.
+    async fn refresh(&self, session: &Session) -> Result<Session, GatewayError>;
+    /// Завершает сессию на backend.
+    async fn logout(&self, session: &Session) -> Result<(), GatewayError>;
+}
+
 /// Порт для отправки вопроса в backend.
 #[async_trait(?Send)]
 pub trait ChatGateway {
-    async fn ask(&self, question: Question) -> Result<AskResult, GatewayError>;
+    async fn ask(&self, request: AskRequest) -> Result<AskResult, GatewayError>;
+}
+
+/// Фрагмент потокового ответа.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnswerChunk {
+    /// Очередной кусок текста ответа.
+    Delta(String),
+    /// Ответ завершён; сведения о нём те же, что в `AskResult`.
+    Done {
+        source: AnswerSource,
+        system_prompt_applied: bool,
+        cached: bool,
+    },
+}
+
+/// Поток фрагментов ответа.
+pub type AnswerStream = LocalBoxStream<'static, Result<AnswerChunk, GatewayError>>;
+
+/// Порт для получения ответа по частям, по мере генерации.
+///
+/// Если backend не умеет отдавать ответ потоком, шлюз возвращает
+/// `GatewayError::StreamingUnsupported`, и вопрос отправляется обычным
+/// `ChatGateway::ask`.
+#[async_trait(?Send)]
+pub trait StreamingChatGateway {
+    async fn ask_stream(&self, request: AskRequest) -> Result<AnswerStream, GatewayError>;
 }
 
 /// Порт для проверки состояния backend.
@@ -33,3 +439,95 @@ pub trait ChatGateway {
 pub trait HealthGateway {
     async fn health(&self) -> Result<HealthStatus, GatewayError>;
 }
+
+/// Шлюз вопросов, отвечающий и целиком, и потоком.
+///
+/// Нужен для `Box<dyn ChatBackend>`: шлюз, выбранный во время работы,
+/// оборачивается теми же декораторами, что и конкретный клиент.
+pub trait ChatBackend: ChatGateway + StreamingChatGateway {}
+
+impl<G: ChatGateway + StreamingChatGateway + ?Sized> ChatBackend for G {}
+
+#[async_trait(?Send)]
+impl<G: ChatGateway + ?Sized> ChatGateway for Box<G> {
+    async fn ask(&self, request: AskRequest) -> Result<AskResult, GatewayError> {
+        (**self).ask(request).await
+    }
+}
+
+#[async_trait(?Send)]
+impl<G: StreamingChatGateway + ?Sized> StreamingChatGateway for Box<G> {
+    async fn ask_stream(&self, request: AskRequest) -> Result<AnswerStream, GatewayError> {
+        (**self).ask_stream(request).await
+    }
+}
+
+#[async_trait(?Send)]
+impl<G: HealthGateway + ?Sized> HealthGateway for Box<G> {
+    async fn health(&self) -> Result<HealthStatus, GatewayError> {
+        (**self).health().await
+    }
+}
+
+/// Порт для хранения истории вопросов и ответов.
+///
+/// Хранилище синхронное: в браузере это `localStorage`.
+pub trait HistoryRepository {
+    /// Все записи в порядке добавления (от старых к новым).
+    fn list(&self) -> Result<Vec<HistoryEntry>, StorageError>;
+    /// Добавляет запись в конец истории.
+    fn add(&self, entry: HistoryEntry) -> Result<(), StorageError>;
+    /// Удаляет запись; возвращает `false`, если записи не было.
+    fn remove(&self, id: HistoryId) -> Result<bool, StorageError>;
+}
+
+/// Порт для хранения очереди неотправленных вопросов.
+///
+/// Как и история, хранилище синхронное.
+pub trait OutboxRepository {
+    /// Все записи в порядке постановки в очередь.
+    fn list(&self) -> Result<Vec<PendingQuestion>, StorageError>;
+    /// Добавляет запись в конец очереди.
+    fn add(&self, item: PendingQuestion) -> Result<(), StorageError>;
+    /// Заменяет запись с тем же идентификатором; `false`, если её нет.
+    fn update(&self, item: PendingQuestion) -> Result<bool, StorageError>;
+    /// Удаляет запись; возвращает `false`, если записи не было.
+    fn remove(&self, id: OutboxId) -> Result<bool, StorageError>;
+}
+
+/// Порт для хранения текущей сессии.
+pub trait SessionRepository {
+    fn load(&self) -> Result<Option<Session>, StorageError>;
+    fn save(&self, session: &Session) -> Result<(), StorageError>;
+    fn clear(&self) -> Result<(), StorageError>;
+}
+
+/// Ответ в кэше: ключ запроса, результат и момент сохранения.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedAnswer {
+    pub key: String,
+    pub result: AskResult,
+    pub stored_at: Timestamp,
+}
+
+/// Порт для сохранения кэша ответов между сессиями.
+///
+/// Записи передаются целиком в порядке LRU: от давно не использованных
+/// к недавним.
+pub trait AnswerCacheStore {
+    fn load(&self) -> Result<Vec<CachedAnswer>, StorageError>;
+    fn save(&self, entries: &[CachedAnswer]) -> Result<(), StorageError>;
+}
+
+/// Порт для получения текущего времени.
+///
+/// Выделен отдельно, чтобы use‑cases можно было тестировать с «замороженными» часами.
+pub trait Clock {
+    fn now(&self) -> Timestamp;
+}
+
+/// Порт для асинхронного ожидания (паузы между повторами запроса).
+#[async_trait(?Send)]
+pub trait Sleeper {
+    async fn sleep(&self, millis: u32);
+}
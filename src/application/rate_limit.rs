@@ -0,0 +1,264 @@
+//! Ограничение частоты запросов на стороне клиента.
+//!
+//! `RateLimitedGateway` — декоратор над `ChatGateway`/`StreamingChatGateway`
+//! с алгоритмом «ведро токенов»: каждый запрос забирает токен, ведро
+//! пополняется с постоянной скоростью до `burst` токенов. Если токенов нет,
+//! запрос не отправляется, а шлюз возвращает `GatewayError::Throttled`
+//! со временем до появления следующего токена. Если потоковые ответы
+//! не поддерживаются, токен возвращается: вопрос уйдёт обычным запросом,
+//! и тот заберёт свой токен.
+//!
+//! Состояние ведра хранит `RateLimiter`: его клоны разделяют одно ведро,
+//! поэтому лимит действует на все декораторы, которые UI собирает
+//! на каждый запрос.
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use async_trait::async_trait;
+
+use crate::application::ports::{
+    AnswerStream, AskRequest, ChatGateway, Clock, GatewayError, StreamingChatGateway,
+};
+use crate::domain::{AskResult, Timestamp};
+
+/// Лимит запросов.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitPolicy {
+    /// Сколько запросов можно отправить подряд без ожидания.
+    pub burst: u32,
+    /// Сколько токенов ведро получает за минуту.
+    pub per_minute: u32,
+}
+
+impl Default for RateLimitPolicy {
+    fn default() -> Self {
+        Self {
+            burst: 3,
+            per_minute: 20,
+        }
+    }
+}
+
+impl RateLimitPolicy {
+    /// Сколько миллисекунд нужно на один токен.
+    fn millis_per_token(&self) -> f64 {
+        60_000.0 / self.per_minute.max(1) as f64
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    policy: RateLimitPolicy,
+    tokens: f64,
+    updated_at: Option<Timestamp>,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Timestamp) {
+        if let Some(updated_at) = self.updated_at {
+            let elapsed = now.as_millis().saturating_sub(updated_at.as_millis()) as f64;
+            self.tokens = (self.tokens + elapsed / self.policy.millis_per_token())
+                .min(self.policy.burst as f64);
+        }
+        self.updated_at = Some(now);
+    }
+}
+
+/// Общее ведро токенов.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    bucket: Rc<RefCell<Bucket>>,
+}
+
+impl RateLimiter {
+    /// Ведро создаётся полным.
+    pub fn new(policy: RateLimitPolicy) -> Self {
+        Self {
+            bucket: Rc::new(RefCell::new(Bucket {
+                policy,
+                tokens: policy.burst as f64,
+                updated_at: None,
+            })),
+        }
+    }
+
+    /// Забирает токен или возвращает, через сколько миллисекунд он появится.
+    pub fn try_acquire(&self, now: Timestamp) -> Result<(), u32> {
+        let mut bucket = self.bucket.borrow_mut();
+        bucket.refill(now);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        let missing = 1.0 - bucket.tokens;
+        Err((missing * bucket.policy.millis_per_token()).ceil() as u32)
+    }
+
+    /// Возвращает токен, если запрос так и не был отправлен.
+    fn release(&self) {
+        let mut bucket = self.bucket.borrow_mut();
+        bucket.tokens = (bucket.tokens + 1.0).min(bucket.policy.burst as f64);
+    }
+}
+
+/// Декоратор шлюза, не пропускающий запросы сверх лимита.
+pub struct RateLimitedGateway<G, C> {
+    inner: G,
+    limiter: RateLimiter,
+    clock: C,
+}
+
+impl<G, C: Clock> RateLimitedGateway<G, C> {
+    pub fn new(inner: G, limiter: RateLimiter, clock: C) -> Self {
+        Self {
+            inner,
+            limiter,
+            clock,
+        }
+    }
+
+    fn acquire(&self) -> Result<(), GatewayError> {
+        self.limiter
+            .try_acquire(self.clock.now())
+            .map_err(GatewayError::Throttled)
+    }
+}
+
+#[async_trait(?Send)]
+impl<G, C> ChatGateway for RateLimitedGateway<G, C>
+where
+    G: ChatGateway,
+    C: Clock,
+{
+    async fn ask(&self, request: AskRequest) -> Result<AskResult, GatewayError> {
+        self.acquire()?;
+        self.inner.ask(request).await
+    }
+}
+
+#[async_trait(?Send)]
+impl<G, C> StreamingChatGateway for RateLimitedGateway<G, C>
+where
+    G: StreamingChatGateway,
+    C: Clock,
+{
+    async fn ask_stream(&self, request: AskRequest) -> Result<AnswerStream, GatewayError> {
+        self.acquire()?;
+        let result = self.inner.ask_stream(request).await;
+        if let Err(GatewayError::StreamingUnsupported) = result {
+            self.limiter.release();
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::application::{ContinueConversationUseCase, UseCaseError};
+    use crate::domain::{AnswerSource, Conversation, Question};
+
+    #[derive(Default)]
+    struct CountingGateway {
+        calls: Rc<Cell<u32>>,
+    }
+
+    #[async_trait(?Send)]
+    impl ChatGateway for CountingGateway {
+        async fn ask(&self, _request: AskRequest) -> Result<AskResult, GatewayError> {
+            self.calls.set(self.calls.get() + 1);
+            Ok(AskResult {
+                answer: "ok".to_string(),
+                source: AnswerSource::Mock,
+                system_prompt_applied: false,
+                cached: false,
+            })
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl StreamingChatGateway for CountingGateway {
+        async fn ask_stream(&self, _request: AskRequest) -> Result<AnswerStream, GatewayError> {
+            Err(GatewayError::StreamingUnsupported)
+        }
+    }
+
+    #[derive(Clone)]
+    struct ManualClock(Rc<Cell<u64>>);
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Timestamp {
+            Timestamp::from_millis(self.0.get())
+        }
+    }
+
+    #[test]
+    fn requests_over_burst_are_throttled_until_refill() {
+        let time = Rc::new(Cell::new(1_000));
+        let gateway = RateLimitedGateway::new(
+            CountingGateway::default(),
+            RateLimiter::new(RateLimitPolicy {
+                burst: 2,
+                per_minute: 60,
+            }),
+            ManualClock(time.clone()),
+        );
+        let ask = || block_on(gateway.ask(AskRequest::new(Question::try_new("Вопрос").unwrap())));
+
+        assert!(ask().is_ok());
+        assert!(ask().is_ok());
+        assert_eq!(ask(), Err(GatewayError::Throttled(1_000)));
+        assert_eq!(gateway.inner.calls.get(), 2);
+
+        time.set(1_400);
+        assert_eq!(ask(), Err(GatewayError::Throttled(600)));
+        time.set(2_000);
+        assert!(ask().is_ok());
+        assert_eq!(gateway.inner.calls.get(), 3);
+    }
+
+    #[test]
+    fn streaming_fallback_takes_one_token_per_question() {
+        let time = Rc::new(Cell::new(1_000));
+        let calls = Rc::new(Cell::new(0));
+        let gateway = RateLimitedGateway::new(
+            CountingGateway {
+                calls: calls.clone(),
+            },
+            RateLimiter::new(RateLimitPolicy {
+                burst: 2,
+                per_minute: 60,
+            }),
+            ManualClock(time.clone()),
+        );
+        let usecase = ContinueConversationUseCase::new(gateway, ManualClock(time));
+        let mut conversation = Conversation::new();
+        let mut ask = |question: &str| {
+            block_on(usecase.execute_streaming(&mut conversation, question.to_string(), |_| {}))
+        };
+
+        assert!(ask("Первый вопрос").is_ok());
+        assert!(ask("Второй вопрос").is_ok());
+        assert_eq!(calls.get(), 2);
+        assert_eq!(
+            ask("Третий вопрос"),
+            Err(UseCaseError::Gateway(GatewayError::Throttled(1_000)))
+        );
+    }
+
+    #[test]
+    fn bucket_does_not_grow_past_burst() {
+        let limiter = RateLimiter::new(RateLimitPolicy {
+            burst: 1,
+            per_minute: 60,
+        });
+        assert_eq!(limiter.try_acquire(Timestamp::from_millis(0)), Ok(()));
+        let later = Timestamp::from_millis(600_000);
+        assert_eq!(limiter.try_acquire(later), Ok(()));
+        assert_eq!(limiter.try_acquire(later), Err(1_000));
+    }
+}
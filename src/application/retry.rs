@@ -0,0 +1,399 @@
+//! Повтор запросов с экспоненциальной задержкой.
+//!
+//! `RetryingGateway` — декоратор над любым `ChatGateway`/`HealthGateway`
+//! (и `StreamingChatGateway`, где повторяется только открытие потока):
+//! при временной ошибке он ждёт и повторяет запрос, пока не исчерпает
+//! число попыток из `RetryPolicy`. Задержка растёт экспоненциально и
+//! «размазывается» случайным разбросом (jitter), чтобы клиенты после сбоя
+//! не повторяли запросы одновременно.
+//!
+//! Отменённый запрос не повторяется: токен отмены проверяется перед
+//! каждой попыткой, а отмена во время ожидания прерывает его сразу.
+//!
+//! Ожидание идёт через порт `Sleeper`, а источник случайности подставляется
+//! функцией, поэтому поведение декоратора проверяется тестами без таймеров.
+use std::future::Future;
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use futures::channel::oneshot;
+use futures::future::{select, Either};
+
+use crate::application::ports::{
+    AnswerStream, AskRequest, CancellationToken, ChatGateway, GatewayError, HealthGateway, Sleeper,
+    StreamingChatGateway,
+};
+use crate::domain::{AskResult, HealthStatus};
+
+/// Политика повторов.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Общее число попыток, включая первую.
+    pub max_attempts: u32,
+    /// Задержка перед первым повтором.
+    pub initial_delay_ms: u32,
+    /// Во сколько раз растёт задержка с каждым повтором.
+    pub multiplier: f64,
+    /// Верхняя граница задержки.
+    pub max_delay_ms: u32,
+    /// Доля случайного разброса задержки: `0.2` — ±20%.
+    pub jitter: f64,
+    /// Какие ошибки имеет смысл повторять.
+    pub retryable: fn(&GatewayError) -> bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay_ms: 500,
+            multiplier: 2.0,
+            max_delay_ms: 5_000,
+            jitter: 0.2,
+            retryable: GatewayError::retryable,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Политика без повторов: ровно одна попытка.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Нужно ли повторить запрос после ошибки на попытке `attempt` (с 1).
+    pub fn should_retry(&self, attempt: u32, error: &GatewayError) -> bool {
+        attempt < self.max_attempts && (self.retryable)(error)
+    }
+
+    /// Задержка перед повтором номер `retry` (с 1).
+    ///
+    /// `random` — число из `[0, 1)`; `0.5` даёт задержку без разброса.
+    pub fn delay_ms(&self, retry: u32, random: f64) -> u32 {
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let base = (self.initial_delay_ms as f64 * self.multiplier.max(1.0).powi(exponent))
+            .min(self.max_delay_ms as f64);
+        let spread = self.jitter.clamp(0.0, 1.0) * (2.0 * random.clamp(0.0, 1.0) - 1.0);
+        (base * (1.0 + spread))
+            .round()
+            .clamp(0.0, self.max_delay_ms as f64) as u32
+    }
+}
+
+/// Сведения о предстоящем повторе — для отображения в UI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryAttempt {
+    /// Номер следующей попытки (с 2).
+    pub attempt: u32,
+    pub max_attempts: u32,
+    /// Сколько ждать перед попыткой.
+    pub delay_ms: u32,
+    /// Ошибка предыдущей попытки.
+    pub error: GatewayError,
+}
+
+/// Наблюдатель за повторами.
+pub type RetryObserver = Rc<dyn Fn(&RetryAttempt)>;
+
+/// Декоратор шлюза, повторяющий запросы при временных ошибках.
+pub struct RetryingGateway<G, S> {
+    inner: G,
+    sleeper: S,
+    policy: RetryPolicy,
+    random: fn() -> f64,
+    observer: Option<RetryObserver>,
+}
+
+impl<G, S> RetryingGateway<G, S>
+where
+    S: Sleeper,
+{
+    /// Оборачивает шлюз с политикой по умолчанию и без разброса задержек.
+    pub fn new(inner: G, sleeper: S) -> Self {
+        Self {
+            inner,
+            sleeper,
+            policy: RetryPolicy::default(),
+            random: || 0.5,
+            observer: None,
+        }
+    }
+
+    pub fn with_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Источник случайных чисел из `[0, 1)` для разброса задержек.
+    pub fn with_random(mut self, random: fn() -> f64) -> Self {
+        self.random = random;
+        self
+    }
+
+    /// Вызывается перед каждым повтором.
+    pub fn with_observer(mut self, observer: RetryObserver) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    async fn run<T, F, Fut>(
+        &self,
+        cancellation: &CancellationToken,
+        mut call: F,
+    ) -> Result<T, GatewayError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, GatewayError>>,
+    {
+        let mut attempt = 1;
+        loop {
+            if cancellation.is_cancelled() {
+                return Err(GatewayError::Cancelled);
+            }
+            let error = match call().await {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+            if !self.policy.should_retry(attempt, &error) {
+                return Err(error);
+            }
+
+            let delay_ms = self.policy.delay_ms(attempt, (self.random)());
+            attempt += 1;
+            if let Some(observer) = &self.observer {
+                observer(&RetryAttempt {
+                    attempt,
+                    max_attempts: self.policy.max_attempts,
+                    delay_ms,
+                    error,
+                });
+            }
+            let (cancel, cancelled) = oneshot::channel();
+            cancellation.on_cancel(move || {
+                let _ = cancel.send(());
+            });
+            match select(cancelled, self.sleeper.sleep(delay_ms)).await {
+                Either::Left((Ok(()), _)) => return Err(GatewayError::Cancelled),
+                Either::Left((Err(_), sleep)) => sleep.await,
+                Either::Right(_) => {}
+            }
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<G, S> ChatGateway for RetryingGateway<G, S>
+where
+    G: ChatGateway,
+    S: Sleeper,
+{
+    async fn ask(&self, request: AskRequest) -> Result<AskResult, GatewayError> {
+        self.run(&request.cancellation, || self.inner.ask(request.clone()))
+            .await
+    }
+}
+
+#[async_trait(?Send)]
+impl<G, S> StreamingChatGateway for RetryingGateway<G, S>
+where
+    G: StreamingChatGateway,
+    S: Sleeper,
+{
+    async fn ask_stream(&self, request: AskRequest) -> Result<AnswerStream, GatewayError> {
+        self.run(&request.cancellation, || {
+            self.inner.ask_stream(request.clone())
+        })
+        .await
+    }
+}
+
+#[async_trait(?Send)]
+impl<G, S> HealthGateway for RetryingGateway<G, S>
+where
+    G: HealthGateway,
+    S: Sleeper,
+{
+    async fn health(&self) -> Result<HealthStatus, GatewayError> {
+        self.run(&CancellationToken::new(), || self.inner.health())
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::application::ports::{ApiError, ApiErrorCode};
+    use crate::domain::{AnswerSource, Question};
+
+    /// Шлюз, отдающий заранее заданные ошибки, а затем успешный ответ.
+    struct FlakyGateway {
+        failures: RefCell<Vec<GatewayError>>,
+        calls: RefCell<u32>,
+    }
+
+    impl FlakyGateway {
+        fn new(failures: Vec<GatewayError>) -> Self {
+            Self {
+                failures: RefCell::new(failures),
+                calls: RefCell::new(0),
+            }
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl ChatGateway for FlakyGateway {
+        async fn ask(&self, _request: AskRequest) -> Result<AskResult, GatewayError> {
+            *self.calls.borrow_mut() += 1;
+            let mut failures = self.failures.borrow_mut();
+            if failures.is_empty() {
+                Ok(AskResult {
+                    answer: "ok".to_string(),
+                    source: AnswerSource::Mock,
+                    system_prompt_applied: false,
+                    cached: false,
+                })
+            } else {
+                Err(failures.remove(0))
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingSleeper {
+        delays: RefCell<Vec<u32>>,
+    }
+
+    #[async_trait(?Send)]
+    impl Sleeper for &RecordingSleeper {
+        async fn sleep(&self, millis: u32) {
+            self.delays.borrow_mut().push(millis);
+        }
+    }
+
+    fn request() -> AskRequest {
+        AskRequest::new(Question::try_new("Что такое Rust?").unwrap())
+    }
+
+    fn network() -> GatewayError {
+        GatewayError::Network("timeout".to_string())
+    }
+
+    #[test]
+    fn retries_transient_errors_with_backoff() {
+        let sleeper = RecordingSleeper::default();
+        let attempts = Rc::new(RefCell::new(Vec::new()));
+        let observed = attempts.clone();
+        let gateway = RetryingGateway::new(
+            FlakyGateway::new(vec![
+                network(),
+                GatewayError::Api(ApiError {
+                    status: Some(503),
+                    code: None,
+                    message: "Service Unavailable".to_string(),
+                    body: String::new(),
+                }),
+            ]),
+            &sleeper,
+        )
+        .with_observer(Rc::new(move |attempt: &RetryAttempt| {
+            observed
+                .borrow_mut()
+                .push((attempt.attempt, attempt.max_attempts));
+        }));
+
+        let result = block_on(gateway.ask(request())).unwrap();
+
+        assert_eq!(result.answer, "ok");
+        assert_eq!(*gateway.inner.calls.borrow(), 3);
+        assert_eq!(*sleeper.delays.borrow(), vec![500, 1_000]);
+        assert_eq!(*attempts.borrow(), vec![(2, 3), (3, 3)]);
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts_and_skips_permanent_errors() {
+        let sleeper = RecordingSleeper::default();
+        let gateway = RetryingGateway::new(
+            FlakyGateway::new(vec![network(), network(), network(), network()]),
+            &sleeper,
+        );
+        assert_eq!(block_on(gateway.ask(request())), Err(network()));
+        assert_eq!(*gateway.inner.calls.borrow(), 3);
+
+        let gateway = RetryingGateway::new(FlakyGateway::new(vec![network()]), &sleeper)
+            .with_policy(RetryPolicy::none());
+        assert_eq!(block_on(gateway.ask(request())), Err(network()));
+        assert_eq!(*gateway.inner.calls.borrow(), 1);
+
+        let sleeper = RecordingSleeper::default();
+        let api = GatewayError::Api(ApiError {
+            status: Some(400),
+            code: Some(ApiErrorCode::EmptyQuestion),
+            message: "Question cannot be empty".to_string(),
+            body: String::new(),
+        });
+        let gateway = RetryingGateway::new(FlakyGateway::new(vec![api.clone()]), &sleeper);
+        assert_eq!(block_on(gateway.ask(request())), Err(api));
+        assert_eq!(*gateway.inner.calls.borrow(), 1);
+        assert!(sleeper.delays.borrow().is_empty());
+    }
+
+    #[test]
+    fn cancelled_request_is_not_retried() {
+        let sleeper = RecordingSleeper::default();
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        let gateway = RetryingGateway::new(FlakyGateway::new(vec![network(), network()]), &sleeper)
+            .with_observer(Rc::new(move |_: &RetryAttempt| cancel.cancel()));
+
+        let result = block_on(gateway.ask(request().with_cancellation(token)));
+
+        assert_eq!(result, Err(GatewayError::Cancelled));
+        assert_eq!(*gateway.inner.calls.borrow(), 1);
+    }
+
+    /// Ожидание, которое заканчивается только отменой запроса.
+    struct StopDuringSleep(CancellationToken);
+
+    #[async_trait(?Send)]
+    impl Sleeper for StopDuringSleep {
+        async fn sleep(&self, _millis: u32) {
+            self.0.cancel();
+            futures::future::pending::<()>().await;
+        }
+    }
+
+    #[test]
+    fn cancellation_interrupts_backoff() {
+        let token = CancellationToken::new();
+        let gateway = RetryingGateway::new(
+            FlakyGateway::new(vec![network(), network()]),
+            StopDuringSleep(token.clone()),
+        );
+
+        let result = block_on(gateway.ask(request().with_cancellation(token)));
+
+        assert_eq!(result, Err(GatewayError::Cancelled));
+        assert_eq!(*gateway.inner.calls.borrow(), 1);
+    }
+
+    #[test]
+    fn delay_grows_exponentially_with_jitter_and_cap() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay_ms(1, 0.5), 500);
+        assert_eq!(policy.delay_ms(2, 0.5), 1_000);
+        assert_eq!(policy.delay_ms(3, 0.5), 2_000);
+        assert_eq!(policy.delay_ms(10, 0.5), 5_000);
+        assert_eq!(policy.delay_ms(1, 0.0), 400);
+        assert_eq!(policy.delay_ms(1, 1.0), 600);
+        // Разброс не выводит задержку за верхнюю границу.
+        assert_eq!(policy.delay_ms(10, 1.0), 5_000);
+    }
+}
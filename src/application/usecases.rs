@@ -2,51 +2,295 @@
 //!
 //! Каждая структура в этом файле описывает конкретный сценарий:
 //! - `AskQuestionUseCase` — отправка вопроса;
-//! - `CheckHealthUseCase` — проверка доступности API.
+//! - `ContinueConversationUseCase` — продолжение диалога с учётом контекста,
+//!   в том числе с потоковым получением ответа;
+//! - `CheckHealthUseCase` — проверка доступности API;
+//! - `RecordExchangeUseCase` — запись обмена в историю;
+//! - `ManageHistoryUseCase` — просмотр и удаление записей истории;
+//! - `SearchHistoryUseCase` — полнотекстовый поиск по истории;
+//! - `OutboxUseCase` — очередь вопросов, не отправленных без связи;
+//! - `AuthenticateUseCase` — вход, выход и продление сессии.
 //!
 //! Use‑cases используют только порты, поэтому их легко тестировать с фейковыми
 //! реализациями.
+use std::collections::BTreeMap;
+
+use futures::StreamExt;
 use thiserror::Error;
 
-use crate::application::ports::{ChatGateway, GatewayError, HealthGateway};
-use crate::domain::{AskResult, DomainError, HealthStatus, Question};
+use crate::application::ports::{
+    AnswerChunk, AskRequest, CancellationToken, ChatGateway, Clock, GatewayError, HealthGateway,
+    HistoryRepository, OutboxRepository, SessionGateway, SessionRepository, StorageError,
+    StreamingChatGateway,
+};
+use crate::domain::{
+    search_history, AskResult, Conversation, DomainError, GenerationSettings, HealthStatus,
+    HistoryEntry, HistoryId, HistorySearchHit, LoginCredentials, OutboxId, PendingQuestion,
+    PromptTemplate, Question, QuestionPolicy, SearchQuery, Session, SystemPrompt,
+};
 
 /// Ошибка сценария использования.
 ///
-/// Делит ошибки на два типа:
+/// Делит ошибки на типы:
 /// - **Domain** — нарушения правил предметной области;
-/// - **Gateway** — проблемы взаимодействия с API.
+/// - **Gateway** — проблемы взаимодействия с API;
+/// - **Storage** — проблемы локального хранилища;
+/// - **NotAuthenticated** — нет сессии или её нельзя продлить, нужен вход.
 #[derive(Debug, Error, Clone, PartialEq, Eq)]
 pub enum UseCaseError {
     #[error("Ошибка домена: {0}")]
     Domain(DomainError),
     #[error("Ошибка шлюза: {0}")]
     Gateway(GatewayError),
+    #[error("Ошибка хранилища: {0}")]
+    Storage(StorageError),
+    #[error("Требуется вход")]
+    NotAuthenticated,
 }
 
 /// Сценарий "задать вопрос".
 ///
-/// Принимает строку, проверяет её на валидность и передаёт в gateway.
+/// Принимает строку, нормализует и проверяет её по `QuestionPolicy`
+/// и передаёт в gateway.
 pub struct AskQuestionUseCase<G: ChatGateway> {
     gateway: G,
+    policy: QuestionPolicy,
+    generation: GenerationSettings,
+    system_prompt: Option<SystemPrompt>,
+    cancellation: CancellationToken,
+    bypass_cache: bool,
 }
 
 impl<G: ChatGateway> AskQuestionUseCase<G> {
-    /// Создаёт use‑case с заданной реализацией gateway.
+    /// Создаёт use‑case с заданной реализацией gateway и политикой по умолчанию.
     pub fn new(gateway: G) -> Self {
-        Self { gateway }
+        Self {
+            gateway,
+            policy: QuestionPolicy::default(),
+            generation: GenerationSettings::default(),
+            system_prompt: None,
+            cancellation: CancellationToken::new(),
+            bypass_cache: false,
+        }
+    }
+
+    /// Задаёт политику проверки вопросов.
+    pub fn with_policy(mut self, policy: QuestionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Задаёт параметры генерации, которые уйдут вместе с вопросом.
+    pub fn with_generation(mut self, generation: GenerationSettings) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    /// Задаёт системный промпт для запроса (`None` — промпт backend).
+    pub fn with_system_prompt(mut self, system_prompt: Option<SystemPrompt>) -> Self {
+        self.system_prompt = system_prompt;
+        self
+    }
+
+    /// Задаёт токен, которым пользователь может остановить запрос.
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Просит получить свежий ответ, минуя кэш ответов.
+    pub fn with_cache_bypass(mut self, bypass_cache: bool) -> Self {
+        self.bypass_cache = bypass_cache;
+        self
     }
 
     /// Выполняет сценарий: валидирует вопрос и отправляет его в API.
     pub async fn execute(&self, question: String) -> Result<AskResult, UseCaseError> {
-        let question = Question::try_new(question).map_err(UseCaseError::Domain)?;
+        let question = self.policy.apply(question).map_err(UseCaseError::Domain)?;
+        self.send(question).await
+    }
+
+    /// Выполняет сценарий для шаблона: подставляет значения переменных
+    /// и отправляет получившийся вопрос в API.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub async fn execute_template(
+        &self,
+        template: &PromptTemplate,
+        values: &BTreeMap<String, String>,
+    ) -> Result<AskResult, UseCaseError> {
+        let question = template
+            .render(values, &self.policy)
+            .map_err(UseCaseError::Domain)?;
+        self.send(question).await
+    }
+
+    async fn send(&self, question: Question) -> Result<AskResult, UseCaseError> {
+        let request = AskRequest::new(question)
+            .with_generation(self.generation.clone())
+            .with_system_prompt(self.system_prompt.clone())
+            .with_cancellation(self.cancellation.clone())
+            .with_cache_bypass(self.bypass_cache);
         self.gateway
-            .ask(question)
+            .ask(request)
             .await
             .map_err(UseCaseError::Gateway)
     }
 }
 
+/// Сценарий "продолжить диалог".
+///
+/// Отправляет вопрос вместе со всеми предыдущими сообщениями диалога,
+/// а после успешного ответа добавляет обмен в `Conversation`.
+/// При ошибке диалог не меняется.
+pub struct ContinueConversationUseCase<G: ChatGateway, C: Clock> {
+    gateway: G,
+    clock: C,
+    policy: QuestionPolicy,
+    generation: GenerationSettings,
+    system_prompt: Option<SystemPrompt>,
+    cancellation: CancellationToken,
+    bypass_cache: bool,
+}
+
+impl<G: ChatGateway, C: Clock> ContinueConversationUseCase<G, C> {
+    /// Создаёт use‑case с заданными gateway и часами и политикой по умолчанию.
+    pub fn new(gateway: G, clock: C) -> Self {
+        Self {
+            gateway,
+            clock,
+            policy: QuestionPolicy::default(),
+            generation: GenerationSettings::default(),
+            system_prompt: None,
+            cancellation: CancellationToken::new(),
+            bypass_cache: false,
+        }
+    }
+
+    /// Задаёт политику проверки вопросов.
+    pub fn with_policy(mut self, policy: QuestionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Задаёт параметры генерации, которые уйдут вместе с вопросом.
+    pub fn with_generation(mut self, generation: GenerationSettings) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    /// Задаёт системный промпт для запроса (`None` — промпт backend).
+    pub fn with_system_prompt(mut self, system_prompt: Option<SystemPrompt>) -> Self {
+        self.system_prompt = system_prompt;
+        self
+    }
+
+    /// Задаёт токен, которым пользователь может остановить запрос.
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Просит получить свежий ответ, минуя кэш ответов.
+    pub fn with_cache_bypass(mut self, bypass_cache: bool) -> Self {
+        self.bypass_cache = bypass_cache;
+        self
+    }
+
+    /// Выполняет сценарий: валидирует вопрос, отправляет его с контекстом
+    /// и записывает обмен в диалог.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub async fn execute(
+        &self,
+        conversation: &mut Conversation,
+        question: String,
+    ) -> Result<AskResult, UseCaseError> {
+        let question = self.policy.apply(question).map_err(UseCaseError::Domain)?;
+        let asked_at = self.clock.now();
+        let result = self
+            .gateway
+            .ask(self.request(&question, conversation))
+            .await
+            .map_err(UseCaseError::Gateway)?;
+        conversation.record_exchange(&question, asked_at, &result, self.clock.now());
+        Ok(result)
+    }
+
+    /// Как `execute`, но получает ответ потоком: `on_progress` вызывается
+    /// с уже полученной частью ответа после каждого фрагмента.
+    ///
+    /// Если backend не поддерживает потоковые ответы, вопрос отправляется
+    /// обычным запросом и `on_progress` не вызывается.
+    pub async fn execute_streaming(
+        &self,
+        conversation: &mut Conversation,
+        question: String,
+        on_progress: impl Fn(&str),
+    ) -> Result<AskResult, UseCaseError>
+    where
+        G: StreamingChatGateway,
+    {
+        let question = self.policy.apply(question).map_err(UseCaseError::Domain)?;
+        let asked_at = self.clock.now();
+        let request = self.request(&question, conversation);
+        let result = stream_answer(&self.gateway, request, on_progress)
+            .await
+            .map_err(UseCaseError::Gateway)?;
+        conversation.record_exchange(&question, asked_at, &result, self.clock.now());
+        Ok(result)
+    }
+
+    fn request(&self, question: &Question, conversation: &Conversation) -> AskRequest {
+        AskRequest::new(question.clone())
+            .with_context(conversation.messages().to_vec())
+            .with_generation(self.generation.clone())
+            .with_system_prompt(self.system_prompt.clone())
+            .with_cancellation(self.cancellation.clone())
+            .with_cache_bypass(self.bypass_cache)
+    }
+}
+
+/// Собирает потоковый ответ в `AskResult`, при необходимости откатываясь
+/// на обычный запрос.
+///
+/// Поток, оборвавшийся без завершающего фрагмента, считается некорректным
+/// ответом: неполный текст в диалог не попадает.
+async fn stream_answer<G>(
+    gateway: &G,
+    request: AskRequest,
+    on_progress: impl Fn(&str),
+) -> Result<AskResult, GatewayError>
+where
+    G: ChatGateway + StreamingChatGateway,
+{
+    let mut stream = match gateway.ask_stream(request.clone()).await {
+        Ok(stream) => stream,
+        Err(GatewayError::StreamingUnsupported) => return gateway.ask(request).await,
+        Err(error) => return Err(error),
+    };
+    let mut answer = String::new();
+    while let Some(chunk) = stream.next().await {
+        match chunk? {
+            AnswerChunk::Delta(text) => {
+                answer.push_str(&text);
+                on_progress(&answer);
+            }
+            AnswerChunk::Done {
+                source,
+                system_prompt_applied,
+                cached,
+            } => {
+                return Ok(AskResult {
+                    answer,
+                    source,
+                    system_prompt_applied,
+                    cached,
+                })
+            }
+        }
+    }
+    Err(GatewayError::InvalidPayload)
+}
+
 /// Сценарий "проверить состояние API".
 pub struct CheckHealthUseCase<G: HealthGateway> {
     gateway: G,
@@ -67,12 +311,377 @@ impl<G: HealthGateway> CheckHealthUseCase<G> {
     }
 }
 
+/// Сценарий "записать обмен в историю".
+///
+/// Вопрос нормализуется той же политикой, что и при отправке, поэтому
+/// в истории хранится именно отправленный текст. Если записей больше
+/// лимита, самые старые удаляются.
+pub struct RecordExchangeUseCase<R: HistoryRepository, C: Clock> {
+    repository: R,
+    clock: C,
+    policy: QuestionPolicy,
+    limit: usize,
+}
+
+impl<R: HistoryRepository, C: Clock> RecordExchangeUseCase<R, C> {
+    /// Лимит записей по умолчанию.
+    pub const DEFAULT_LIMIT: usize = 200;
+
+    /// Создаёт use‑case с политикой и лимитом по умолчанию.
+    pub fn new(repository: R, clock: C) -> Self {
+        Self {
+            repository,
+            clock,
+            policy: QuestionPolicy::default(),
+            limit: Self::DEFAULT_LIMIT,
+        }
+    }
+
+    /// Задаёт политику проверки вопросов.
+    pub fn with_policy(mut self, policy: QuestionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Задаёт максимальное число хранимых записей (не меньше одной).
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit.max(1);
+        self
+    }
+
+    /// Выполняет сценарий и возвращает созданную запись.
+    pub fn execute(
+        &self,
+        question: &str,
+        result: &AskResult,
+    ) -> Result<HistoryEntry, UseCaseError> {
+        let question = self.policy.apply(question).map_err(UseCaseError::Domain)?;
+        let entries = self.repository.list().map_err(UseCaseError::Storage)?;
+        let last = entries.iter().map(|entry| entry.id).max();
+        let recorded_at = self.clock.now();
+        let entry = HistoryEntry {
+            id: HistoryEntry::next_id(last, recorded_at),
+            question,
+            result: result.clone(),
+            recorded_at,
+        };
+        self.repository
+            .add(entry.clone())
+            .map_err(UseCaseError::Storage)?;
+
+        let overflow = (entries.len() + 1).saturating_sub(self.limit);
+        for old in entries.iter().take(overflow) {
+            self.repository
+                .remove(old.id)
+                .map_err(UseCaseError::Storage)?;
+        }
+        Ok(entry)
+    }
+}
+
+/// Сценарий "просмотреть историю": список записей и удаление.
+pub struct ManageHistoryUseCase<R: HistoryRepository> {
+    repository: R,
+}
+
+impl<R: HistoryRepository> ManageHistoryUseCase<R> {
+    /// Создаёт use‑case с заданным хранилищем.
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    /// Записи от новых к старым.
+    pub fn list(&self) -> Result<Vec<HistoryEntry>, UseCaseError> {
+        let mut entries = self.repository.list().map_err(UseCaseError::Storage)?;
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.id));
+        Ok(entries)
+    }
+
+    /// Удаляет запись; отсутствие записи ошибкой не считается.
+    pub fn delete(&self, id: HistoryId) -> Result<(), UseCaseError> {
+        self.repository
+            .remove(id)
+            .map(|_| ())
+            .map_err(UseCaseError::Storage)
+    }
+}
+
+/// Сценарий "найти в истории".
+///
+/// Ищет по тем записям, которые уже загружены в UI, а не перечитывает
+/// хранилище: так найденное всегда совпадает с показанным списком.
+pub struct SearchHistoryUseCase<'a> {
+    entries: &'a [HistoryEntry],
+}
+
+impl<'a> SearchHistoryUseCase<'a> {
+    /// Создаёт use‑case над списком записей.
+    pub fn new(entries: &'a [HistoryEntry]) -> Self {
+        Self { entries }
+    }
+
+    /// Ищет записи по запросу; пустой запрос ничего не находит.
+    pub fn execute(&self, query: &str) -> Vec<HistorySearchHit> {
+        let query = SearchQuery::parse(query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+        search_history(self.entries, &query)
+    }
+}
+
+/// Итог отправки очереди.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OutboxReplay {
+    /// Отправленные вопросы с ответами, в порядке очереди.
+    pub sent: Vec<(PendingQuestion, AskResult)>,
+    /// Сколько вопросов осталось в очереди.
+    pub remaining: usize,
+}
+
+/// Сценарий "очередь неотправленных вопросов".
+///
+/// Вопрос, который не удалось отправить из‑за недоступного backend,
+/// сохраняется в очереди и отправляется позже отдельным запросом, без
+/// контекста диалога. Отправленный вопрос из очереди удаляется, а
+/// неудачная попытка запоминается в записи.
+pub struct OutboxUseCase<R: OutboxRepository, C: Clock> {
+    repository: R,
+    clock: C,
+    policy: QuestionPolicy,
+}
+
+impl<R: OutboxRepository, C: Clock> OutboxUseCase<R, C> {
+    /// Создаёт use‑case с политикой по умолчанию.
+    pub fn new(repository: R, clock: C) -> Self {
+        Self {
+            repository,
+            clock,
+            policy: QuestionPolicy::default(),
+        }
+    }
+
+    /// Задаёт политику проверки вопросов.
+    pub fn with_policy(mut self, policy: QuestionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Ставит вопрос в конец очереди.
+    pub fn enqueue(&self, question: &str) -> Result<PendingQuestion, UseCaseError> {
+        let question = self.policy.apply(question).map_err(UseCaseError::Domain)?;
+        let items = self.repository.list().map_err(UseCaseError::Storage)?;
+        let last = items.iter().map(|item| item.id).max();
+        let item = PendingQuestion::new(question, last, self.clock.now());
+        self.repository
+            .add(item.clone())
+            .map_err(UseCaseError::Storage)?;
+        Ok(item)
+    }
+
+    /// Записи в порядке очереди.
+    pub fn list(&self) -> Result<Vec<PendingQuestion>, UseCaseError> {
+        self.repository.list().map_err(UseCaseError::Storage)
+    }
+
+    /// Убирает вопрос из очереди без отправки.
+    pub fn cancel(&self, id: OutboxId) -> Result<(), UseCaseError> {
+        self.repository
+            .remove(id)
+            .map(|_| ())
+            .map_err(UseCaseError::Storage)
+    }
+
+    /// Отправляет один вопрос из очереди.
+    pub async fn send<G: ChatGateway>(
+        &self,
+        gateway: &G,
+        item: &PendingQuestion,
+    ) -> Result<AskResult, UseCaseError> {
+        match gateway.ask(AskRequest::new(item.question.clone())).await {
+            Ok(result) => {
+                self.repository
+                    .remove(item.id)
+                    .map_err(UseCaseError::Storage)?;
+                Ok(result)
+            }
+            Err(error) => {
+                self.repository
+                    .update(item.failed(error.to_string()))
+                    .map_err(UseCaseError::Storage)?;
+                Err(UseCaseError::Gateway(error))
+            }
+        }
+    }
+
+    /// Отправляет очередь по порядку.
+    ///
+    /// Если backend снова недоступен, остальные вопросы не отправляются:
+    /// они дождутся следующего восстановления связи.
+    pub async fn replay<G: ChatGateway>(&self, gateway: &G) -> Result<OutboxReplay, UseCaseError> {
+        let items = self.list()?;
+        let mut report = OutboxReplay::default();
+        for item in items {
+            match self.send(gateway, &item).await {
+                Ok(result) => report.sent.push((item, result)),
+                Err(UseCaseError::Gateway(error)) if error.is_unreachable() => break,
+                Err(UseCaseError::Storage(error)) => return Err(UseCaseError::Storage(error)),
+                Err(_) => {}
+            }
+        }
+        report.remaining = self.list()?.len();
+        Ok(report)
+    }
+}
+
+/// Сценарий "вход и сессия пользователя".
+///
+/// Хранит сессию в `SessionRepository` и продлевает её заранее — за
+/// `refresh_margin_ms` до истечения токена. Если backend отверг токен
+/// обновления, сессия забывается и нужен новый вход.
+pub struct AuthenticateUseCase<G: SessionGateway, R: SessionRepository, C: Clock> {
+    gateway: G,
+    repository: R,
+    clock: C,
+    refresh_margin_ms: u64,
+}
+
+impl<G: SessionGateway, R: SessionRepository, C: Clock> AuthenticateUseCase<G, R, C> {
+    /// За сколько миллисекунд до истечения токен обновляется по умолчанию.
+    pub const DEFAULT_REFRESH_MARGIN_MS: u64 = 60_000;
+
+    /// Создаёт use‑case с запасом обновления по умолчанию.
+    pub fn new(gateway: G, repository: R, clock: C) -> Self {
+        Self {
+            gateway,
+            repository,
+            clock,
+            refresh_margin_ms: Self::DEFAULT_REFRESH_MARGIN_MS,
+        }
+    }
+
+    /// Задаёт запас времени до истечения токена, когда пора его обновить.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn with_refresh_margin(mut self, margin_ms: u64) -> Self {
+        self.refresh_margin_ms = margin_ms;
+        self
+    }
+
+    /// Сохранённая сессия как есть, без продления.
+    pub fn current(&self) -> Result<Option<Session>, UseCaseError> {
+        self.repository.load().map_err(UseCaseError::Storage)
+    }
+
+    /// Входит по логину и паролю и запоминает сессию.
+    pub async fn login(&self, username: &str, password: &str) -> Result<Session, UseCaseError> {
+        let credentials =
+            LoginCredentials::try_new(username, password).map_err(UseCaseError::Domain)?;
+        let session = self
+            .gateway
+            .login(&credentials)
+            .await
+            .map_err(UseCaseError::Gateway)?;
+        self.repository
+            .save(&session)
+            .map_err(UseCaseError::Storage)?;
+        Ok(session)
+    }
+
+    /// Завершает сессию.
+    ///
+    /// Локально сессия забывается в любом случае; ошибка backend
+    /// (например, он уже забыл сессию) выход не отменяет.
+    pub async fn logout(&self) -> Result<(), UseCaseError> {
+        let session = self.current()?;
+        self.repository.clear().map_err(UseCaseError::Storage)?;
+        if let Some(session) = session {
+            let _ = self.gateway.logout(&session).await;
+        }
+        Ok(())
+    }
+
+    /// Продлевает сессию по токену обновления.
+    pub async fn refresh(&self) -> Result<Session, UseCaseError> {
+        let session = self.current()?.ok_or(UseCaseError::NotAuthenticated)?;
+        if !session.can_refresh() {
+            return Err(self.forget()?);
+        }
+        match self.gateway.refresh(&session).await {
+            Ok(renewed) => {
+                self.repository
+                    .save(&renewed)
+                    .map_err(UseCaseError::Storage)?;
+                Ok(renewed)
+            }
+            Err(error) if error.requires_auth() => {
+                self.forget()?;
+                Err(UseCaseError::Gateway(error))
+            }
+            Err(error) => Err(UseCaseError::Gateway(error)),
+        }
+    }
+
+    /// Сессия, пригодная для запроса: при необходимости продлённая.
+    ///
+    /// Если продлить не удалось из‑за временной ошибки, а токен ещё
+    /// действует, возвращается текущая сессия. Истёкшую сессию без токена
+    /// обновления продлить нельзя — она забывается.
+    pub async fn active_session(&self) -> Result<Option<Session>, UseCaseError> {
+        let Some(session) = self.current()? else {
+            return Ok(None);
+        };
+        let now = self.clock.now();
+        if !session.needs_refresh(now, self.refresh_margin_ms) {
+            return Ok(Some(session));
+        }
+        if !session.can_refresh() {
+            if session.is_expired(now) {
+                return Err(self.forget()?);
+            }
+            return Ok(Some(session));
+        }
+        match self.refresh().await {
+            Ok(renewed) => Ok(Some(renewed)),
+            Err(UseCaseError::Gateway(error))
+                if !error.requires_auth() && !session.is_expired(now) =>
+            {
+                Ok(Some(session))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Через сколько миллисекунд сессию пора продлить; `None`, если
+    /// срок действия неизвестен или продлить её нельзя.
+    pub fn refresh_delay_ms(&self, session: &Session) -> Option<u64> {
+        if !session.can_refresh() {
+            return None;
+        }
+        let refresh_at = session.refresh_at(self.refresh_margin_ms)?.as_millis();
+        Some(refresh_at.saturating_sub(self.clock.now().as_millis()))
+    }
+
+    /// Забывает сессию; возвращает ошибку «нужен вход».
+    fn forget(&self) -> Result<UseCaseError, UseCaseError> {
+        self.repository.clear().map_err(UseCaseError::Storage)?;
+        Ok(UseCaseError::NotAuthenticated)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
     use async_trait::async_trait;
     use futures::executor::block_on;
 
+    use crate::application::ports::{AnswerStream, ApiError};
+
+    use crate::domain::{AnswerSource, MessageRole, ServiceState, Temperature, Timestamp};
+
     #[derive(Clone)]
     struct FakeChatGateway {
         result: Result<AskResult, GatewayError>,
@@ -80,11 +689,98 @@ mod tests {
 
     #[async_trait(?Send)]
     impl ChatGateway for FakeChatGateway {
-        async fn ask(&self, _question: Question) -> Result<AskResult, GatewayError> {
+        async fn ask(&self, _request: AskRequest) -> Result<AskResult, GatewayError> {
             self.result.clone()
         }
     }
 
+    /// Gateway, запоминающий полученные запросы.
+    #[derive(Clone, Default)]
+    struct RecordingChatGateway {
+        requests: Rc<RefCell<Vec<AskRequest>>>,
+    }
+
+    #[async_trait(?Send)]
+    impl ChatGateway for RecordingChatGateway {
+        async fn ask(&self, request: AskRequest) -> Result<AskResult, GatewayError> {
+            let answer = format!("ответ на: {}", request.question.as_str());
+            self.requests.borrow_mut().push(request);
+            Ok(AskResult {
+                answer,
+                source: AnswerSource::Mock,
+                system_prompt_applied: false,
+                cached: false,
+            })
+        }
+    }
+
+    struct FixedClock(u64);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> Timestamp {
+            Timestamp::from_millis(self.0)
+        }
+    }
+
+    /// Хранилище истории в памяти.
+    #[derive(Clone, Default)]
+    struct InMemoryHistory {
+        entries: Rc<RefCell<Vec<HistoryEntry>>>,
+    }
+
+    impl HistoryRepository for InMemoryHistory {
+        fn list(&self) -> Result<Vec<HistoryEntry>, StorageError> {
+            Ok(self.entries.borrow().clone())
+        }
+
+        fn add(&self, entry: HistoryEntry) -> Result<(), StorageError> {
+            self.entries.borrow_mut().push(entry);
+            Ok(())
+        }
+
+        fn remove(&self, id: HistoryId) -> Result<bool, StorageError> {
+            let mut entries = self.entries.borrow_mut();
+            let before = entries.len();
+            entries.retain(|entry| entry.id != id);
+            Ok(entries.len() != before)
+        }
+    }
+
+    /// Очередь неотправленных вопросов в памяти.
+    #[derive(Clone, Default)]
+    struct InMemoryOutbox {
+        items: Rc<RefCell<Vec<PendingQuestion>>>,
+    }
+
+    impl OutboxRepository for InMemoryOutbox {
+        fn list(&self) -> Result<Vec<PendingQuestion>, StorageError> {
+            Ok(self.items.borrow().clone())
+        }
+
+        fn add(&self, item: PendingQuestion) -> Result<(), StorageError> {
+            self.items.borrow_mut().push(item);
+            Ok(())
+        }
+
+        fn update(&self, item: PendingQuestion) -> Result<bool, StorageError> {
+            let mut items = self.items.borrow_mut();
+            match items.iter_mut().find(|stored| stored.id == item.id) {
+                Some(stored) => {
+                    *stored = item;
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        }
+
+        fn remove(&self, id: OutboxId) -> Result<bool, StorageError> {
+            let mut items = self.items.borrow_mut();
+            let before = items.len();
+            items.retain(|item| item.id != id);
+            Ok(items.len() != before)
+        }
+    }
+
     #[derive(Clone)]
     struct FakeHealthGateway {
         result: Result<HealthStatus, GatewayError>,
@@ -102,8 +798,9 @@ mod tests {
         let gateway = FakeChatGateway {
             result: Ok(AskResult {
                 answer: "ok".to_string(),
-                source: "mock".to_string(),
+                source: AnswerSource::Mock,
                 system_prompt_applied: false,
+                cached: false,
             }),
         };
         let usecase = AskQuestionUseCase::new(gateway);
@@ -111,17 +808,516 @@ mod tests {
         assert!(matches!(error, UseCaseError::Domain(DomainError::EmptyQuestion)));
     }
 
+    #[test]
+    fn ask_usecase_applies_custom_policy() {
+        let gateway = FakeChatGateway {
+            result: Err(GatewayError::InvalidPayload),
+        };
+        let policy = QuestionPolicy {
+            max_chars: 3,
+            ..QuestionPolicy::default()
+        };
+        let usecase = AskQuestionUseCase::new(gateway).with_policy(policy);
+        let error = block_on(usecase.execute("Длинный вопрос".to_string())).unwrap_err();
+        assert_eq!(
+            error,
+            UseCaseError::Domain(DomainError::QuestionTooLong { max: 3, actual: 14 })
+        );
+    }
+
+    #[test]
+    fn ask_usecase_renders_template() {
+        let gateway = RecordingChatGateway::default();
+        let usecase = AskQuestionUseCase::new(gateway.clone());
+        let template = PromptTemplate::try_new("Ошибка", "Объясни ошибку: {{error}}").unwrap();
+        let values = BTreeMap::from([("error".to_string(), "E0499".to_string())]);
+
+        block_on(usecase.execute_template(&template, &values)).unwrap();
+
+        let requests = gateway.requests.borrow();
+        assert_eq!(requests[0].question.as_str(), "Объясни ошибку: E0499");
+    }
+
+    #[test]
+    fn ask_usecase_rejects_template_without_values() {
+        let gateway = RecordingChatGateway::default();
+        let usecase = AskQuestionUseCase::new(gateway.clone());
+        let template = PromptTemplate::try_new("Перевод", "Переведи {{text}} на {{lang}}").unwrap();
+        let values = BTreeMap::from([("text".to_string(), "привет".to_string())]);
+
+        let error = block_on(usecase.execute_template(&template, &values)).unwrap_err();
+
+        assert_eq!(
+            error,
+            UseCaseError::Domain(DomainError::TemplateMissingVariables(vec![
+                "lang".to_string()
+            ]))
+        );
+        assert!(gateway.requests.borrow().is_empty());
+    }
+
+    #[test]
+    fn continue_conversation_sends_previous_messages() {
+        let gateway = RecordingChatGateway::default();
+        let usecase = ContinueConversationUseCase::new(gateway.clone(), FixedClock(42));
+        let mut conversation = Conversation::new();
+
+        block_on(usecase.execute(&mut conversation, "Что такое Rust?".to_string())).unwrap();
+        block_on(usecase.execute(&mut conversation, "А Rocket?".to_string())).unwrap();
+
+        let requests = gateway.requests.borrow();
+        assert!(requests[0].context.is_empty());
+        assert_eq!(requests[1].context.len(), 2);
+        assert_eq!(requests[1].context[0].content, "Что такое Rust?");
+        assert_eq!(requests[1].context[1].role, MessageRole::Assistant);
+        assert_eq!(conversation.len(), 4);
+        assert_eq!(conversation.messages()[3].content, "ответ на: А Rocket?");
+    }
+
+    #[test]
+    fn ask_usecase_passes_generation_settings() {
+        let gateway = RecordingChatGateway::default();
+        let generation = GenerationSettings {
+            temperature: Some(Temperature::try_new(0.3).unwrap()),
+            ..GenerationSettings::default()
+        };
+        let usecase = AskQuestionUseCase::new(gateway.clone()).with_generation(generation.clone());
+
+        block_on(usecase.execute("Что такое Rust?".to_string())).unwrap();
+
+        assert_eq!(gateway.requests.borrow()[0].generation, generation);
+    }
+
+    #[test]
+    fn ask_usecase_passes_system_prompt() {
+        let gateway = RecordingChatGateway::default();
+        let prompt = SystemPrompt::try_new("Отвечай кратко").unwrap();
+        let usecase =
+            AskQuestionUseCase::new(gateway.clone()).with_system_prompt(Some(prompt.clone()));
+
+        block_on(usecase.execute("Что такое Rust?".to_string())).unwrap();
+
+        assert_eq!(gateway.requests.borrow()[0].system_prompt, Some(prompt));
+    }
+
+    #[test]
+    fn continue_conversation_passes_system_prompt() {
+        let gateway = RecordingChatGateway::default();
+        let prompt = SystemPrompt::try_new("Переводи на английский").unwrap();
+        let usecase = ContinueConversationUseCase::new(gateway.clone(), FixedClock(0))
+            .with_system_prompt(Some(prompt.clone()));
+        let mut conversation = Conversation::new();
+
+        block_on(usecase.execute(&mut conversation, "Привет".to_string())).unwrap();
+
+        assert_eq!(gateway.requests.borrow()[0].system_prompt, Some(prompt));
+    }
+
+    #[test]
+    fn ask_usecase_passes_cancellation_token() {
+        let gateway = RecordingChatGateway::default();
+        let token = CancellationToken::new();
+        let usecase = AskQuestionUseCase::new(gateway.clone()).with_cancellation(token.clone());
+
+        block_on(usecase.execute("Что такое Rust?".to_string())).unwrap();
+        token.cancel();
+
+        assert!(gateway.requests.borrow()[0].cancellation.is_cancelled());
+    }
+
+    #[test]
+    fn continue_conversation_keeps_dialog_on_error() {
+        let gateway = FakeChatGateway {
+            result: Err(GatewayError::Network("offline".to_string())),
+        };
+        let usecase = ContinueConversationUseCase::new(gateway, FixedClock(0));
+        let mut conversation = Conversation::new();
+
+        let error =
+            block_on(usecase.execute(&mut conversation, "Привет".to_string())).unwrap_err();
+        assert!(matches!(
+            error,
+            UseCaseError::Gateway(GatewayError::Network(_))
+        ));
+        assert!(conversation.is_empty());
+    }
+
+    /// Gateway с заранее заданным потоком фрагментов; `None` — потоковые
+    /// ответы не поддерживаются.
+    struct StreamingGateway {
+        chunks: Option<Vec<Result<AnswerChunk, GatewayError>>>,
+    }
+
+    #[async_trait(?Send)]
+    impl ChatGateway for StreamingGateway {
+        async fn ask(&self, _request: AskRequest) -> Result<AskResult, GatewayError> {
+            Ok(answer("обычный ответ"))
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl StreamingChatGateway for StreamingGateway {
+        async fn ask_stream(&self, _request: AskRequest) -> Result<AnswerStream, GatewayError> {
+            match &self.chunks {
+                Some(chunks) => Ok(futures::stream::iter(chunks.clone()).boxed_local()),
+                None => Err(GatewayError::StreamingUnsupported),
+            }
+        }
+    }
+
+    fn delta(text: &str) -> Result<AnswerChunk, GatewayError> {
+        Ok(AnswerChunk::Delta(text.to_string()))
+    }
+
+    #[test]
+    fn streaming_collects_chunks_and_reports_progress() {
+        let gateway = StreamingGateway {
+            chunks: Some(vec![
+                delta("Rust — "),
+                delta("язык"),
+                Ok(AnswerChunk::Done {
+                    source: AnswerSource::GigaChat,
+                    system_prompt_applied: false,
+                    cached: false,
+                }),
+            ]),
+        };
+        let usecase = ContinueConversationUseCase::new(gateway, FixedClock(0));
+        let mut conversation = Conversation::new();
+        let progress = RefCell::new(Vec::new());
+
+        let result = block_on(usecase.execute_streaming(
+            &mut conversation,
+            "Что такое Rust?".to_string(),
+            |text: &str| progress.borrow_mut().push(text.to_string()),
+        ))
+        .unwrap();
+
+        assert_eq!(result.answer, "Rust — язык");
+        assert_eq!(result.source, AnswerSource::GigaChat);
+        assert_eq!(*progress.borrow(), vec!["Rust — ", "Rust — язык"]);
+        assert_eq!(conversation.messages().len(), 2);
+    }
+
+    #[test]
+    fn streaming_falls_back_to_plain_request() {
+        let usecase =
+            ContinueConversationUseCase::new(StreamingGateway { chunks: None }, FixedClock(0));
+        let mut conversation = Conversation::new();
+
+        let result = block_on(usecase.execute_streaming(
+            &mut conversation,
+            "Привет".to_string(),
+            |_: &str| panic!("прогресс без потока"),
+        ))
+        .unwrap();
+
+        assert_eq!(result.answer, "обычный ответ");
+    }
+
+    #[test]
+    fn streaming_error_keeps_dialog() {
+        let gateway = StreamingGateway {
+            chunks: Some(vec![delta("Обрыв"), Err(GatewayError::Cancelled)]),
+        };
+        let usecase = ContinueConversationUseCase::new(gateway, FixedClock(0));
+        let mut conversation = Conversation::new();
+
+        let error = block_on(usecase.execute_streaming(
+            &mut conversation,
+            "Привет".to_string(),
+            |_: &str| {},
+        ))
+        .unwrap_err();
+
+        assert_eq!(error, UseCaseError::Gateway(GatewayError::Cancelled));
+        assert!(conversation.is_empty());
+    }
+
+    fn answer(text: &str) -> AskResult {
+        AskResult {
+            answer: text.to_string(),
+            source: AnswerSource::GigaChat,
+            system_prompt_applied: true,
+            cached: false,
+        }
+    }
+
+    #[test]
+    fn record_exchange_normalizes_question_and_assigns_ids() {
+        let history = InMemoryHistory::default();
+        let usecase = RecordExchangeUseCase::new(history.clone(), FixedClock(1_000));
+
+        let first = usecase
+            .execute("  Что такое Rust?  ", &answer("Язык"))
+            .unwrap();
+        let second = usecase.execute("А Go?", &answer("Тоже язык")).unwrap();
+
+        assert_eq!(first.question.as_str(), "Что такое Rust?");
+        assert_eq!(first.id, HistoryId::new(1_000));
+        assert_eq!(second.id, HistoryId::new(1_001));
+        assert_eq!(first.recorded_at, Timestamp::from_millis(1_000));
+        assert_eq!(history.entries.borrow().len(), 2);
+        assert!(matches!(
+            usecase.execute("   ", &answer("x")),
+            Err(UseCaseError::Domain(DomainError::EmptyQuestion))
+        ));
+    }
+
+    #[test]
+    fn record_exchange_drops_oldest_over_limit() {
+        let history = InMemoryHistory::default();
+        let usecase = RecordExchangeUseCase::new(history.clone(), FixedClock(0)).with_limit(2);
+
+        for question in ["один", "два", "три"] {
+            usecase.execute(question, &answer("ok")).unwrap();
+        }
+
+        let questions: Vec<String> = history
+            .entries
+            .borrow()
+            .iter()
+            .map(|entry| entry.question.as_str().to_string())
+            .collect();
+        assert_eq!(questions, vec!["два", "три"]);
+    }
+
+    #[test]
+    fn manage_history_lists_newest_first_and_deletes() {
+        let history = InMemoryHistory::default();
+        let record = RecordExchangeUseCase::new(history.clone(), FixedClock(10));
+        let old = record.execute("старый", &answer("ok")).unwrap();
+        let new = record.execute("новый", &answer("ok")).unwrap();
+        let usecase = ManageHistoryUseCase::new(history);
+
+        let ids: Vec<HistoryId> = usecase
+            .list()
+            .unwrap()
+            .iter()
+            .map(|entry| entry.id)
+            .collect();
+        assert_eq!(ids, vec![new.id, old.id]);
+
+        usecase.delete(old.id).unwrap();
+        usecase.delete(old.id).unwrap();
+        assert_eq!(usecase.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn search_history_finds_entries_with_highlights() {
+        let history = InMemoryHistory::default();
+        let record = RecordExchangeUseCase::new(history.clone(), FixedClock(1));
+        record
+            .execute("Что такое Rocket?", &answer("Rocket — веб‑фреймворк."))
+            .unwrap();
+        record
+            .execute("Что такое Axum?", &answer("Тоже фреймворк."))
+            .unwrap();
+        let entries = history.list().unwrap();
+        let usecase = SearchHistoryUseCase::new(&entries);
+
+        assert!(usecase.execute("рокет rocket").is_empty());
+
+        let hits = usecase.execute("rocket");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].question_highlights, vec![18..24]);
+        assert_eq!(hits[0].answer_highlights, vec![0..6]);
+
+        assert_eq!(usecase.execute("фреймворки").len(), 2);
+        assert!(usecase.execute("  ").is_empty());
+    }
+
     #[test]
     fn health_usecase_returns_result() {
         let gateway = FakeHealthGateway {
             result: Ok(HealthStatus {
-                status: "ok".to_string(),
+                status: ServiceState::Ok,
                 version: "0.1.0".to_string(),
                 gigachat_enabled: false,
             }),
         };
         let usecase = CheckHealthUseCase::new(gateway);
         let result = block_on(usecase.execute()).unwrap();
-        assert_eq!(result.status, "ok");
+        assert_eq!(result.status, ServiceState::Ok);
+    }
+
+    #[test]
+    fn outbox_keeps_questions_until_backend_is_reachable() {
+        let outbox = InMemoryOutbox::default();
+        let usecase = OutboxUseCase::new(outbox.clone(), FixedClock(1_000));
+        usecase.enqueue("  Первый вопрос ").unwrap();
+        usecase.enqueue("Второй вопрос").unwrap();
+        assert!(usecase.enqueue("   ").is_err());
+
+        let offline = FakeChatGateway {
+            result: Err(GatewayError::Network("offline".to_string())),
+        };
+        let report = block_on(usecase.replay(&offline)).unwrap();
+        assert!(report.sent.is_empty());
+        assert_eq!(report.remaining, 2);
+        // После первой сетевой ошибки остальные вопросы не отправляются.
+        let items = usecase.list().unwrap();
+        assert_eq!(items[0].attempts, 1);
+        assert_eq!(items[1].attempts, 0);
+
+        let online = RecordingChatGateway::default();
+        let report = block_on(usecase.replay(&online)).unwrap();
+        let sent: Vec<_> = report
+            .sent
+            .iter()
+            .map(|(item, result)| (item.question.as_str(), result.answer.as_str()))
+            .collect();
+        assert_eq!(
+            sent,
+            vec![
+                ("Первый вопрос", "ответ на: Первый вопрос"),
+                ("Второй вопрос", "ответ на: Второй вопрос"),
+            ]
+        );
+        assert_eq!(report.remaining, 0);
+        assert!(outbox.items.borrow().is_empty());
+    }
+
+    #[test]
+    fn outbox_item_can_be_cancelled() {
+        let usecase = OutboxUseCase::new(InMemoryOutbox::default(), FixedClock(1_000));
+        let item = usecase.enqueue("Вопрос").unwrap();
+        usecase.cancel(item.id).unwrap();
+        usecase.cancel(item.id).unwrap();
+        assert!(usecase.list().unwrap().is_empty());
+    }
+
+    /// Backend сессий: пароль `secret`, токены `access-1`, `access-2`, …
+    /// живут минуту. Ошибку продления можно задать заранее.
+    #[derive(Default)]
+    struct FakeSessionGateway {
+        issued: RefCell<u32>,
+        refresh_error: RefCell<Option<GatewayError>>,
+        logged_out: RefCell<Vec<String>>,
+    }
+
+    impl FakeSessionGateway {
+        fn issue(&self, username: &str, now: u64) -> Session {
+            *self.issued.borrow_mut() += 1;
+            Session {
+                username: username.to_string(),
+                access_token: format!("access-{}", self.issued.borrow()),
+                refresh_token: Some("refresh".to_string()),
+                expires_at: Some(Timestamp::from_millis(now + 60_000)),
+            }
+        }
+    }
+
+    fn unauthorized() -> GatewayError {
+        GatewayError::Unauthorized(ApiError {
+            status: Some(401),
+            code: None,
+            message: "invalid credentials".to_string(),
+            body: String::new(),
+        })
+    }
+
+    #[async_trait(?Send)]
+    impl SessionGateway for &FakeSessionGateway {
+        async fn login(&self, credentials: &LoginCredentials) -> Result<Session, GatewayError> {
+            if credentials.password() != "secret" {
+                return Err(unauthorized());
+            }
+            Ok(self.issue(credentials.username(), 0))
+        }
+
+        async fn refresh(&self, session: &Session) -> Result<Session, GatewayError> {
+            if let Some(error) = self.refresh_error.borrow().clone() {
+                return Err(error);
+            }
+            let expires_at = session.expires_at.map_or(0, |at| at.as_millis());
+            Ok(self.issue(&session.username, expires_at))
+        }
+
+        async fn logout(&self, session: &Session) -> Result<(), GatewayError> {
+            self.logged_out
+                .borrow_mut()
+                .push(session.access_token.clone());
+            Err(GatewayError::Network("offline".to_string()))
+        }
+    }
+
+    /// Хранилище сессии в памяти.
+    #[derive(Clone, Default)]
+    struct InMemorySession(Rc<RefCell<Option<Session>>>);
+
+    impl SessionRepository for InMemorySession {
+        fn load(&self) -> Result<Option<Session>, StorageError> {
+            Ok(self.0.borrow().clone())
+        }
+
+        fn save(&self, session: &Session) -> Result<(), StorageError> {
+            *self.0.borrow_mut() = Some(session.clone());
+            Ok(())
+        }
+
+        fn clear(&self) -> Result<(), StorageError> {
+            self.0.borrow_mut().take();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn login_stores_session_and_logout_forgets_it() {
+        let backend = FakeSessionGateway::default();
+        let store = InMemorySession::default();
+        let usecase = AuthenticateUseCase::new(&backend, store.clone(), FixedClock(0));
+
+        assert!(matches!(
+            block_on(usecase.login(" ", "secret")),
+            Err(UseCaseError::Domain(DomainError::EmptyUsername))
+        ));
+        assert_eq!(
+            block_on(usecase.login("student", "wrong")),
+            Err(UseCaseError::Gateway(unauthorized()))
+        );
+        assert_eq!(usecase.current(), Ok(None));
+
+        let session = block_on(usecase.login("student", "secret")).unwrap();
+        assert_eq!(session.username, "student");
+        assert_eq!(usecase.current(), Ok(Some(session)));
+
+        // Backend недоступен, но локально выход всё равно выполняется.
+        block_on(usecase.logout()).unwrap();
+        assert_eq!(usecase.current(), Ok(None));
+        assert_eq!(*backend.logged_out.borrow(), vec!["access-1".to_string()]);
+    }
+
+    #[test]
+    fn session_is_refreshed_before_expiry_and_dropped_when_rejected() {
+        let backend = FakeSessionGateway::default();
+        let store = InMemorySession::default();
+        let at = |now| {
+            AuthenticateUseCase::new(&backend, store.clone(), FixedClock(now))
+                .with_refresh_margin(10_000)
+        };
+        block_on(at(0).login("student", "secret")).unwrap();
+        let session = at(0).current().unwrap().unwrap();
+        assert_eq!(at(20_000).refresh_delay_ms(&session), Some(30_000));
+
+        let active = |now| block_on(at(now).active_session()).unwrap().unwrap();
+        assert_eq!(active(49_000).access_token, "access-1");
+        assert_eq!(active(50_000).access_token, "access-2");
+
+        // Временный сбой: ещё действующий токен остаётся в ходу.
+        *backend.refresh_error.borrow_mut() = Some(GatewayError::Network("offline".to_string()));
+        assert_eq!(active(115_000).access_token, "access-2");
+
+        // Backend отверг продление — сессия забыта, нужен новый вход.
+        *backend.refresh_error.borrow_mut() = Some(unauthorized());
+        assert_eq!(
+            block_on(at(115_000).active_session()),
+            Err(UseCaseError::Gateway(unauthorized()))
+        );
+        assert_eq!(store.load(), Ok(None));
+        assert_eq!(
+            block_on(at(0).refresh()),
+            Err(UseCaseError::NotAuthenticated)
+        );
     }
 }
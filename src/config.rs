@@ -5,35 +5,115 @@
 //! 1) сохранённое в `localStorage`,
 //! 2) переменная окружения `API_BASE_URL` на этапе сборки,
 //! 3) значение по умолчанию (`http://127.0.0.1:8000`).
+//!
+//! Адрес и тип сервера хранятся в именованных профилях backend; профиль
+//! backend курса по умолчанию получает адрес по этим правилам.
+//!
+//! Рядом, в том же `localStorage`, хранятся пресеты системного промпта,
+//! шаблоны вопросов, бюджет токенов на запрос и лимиты длины вопроса.
 use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
 
-use crate::domain::{ApiBaseUrl, DomainError};
+use crate::application::{
+    BackendKind, BackendProfile, CachePolicy, CircuitBreakerPolicy, RateLimitPolicy, RetryPolicy,
+};
+use crate::domain::{
+    ApiBaseUrl, DomainError, PromptTemplate, QuestionPolicy, SystemPrompt, SystemPromptPreset,
+    TokenBudget,
+};
+use crate::infrastructure::ApiClient;
 
 const STORAGE_KEY: &str = "rust_gigachat_webapp.api_base_url";
+const BACKEND_PROFILES_KEY: &str = "rust_gigachat_webapp.backend_profiles";
+const ACTIVE_PROFILE_KEY: &str = "rust_gigachat_webapp.active_backend_profile";
+const PROMPT_PRESETS_KEY: &str = "rust_gigachat_webapp.system_prompt_presets";
+const PROMPT_TEMPLATES_KEY: &str = "rust_gigachat_webapp.prompt_templates";
+const TOKEN_BUDGET_KEY: &str = "rust_gigachat_webapp.token_budget";
+const QUESTION_POLICY_KEY: &str = "rust_gigachat_webapp.question_policy";
+
+/// Формат хранения пресета системного промпта в `localStorage`.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredPromptPreset {
+    name: String,
+    prompt: String,
+}
+
+/// Формат хранения лимитов вопроса в `localStorage`.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredQuestionPolicy {
+    max_chars: usize,
+    max_lines: usize,
+}
+
+/// Формат хранения шаблона вопроса в `localStorage`.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredPromptTemplate {
+    name: String,
+    body: String,
+}
+
+/// Формат хранения профиля backend в `localStorage`.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredBackendProfile {
+    name: String,
+    kind: String,
+    base_url: String,
+}
 
 /// Конфигурация приложения, доступная UI‑слою.
 ///
-/// Сейчас она хранит только базовый URL API, но структура оставлена
-/// расширяемой — в будущем можно добавить таймауты, флаги режима и т.п.
+/// Хранит профили backend и выбранный из них, политику проверки вопросов,
+/// бюджет токенов, политику повтора запросов, таймаут одного запроса,
+/// настройки кэша ответов, лимит частоты запросов и настройки автомата
+/// защиты.
+/// Структура оставлена расширяемой — в будущем можно добавить таймауты,
+/// флаги режима и т.п.
 #[derive(Clone, Debug)]
 pub struct AppConfig {
-    pub api_base_url: ApiBaseUrl,
+    /// Профили backend; список не бывает пустым.
+    pub backend_profiles: Vec<BackendProfile>,
+    /// Индекс выбранного профиля в `backend_profiles`.
+    pub active_profile: usize,
+    pub question_policy: QuestionPolicy,
+    pub token_budget: TokenBudget,
+    pub retry_policy: RetryPolicy,
+    pub request_timeout_ms: u32,
+    pub cache_policy: CachePolicy,
+    /// Сохранять ли кэш ответов в `localStorage` между сессиями.
+    pub persist_answer_cache: bool,
+    pub rate_limit: RateLimitPolicy,
+    pub circuit_breaker: CircuitBreakerPolicy,
 }
 
 impl AppConfig {
     /// Загружает конфигурацию, следуя приоритетам источников.
     pub fn load() -> Self {
+        let backend_profiles = Self::load_backend_profiles();
+        Self {
+            active_profile: Self::load_active_profile(&backend_profiles),
+            backend_profiles,
+            question_policy: Self::load_question_policy(),
+            token_budget: Self::load_token_budget(),
+            retry_policy: RetryPolicy::default(),
+            request_timeout_ms: ApiClient::DEFAULT_TIMEOUT_MS,
+            cache_policy: CachePolicy::default(),
+            persist_answer_cache: true,
+            rate_limit: RateLimitPolicy::default(),
+            circuit_breaker: CircuitBreakerPolicy::default(),
+        }
+    }
+
+    /// Выбирает базовый URL: сохранённый, из `API_BASE_URL` или по умолчанию.
+    fn load_base_url() -> ApiBaseUrl {
         if let Ok(saved) = LocalStorage::get::<String>(STORAGE_KEY) {
             if let Ok(base_url) = ApiBaseUrl::try_new(saved) {
-                return Self { api_base_url: base_url };
+                return base_url;
             }
         }
 
         let fallback = Self::default_base_url();
-        let base_url = ApiBaseUrl::try_new(fallback)
-            .unwrap_or_else(|_| ApiBaseUrl::try_new("http://127.0.0.1:8000").expect("default URL"));
-
-        Self { api_base_url: base_url }
+        ApiBaseUrl::try_new(fallback)
+            .unwrap_or_else(|_| ApiBaseUrl::try_new("http://127.0.0.1:8000").expect("default URL"))
     }
 
     /// Возвращает базовый URL по умолчанию.
@@ -46,16 +126,208 @@ impl AppConfig {
             .to_string()
     }
 
-    /// Сохраняет базовый URL в `localStorage`.
+    /// Валидирует ввод пользователя и преобразует строку в value object.
+    pub fn parse_base_url(value: &str) -> Result<ApiBaseUrl, DomainError> {
+        ApiBaseUrl::try_new(value.to_string())
+    }
+
+    /// Загружает профили backend.
+    ///
+    /// Если пользователь ещё ничего не сохранял, возвращаются встроенные
+    /// профили. Повреждённые записи пропускаются.
+    fn load_backend_profiles() -> Vec<BackendProfile> {
+        let stored: Vec<BackendProfile> =
+            LocalStorage::get::<Vec<StoredBackendProfile>>(BACKEND_PROFILES_KEY)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|profile| {
+                    let kind = BackendKind::parse(&profile.kind)?;
+                    let base_url = ApiBaseUrl::try_new(profile.base_url).ok()?;
+                    BackendProfile::try_new(profile.name, kind, base_url).ok()
+                })
+                .collect();
+        if stored.is_empty() {
+            Self::default_backend_profiles()
+        } else {
+            stored
+        }
+    }
+
+    /// Сохраняет профили backend в `localStorage`.
     ///
     /// Возвращает строку ошибки, чтобы UI мог показать её пользователю.
-    pub fn save_base_url(base_url: &ApiBaseUrl) -> Result<(), String> {
-        LocalStorage::set(STORAGE_KEY, base_url.as_str().to_string())
-            .map_err(|e| e.to_string())
+    pub fn save_backend_profiles(profiles: &[BackendProfile]) -> Result<(), String> {
+        let stored: Vec<StoredBackendProfile> = profiles
+            .iter()
+            .map(|profile| StoredBackendProfile {
+                name: profile.name().to_string(),
+                kind: profile.kind().as_str().to_string(),
+                base_url: profile.base_url().as_str().to_string(),
+            })
+            .collect();
+        LocalStorage::set(BACKEND_PROFILES_KEY, stored).map_err(|e| e.to_string())
     }
 
-    /// Валидирует ввод пользователя и преобразует строку в value object.
-    pub fn parse_base_url(value: &str) -> Result<ApiBaseUrl, DomainError> {
-        ApiBaseUrl::try_new(value.to_string())
+    /// Встроенные профили: backend курса и заглушка без сети.
+    pub fn default_backend_profiles() -> Vec<BackendProfile> {
+        let base_url = Self::load_base_url();
+        [BackendKind::Course, BackendKind::Mock]
+            .into_iter()
+            .filter_map(|kind| BackendProfile::try_new(kind.label(), kind, base_url.clone()).ok())
+            .collect()
+    }
+
+    /// Индекс выбранного профиля; если он не найден — первый профиль.
+    fn load_active_profile(profiles: &[BackendProfile]) -> usize {
+        LocalStorage::get::<String>(ACTIVE_PROFILE_KEY)
+            .ok()
+            .and_then(|name| profiles.iter().position(|profile| profile.name() == name))
+            .unwrap_or(0)
+    }
+
+    /// Запоминает выбранный профиль по названию.
+    pub fn save_active_profile(name: &str) -> Result<(), String> {
+        LocalStorage::set(ACTIVE_PROFILE_KEY, name.to_string()).map_err(|e| e.to_string())
+    }
+
+    /// Загружает бюджет токенов; при отсутствии или ошибке — значение по умолчанию.
+    fn load_token_budget() -> TokenBudget {
+        LocalStorage::get::<u32>(TOKEN_BUDGET_KEY)
+            .ok()
+            .and_then(|value| TokenBudget::try_new(value).ok())
+            .unwrap_or_default()
+    }
+
+    /// Сохраняет бюджет токенов в `localStorage`.
+    pub fn save_token_budget(budget: TokenBudget) -> Result<(), String> {
+        LocalStorage::set(TOKEN_BUDGET_KEY, budget.get()).map_err(|e| e.to_string())
+    }
+
+    /// Загружает лимиты вопроса; нулевые или битые значения заменяются
+    /// значениями по умолчанию.
+    fn load_question_policy() -> QuestionPolicy {
+        let mut policy = QuestionPolicy::default();
+        if let Ok(stored) = LocalStorage::get::<StoredQuestionPolicy>(QUESTION_POLICY_KEY) {
+            if stored.max_chars > 0 {
+                policy.max_chars = stored.max_chars;
+            }
+            if stored.max_lines > 0 {
+                policy.max_lines = stored.max_lines;
+            }
+        }
+        policy
+    }
+
+    /// Сохраняет лимиты вопроса в `localStorage`.
+    pub fn save_question_policy(policy: &QuestionPolicy) -> Result<(), String> {
+        let stored = StoredQuestionPolicy {
+            max_chars: policy.max_chars,
+            max_lines: policy.max_lines,
+        };
+        LocalStorage::set(QUESTION_POLICY_KEY, stored).map_err(|e| e.to_string())
+    }
+
+    /// Загружает пресеты системного промпта.
+    ///
+    /// Если пользователь ещё ничего не сохранял, возвращаются встроенные
+    /// пресеты. Повреждённые записи пропускаются.
+    pub fn load_prompt_presets() -> Vec<SystemPromptPreset> {
+        match LocalStorage::get::<Vec<StoredPromptPreset>>(PROMPT_PRESETS_KEY) {
+            Ok(stored) => stored
+                .into_iter()
+                .filter_map(|preset| {
+                    let prompt = SystemPrompt::try_new(preset.prompt).ok()?;
+                    SystemPromptPreset::try_new(preset.name, prompt).ok()
+                })
+                .collect(),
+            Err(_) => Self::default_prompt_presets(),
+        }
+    }
+
+    /// Сохраняет пресеты системного промпта в `localStorage`.
+    pub fn save_prompt_presets(presets: &[SystemPromptPreset]) -> Result<(), String> {
+        let stored: Vec<StoredPromptPreset> = presets
+            .iter()
+            .map(|preset| StoredPromptPreset {
+                name: preset.name().to_string(),
+                prompt: preset.prompt().as_str().to_string(),
+            })
+            .collect();
+        LocalStorage::set(PROMPT_PRESETS_KEY, stored).map_err(|e| e.to_string())
+    }
+
+    /// Встроенные пресеты для типовых задач.
+    pub fn default_prompt_presets() -> Vec<SystemPromptPreset> {
+        [
+            (
+                "Код‑ревью",
+                "Ты опытный ревьюер. Найди ошибки, потенциальные проблемы и \
+                 предложи улучшения. Отвечай списком замечаний.",
+            ),
+            (
+                "Перевод",
+                "Переводи текст пользователя между русским и английским языком. \
+                 Сохраняй форматирование и термины.",
+            ),
+            (
+                "Краткое изложение",
+                "Сделай краткое изложение текста пользователя: 3–5 пунктов, \
+                 только ключевые мысли.",
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, prompt)| {
+            let prompt = SystemPrompt::try_new(prompt).ok()?;
+            SystemPromptPreset::try_new(name, prompt).ok()
+        })
+        .collect()
+    }
+
+    /// Загружает шаблоны вопросов.
+    ///
+    /// Как и для пресетов: без сохранённых данных возвращаются встроенные
+    /// шаблоны, повреждённые записи пропускаются.
+    pub fn load_prompt_templates() -> Vec<PromptTemplate> {
+        match LocalStorage::get::<Vec<StoredPromptTemplate>>(PROMPT_TEMPLATES_KEY) {
+            Ok(stored) => stored
+                .into_iter()
+                .filter_map(|template| PromptTemplate::try_new(template.name, template.body).ok())
+                .collect(),
+            Err(_) => Self::default_prompt_templates(),
+        }
+    }
+
+    /// Сохраняет шаблоны вопросов в `localStorage`.
+    pub fn save_prompt_templates(templates: &[PromptTemplate]) -> Result<(), String> {
+        let stored: Vec<StoredPromptTemplate> = templates
+            .iter()
+            .map(|template| StoredPromptTemplate {
+                name: template.name().to_string(),
+                body: template.body().to_string(),
+            })
+            .collect();
+        LocalStorage::set(PROMPT_TEMPLATES_KEY, stored).map_err(|e| e.to_string())
+    }
+
+    /// Встроенные шаблоны для частых вопросов.
+    pub fn default_prompt_templates() -> Vec<PromptTemplate> {
+        [
+            (
+                "Объяснить ошибку",
+                "Объясни эту ошибку и как её исправить:\n{{error}}",
+            ),
+            (
+                "Сравнить технологии",
+                "Сравни {{first}} и {{second}}: сильные стороны, слабые стороны, \
+                 когда что выбирать.",
+            ),
+            (
+                "Пример кода",
+                "Покажи короткий пример на {{language}}: {{task}}",
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, body)| PromptTemplate::try_new(name, body).ok())
+        .collect()
     }
 }
@@ -0,0 +1,180 @@
+//! Диалог с backend — агрегат `Conversation`.
+//!
+//! Диалог хранит упорядоченный список сообщений. Правила агрегата:
+//! - сообщения добавляются только в конец;
+//! - вопрос пользователя и ответ ассистента добавляются парой (обменом);
+//! - метки времени не убывают — даже если часы клиента «прыгнули» назад.
+//!
+//! Благодаря этому предыдущие сообщения можно без изменений отправлять
+//! в backend как контекст следующего вопроса.
+use super::entities::{AnswerSource, AskResult};
+use super::value_objects::{Question, Timestamp};
+
+/// Роль автора сообщения.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageRole {
+    #[cfg_attr(not(test), allow(dead_code))]
+    System,
+    User,
+    Assistant,
+}
+
+impl MessageRole {
+    /// Строковое имя роли в формате, привычном для chat‑API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessageRole::System => "system",
+            MessageRole::User => "user",
+            MessageRole::Assistant => "assistant",
+        }
+    }
+}
+
+/// Сообщение диалога.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub role: MessageRole,
+    pub content: String,
+    pub timestamp: Timestamp,
+    /// Источник ответа (mock или gigachat). Заполняется только у ассистента.
+    pub source: Option<AnswerSource>,
+}
+
+/// Диалог: упорядоченная последовательность сообщений.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Conversation {
+    messages: Vec<Message>,
+}
+
+impl Conversation {
+    /// Создаёт пустой диалог.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает сообщения в порядке добавления.
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    /// Количество сообщений в диалоге.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Проверяет, что в диалоге ещё нет сообщений.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Добавляет системное сообщение (инструкцию для модели).
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn add_system(&mut self, content: impl Into<String>, at: Timestamp) {
+        self.push(MessageRole::System, content.into(), at, None);
+    }
+
+    /// Добавляет обмен «вопрос — ответ».
+    pub fn record_exchange(
+        &mut self,
+        question: &Question,
+        asked_at: Timestamp,
+        result: &AskResult,
+        answered_at: Timestamp,
+    ) {
+        self.push(
+            MessageRole::User,
+            question.as_str().to_string(),
+            asked_at,
+            None,
+        );
+        self.push(
+            MessageRole::Assistant,
+            result.answer.clone(),
+            answered_at,
+            Some(result.source.clone()),
+        );
+    }
+
+    /// Очищает диалог (начать разговор заново).
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+
+    fn push(
+        &mut self,
+        role: MessageRole,
+        content: String,
+        at: Timestamp,
+        source: Option<AnswerSource>,
+    ) {
+        let timestamp = match self.messages.last() {
+            Some(last) if last.timestamp > at => last.timestamp,
+            _ => at,
+        };
+        self.messages.push(Message {
+            role,
+            content,
+            timestamp,
+            source,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(answer: &str) -> AskResult {
+        AskResult {
+            answer: answer.to_string(),
+            source: AnswerSource::Mock,
+            system_prompt_applied: false,
+            cached: false,
+        }
+    }
+
+    #[test]
+    fn exchange_appends_user_and_assistant_in_order() {
+        let mut conversation = Conversation::new();
+        let question = Question::try_new("Что такое Rust?").unwrap();
+        conversation.record_exchange(
+            &question,
+            Timestamp::from_millis(10),
+            &result("Язык"),
+            Timestamp::from_millis(20),
+        );
+
+        let messages = conversation.messages();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, MessageRole::User);
+        assert_eq!(messages[0].content, "Что такое Rust?");
+        assert_eq!(messages[0].source, None);
+        assert_eq!(messages[1].role, MessageRole::Assistant);
+        assert_eq!(messages[1].source, Some(AnswerSource::Mock));
+
+        conversation.clear();
+        assert!(conversation.is_empty());
+    }
+
+    #[test]
+    fn timestamps_never_go_backwards() {
+        let mut conversation = Conversation::new();
+        conversation.add_system("Отвечай кратко", Timestamp::from_millis(100));
+        let question = Question::try_new("Привет").unwrap();
+        conversation.record_exchange(
+            &question,
+            Timestamp::from_millis(50),
+            &result("Привет!"),
+            Timestamp::from_millis(120),
+        );
+
+        let times: Vec<u64> = conversation
+            .messages()
+            .iter()
+            .map(|message| message.timestamp.as_millis())
+            .collect();
+        assert_eq!(times, vec![100, 100, 120]);
+    }
+}
@@ -1,20 +1,83 @@
+use super::errors::DomainError;
+use super::value_objects::SystemPrompt;
+
+/// Источник ответа на вопрос.
+///
+/// Backend сообщает источник строкой; неизвестные значения сохраняются
+/// в `Unknown`, чтобы UI мог показать их как есть.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AnswerSource {
+    GigaChat,
+    Mock,
+    Unknown(String),
+}
+
+/// Состояние сервиса, которое backend сообщает в `GET /health`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ServiceState {
+    Ok,
+    Degraded,
+    Down,
+    Unknown(String),
+}
+
 /// Состояние backend‑сервера.
 ///
 /// Используется для экрана "Статус API".
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HealthStatus {
-    pub status: String,
+    pub status: ServiceState,
     pub version: String,
     pub gigachat_enabled: bool,
 }
 
+impl HealthStatus {
+    /// Источник, из которого backend будет брать ответы в текущем режиме.
+    pub fn answer_source(&self) -> AnswerSource {
+        if self.gigachat_enabled {
+            AnswerSource::GigaChat
+        } else {
+            AnswerSource::Mock
+        }
+    }
+}
+
 /// Результат ответа на вопрос.
 ///
-/// Содержит текст ответа, источник (mock или gigachat) и флаг применения
-/// системного промпта.
+/// Содержит текст ответа, источник (mock или gigachat), флаг применения
+/// системного промпта и признак того, что ответ взят из кэша UI.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AskResult {
     pub answer: String,
-    pub source: String,
+    pub source: AnswerSource,
     pub system_prompt_applied: bool,
+    pub cached: bool,
+}
+
+/// Сохранённый пресет системного промпта (например, «Код‑ревью»).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemPromptPreset {
+    name: String,
+    prompt: SystemPrompt,
+}
+
+impl SystemPromptPreset {
+    /// Создаёт пресет; название не должно быть пустым.
+    pub fn try_new(name: impl Into<String>, prompt: SystemPrompt) -> Result<Self, DomainError> {
+        let name = name.into().trim().to_string();
+        if name.is_empty() {
+            return Err(DomainError::EmptyPresetName);
+        }
+        Ok(Self { name, prompt })
+    }
+
+    /// Название пресета.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Системный промпт пресета.
+    pub fn prompt(&self) -> &SystemPrompt {
+        &self.prompt
+    }
 }
@@ -9,6 +9,62 @@ use thiserror::Error;
 pub enum DomainError {
     #[error("Вопрос не должен быть пустым")]
     EmptyQuestion,
+    #[error("Вопрос слишком длинный: {actual} символов при максимуме {max}")]
+    QuestionTooLong { max: usize, actual: usize },
+    #[error("В вопросе слишком много строк: {actual} при максимуме {max}")]
+    QuestionTooManyLines { max: usize, actual: usize },
+    #[error("Системный промпт не должен быть пустым")]
+    EmptySystemPrompt,
+    #[error("Системный промпт слишком длинный: {actual} символов при максимуме {max}")]
+    SystemPromptTooLong { max: usize, actual: usize },
+    #[error("Название пресета не должно быть пустым")]
+    EmptyPresetName,
+    #[error("Название профиля не должно быть пустым")]
+    EmptyProfileName,
+    #[error("Название шаблона не должно быть пустым")]
+    EmptyTemplateName,
+    #[error("Текст шаблона не должен быть пустым")]
+    EmptyTemplateBody,
+    #[error("В шаблоне есть незакрытая подстановка «{{{{»")]
+    TemplateUnclosedPlaceholder,
+    #[error("В шаблоне есть подстановка без имени")]
+    TemplateEmptyPlaceholder,
+    #[error("Некорректное имя переменной в шаблоне: {0}")]
+    TemplateInvalidPlaceholder(String),
+    #[error("Не заданы переменные шаблона: {}", .0.join(", "))]
+    TemplateMissingVariables(Vec<String>),
+    #[error("Лишние переменные для шаблона: {}", .0.join(", "))]
+    TemplateUnusedVariables(Vec<String>),
+    #[error("Температура должна быть в диапазоне от 0 до 2, получено: {0}")]
+    TemperatureOutOfRange(String),
+    #[error("Параметр top_p должен быть больше 0 и не больше 1, получено: {0}")]
+    TopPOutOfRange(String),
+    #[error("Ограничение токенов должно быть от 1 до 32768, получено: {0}")]
+    MaxTokensOutOfRange(u32),
+    #[error("Некорректное имя модели: \"{0}\"")]
+    InvalidModelName(String),
+    #[error("Бюджет токенов должен быть целым числом от 1 до 131072, получено: {0}")]
+    InvalidTokenBudget(String),
     #[error("Базовый URL API не задан")]
     EmptyBaseUrl,
+    #[error("В URL не указана схема (ожидается http:// или https://)")]
+    MissingUrlScheme,
+    #[error("Схема URL не поддерживается: {0} (ожидается http или https)")]
+    UnsupportedUrlScheme(String),
+    #[error("В URL не указан хост")]
+    MissingUrlHost,
+    #[error("Некорректный хост в URL: {0}")]
+    InvalidUrlHost(String),
+    #[error("Некорректный порт в URL: {0}")]
+    InvalidUrlPort(String),
+    #[error("Некорректный путь в URL: {0}")]
+    InvalidUrlPath(String),
+    #[error("Базовый URL не должен содержать параметры запроса (?...)")]
+    UrlQueryNotAllowed,
+    #[error("Базовый URL не должен содержать фрагмент (#...)")]
+    UrlFragmentNotAllowed,
+    #[error("Логин не должен быть пустым")]
+    EmptyUsername,
+    #[error("Пароль не должен быть пустым")]
+    EmptyPassword,
 }
@@ -0,0 +1,189 @@
+//! Параметры генерации ответа.
+//!
+//! Каждый параметр — отдельный value object с проверкой диапазона:
+//! - `Temperature` — «креативность» ответа, от 0.0 до 2.0;
+//! - `TopP` — доля вероятностной массы для выборки, от 0.0 (не включая) до 1.0;
+//! - `MaxTokens` — ограничение длины ответа, от 1 до 32768;
+//! - `ModelName` — имя модели без пробелов.
+//!
+//! Все параметры необязательны: если значение не задано, backend использует
+//! собственные настройки по умолчанию.
+//!
+//! Эти value objects живут отдельно от `value_objects`: они имеют смысл
+//! только вместе, в составе `GenerationSettings`, — так же, как
+//! `TokenBudget` лежит рядом с оценкой запроса в `tokens`.
+use super::errors::DomainError;
+
+/// Температура генерации.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Temperature(f64);
+
+impl Temperature {
+    pub const MIN: f64 = 0.0;
+    pub const MAX: f64 = 2.0;
+
+    /// Создаёт температуру и проверяет диапазон.
+    pub fn try_new(value: f64) -> Result<Self, DomainError> {
+        if !(Self::MIN..=Self::MAX).contains(&value) {
+            return Err(DomainError::TemperatureOutOfRange(value.to_string()));
+        }
+        // `+ 0.0` превращает -0.0 в 0.0, чтобы хеш был согласован с равенством.
+        Ok(Self(value + 0.0))
+    }
+
+    /// Возвращает значение температуры.
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+// Значение всегда конечно (NaN не проходит проверку диапазона),
+// поэтому сравнение на равенство рефлексивно.
+impl Eq for Temperature {}
+
+impl std::hash::Hash for Temperature {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+/// Параметр `top_p` (nucleus sampling).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TopP(f64);
+
+impl TopP {
+    pub const MAX: f64 = 1.0;
+
+    /// Создаёт `top_p` и проверяет диапазон `(0.0, 1.0]`.
+    pub fn try_new(value: f64) -> Result<Self, DomainError> {
+        if !(value > 0.0 && value <= Self::MAX) {
+            return Err(DomainError::TopPOutOfRange(value.to_string()));
+        }
+        Ok(Self(value))
+    }
+
+    /// Возвращает значение `top_p`.
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+// См. комментарий к `Temperature`: NaN отсекается при создании.
+impl Eq for TopP {}
+
+impl std::hash::Hash for TopP {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+/// Максимальное количество токенов в ответе.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MaxTokens(u32);
+
+impl MaxTokens {
+    pub const MAX: u32 = 32_768;
+
+    /// Создаёт ограничение и проверяет диапазон `1..=32768`.
+    pub fn try_new(value: u32) -> Result<Self, DomainError> {
+        if value == 0 || value > Self::MAX {
+            return Err(DomainError::MaxTokensOutOfRange(value));
+        }
+        Ok(Self(value))
+    }
+
+    /// Возвращает значение ограничения.
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+}
+
+/// Имя модели (например, `GigaChat-Pro`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ModelName(String);
+
+impl ModelName {
+    /// Максимальная длина имени модели.
+    pub const MAX_LEN: usize = 64;
+
+    /// Создаёт имя модели: непустое, без пробелов, не длиннее 64 символов.
+    pub fn try_new(value: impl Into<String>) -> Result<Self, DomainError> {
+        let value = value.into().trim().to_string();
+        let valid = !value.is_empty()
+            && value.chars().count() <= Self::MAX_LEN
+            && !value.chars().any(|c| c.is_whitespace() || c.is_control());
+        if !valid {
+            return Err(DomainError::InvalidModelName(value));
+        }
+        Ok(Self(value))
+    }
+
+    /// Возвращает имя модели.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Набор параметров генерации для одного запроса.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct GenerationSettings {
+    pub temperature: Option<Temperature>,
+    pub top_p: Option<TopP>,
+    pub max_tokens: Option<MaxTokens>,
+    pub model: Option<ModelName>,
+}
+
+impl GenerationSettings {
+    /// Проверяет, что ни один параметр не задан.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_parameters_check_ranges() {
+        assert!(Temperature::try_new(0.0).is_ok());
+        assert!(Temperature::try_new(2.0).is_ok());
+        assert_eq!(
+            Temperature::try_new(2.5),
+            Err(DomainError::TemperatureOutOfRange("2.5".to_string()))
+        );
+        assert!(Temperature::try_new(f64::NAN).is_err());
+
+        assert!(TopP::try_new(1.0).is_ok());
+        assert!(TopP::try_new(0.0).is_err());
+        assert!(TopP::try_new(f64::NAN).is_err());
+
+        assert!(MaxTokens::try_new(512).is_ok());
+        assert_eq!(
+            MaxTokens::try_new(0),
+            Err(DomainError::MaxTokensOutOfRange(0))
+        );
+        assert!(MaxTokens::try_new(MaxTokens::MAX + 1).is_err());
+    }
+
+    #[test]
+    fn model_name_rejects_spaces() {
+        assert_eq!(
+            ModelName::try_new(" GigaChat-Pro ").unwrap().as_str(),
+            "GigaChat-Pro"
+        );
+        assert!(ModelName::try_new("").is_err());
+        assert!(ModelName::try_new("Giga Chat").is_err());
+    }
+
+    #[test]
+    fn default_settings_are_empty() {
+        assert!(GenerationSettings::default().is_empty());
+        let settings = GenerationSettings {
+            max_tokens: Some(MaxTokens::try_new(100).unwrap()),
+            ..GenerationSettings::default()
+        };
+        assert!(!settings.is_empty());
+    }
+}
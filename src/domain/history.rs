@@ -0,0 +1,62 @@
+//! История вопросов и ответов.
+//!
+//! Каждая запись — завершённый обмен «вопрос — ответ» с моментом записи.
+//! Идентификаторы записей возрастают, поэтому по ним же записи упорядочены.
+use super::entities::AskResult;
+use super::value_objects::{Question, Timestamp};
+
+/// Идентификатор записи истории.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HistoryId(u64);
+
+impl HistoryId {
+    pub fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Запись истории: вопрос, ответ и время записи.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub id: HistoryId,
+    pub question: Question,
+    pub result: AskResult,
+    pub recorded_at: Timestamp,
+}
+
+impl HistoryEntry {
+    /// Идентификатор для новой записи.
+    ///
+    /// Берётся время записи в миллисекундах; если оно не больше последнего
+    /// идентификатора (две записи в одну миллисекунду или сбитые часы),
+    /// используется следующий за последним.
+    pub fn next_id(last: Option<HistoryId>, at: Timestamp) -> HistoryId {
+        match last {
+            Some(last) if last.0 >= at.as_millis() => HistoryId(last.0 + 1),
+            _ => HistoryId(at.as_millis()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_id_follows_time_and_stays_unique() {
+        let at = Timestamp::from_millis(1_000);
+        assert_eq!(HistoryEntry::next_id(None, at), HistoryId::new(1_000));
+        assert_eq!(
+            HistoryEntry::next_id(Some(HistoryId::new(500)), at),
+            HistoryId::new(1_000)
+        );
+        assert_eq!(
+            HistoryEntry::next_id(Some(HistoryId::new(1_000)), at),
+            HistoryId::new(1_001)
+        );
+    }
+}
@@ -1,14 +1,42 @@
 //! Доменные модели учебного проекта.
 //!
 //! Домен содержит минимальный набор сущностей и правил:
-//! - вопросы не должны быть пустыми;
-//! - базовый URL должен быть задан.
+//! - вопросы не должны быть пустыми и превышать лимиты `QuestionPolicy`;
+//! - шаблоны вопросов корректно размечены, а все их переменные заданы;
+//! - параметры генерации (температура, top_p и т.д.) лежат в допустимых диапазонах;
+//! - базовый URL должен быть корректным http(s)‑адресом без query и fragment;
+//! - бюджет токенов на запрос положителен, а размер запроса можно оценить;
+//! - блоки кода из ответа выделяются во фрагменты с распознанным языком;
+//! - сообщения диалога упорядочены и добавляются парами «вопрос — ответ»;
+//! - записи истории имеют уникальные возрастающие идентификаторы и
+//!   ищутся по словоформам русских и английских слов;
+//! - вопросы, не отправленные из‑за недоступного backend, ждут в очереди
+//!   и помнят число попыток отправки;
+//! - сессия пользователя продлевается заранее, до истечения токена.
 //!
 //! Эти правила независимы от UI и сети, поэтому домен легко тестировать.
+pub mod conversation;
 pub mod entities;
 pub mod errors;
+pub mod generation;
+pub mod history;
+pub mod outbox;
+pub mod search;
+pub mod session;
+pub mod snippet;
+pub mod template;
+pub mod tokens;
 pub mod value_objects;
 
-pub use entities::{AskResult, HealthStatus};
+pub use conversation::{Conversation, Message, MessageRole};
+pub use entities::{AnswerSource, AskResult, HealthStatus, ServiceState, SystemPromptPreset};
 pub use errors::DomainError;
-pub use value_objects::{ApiBaseUrl, Question};
+pub use generation::{GenerationSettings, MaxTokens, ModelName, Temperature, TopP};
+pub use history::{HistoryEntry, HistoryId};
+pub use outbox::{OutboxId, PendingQuestion};
+pub use search::{search_history, HistorySearchHit, SearchQuery};
+pub use session::{LoginCredentials, Session};
+pub use snippet::{CodeLanguage, CodeSnippet};
+pub use template::PromptTemplate;
+pub use tokens::{HeuristicTokenEstimator, TokenBudget, TokenEstimator};
+pub use value_objects::{ApiBaseUrl, Question, QuestionPolicy, SystemPrompt, Timestamp};
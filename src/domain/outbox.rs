@@ -0,0 +1,82 @@
+//! Очередь неотправленных вопросов.
+//!
+//! Вопрос попадает в очередь, когда backend недоступен, и ждёт, пока связь
+//! восстановится. Запись помнит, сколько раз её пытались отправить и чем
+//! закончилась последняя попытка. Идентификаторы, как и в истории,
+//! возрастают, поэтому по ним же записи упорядочены.
+use super::value_objects::{Question, Timestamp};
+
+/// Идентификатор записи очереди.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OutboxId(u64);
+
+impl OutboxId {
+    pub fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Вопрос, ожидающий отправки.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingQuestion {
+    pub id: OutboxId,
+    pub question: Question,
+    pub queued_at: Timestamp,
+    /// Сколько раз вопрос уже пытались отправить из очереди.
+    pub attempts: u32,
+    /// Описание ошибки последней попытки.
+    pub last_error: Option<String>,
+}
+
+impl PendingQuestion {
+    /// Новая запись очереди.
+    ///
+    /// Идентификатор выбирается так же, как у записей истории: время
+    /// постановки в очередь, а при совпадении — следующий за последним.
+    pub fn new(question: Question, last: Option<OutboxId>, queued_at: Timestamp) -> Self {
+        let id = match last {
+            Some(last) if last.0 >= queued_at.as_millis() => OutboxId(last.0 + 1),
+            _ => OutboxId(queued_at.as_millis()),
+        };
+        Self {
+            id,
+            question,
+            queued_at,
+            attempts: 0,
+            last_error: None,
+        }
+    }
+
+    /// Запись после неудачной попытки отправки.
+    pub fn failed(&self, error: impl Into<String>) -> Self {
+        Self {
+            attempts: self.attempts + 1,
+            last_error: Some(error.into()),
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_follow_time_and_failures_are_counted() {
+        let question = Question::try_new("Что такое Rust?").unwrap();
+        let at = Timestamp::from_millis(1_000);
+        let first = PendingQuestion::new(question.clone(), None, at);
+        let second = PendingQuestion::new(question, Some(first.id), at);
+        assert_eq!(first.id, OutboxId::new(1_000));
+        assert_eq!(second.id, OutboxId::new(1_001));
+
+        let failed = first.failed("нет сети").failed("таймаут");
+        assert_eq!(failed.attempts, 2);
+        assert_eq!(failed.last_error.as_deref(), Some("таймаут"));
+        assert_eq!(failed.id, first.id);
+    }
+}
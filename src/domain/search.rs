@@ -0,0 +1,335 @@
+//! Полнотекстовый поиск по истории.
+//!
+//! Текст разбивается на слова, слова приводятся к нижнему регистру
+//! (`ё` считается `е`) и грубо стеммируются: у русских и английских слов
+//! отрезаются частые окончания. Поэтому «Rocket», «рокете» и «Рокетом»
+//! находятся по запросам «rocket» и «рокет».
+//!
+//! Вдобавок термин запроса совпадает с любым термином индекса, который
+//! с него начинается: запрос «фрейм» найдёт «фреймворк». Все термины
+//! запроса должны встретиться в записи (логическое «И»).
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use super::history::{HistoryEntry, HistoryId};
+
+/// Минимальная длина основы после отрезания окончания (в символах).
+const MIN_STEM_CHARS: usize = 3;
+/// Короче этого термин запроса сравнивается только точно, без префикса.
+const MIN_PREFIX_CHARS: usize = 2;
+/// Вес совпадения в вопросе относительно совпадения в ответе.
+const QUESTION_WEIGHT: u32 = 2;
+
+/// Русские окончания, от длинных к коротким.
+const RUSSIAN_SUFFIXES: &[&str] = &[
+    "иями", "ями", "ами", "ого", "его", "ому", "ему", "ыми", "ими", "ость", "ости", "ение", "ения",
+    "ении", "ать", "ять", "ить", "еть", "ует", "ают", "яют", "ешь", "ишь", "ая", "яя", "ое", "ее",
+    "ые", "ие", "ый", "ий", "ой", "ом", "ем", "ам", "ям", "ах", "ях", "ую", "юю", "ов", "ев", "ей",
+    "ия", "ию", "а", "я", "о", "е", "ы", "и", "у", "ю", "ь", "й",
+];
+
+/// Английские окончания, от длинных к коротким.
+const ENGLISH_SUFFIXES: &[&str] = &["ing", "ies", "ed", "es", "ly", "s"];
+
+/// Слово текста: нормализованный термин и его место в исходной строке.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    pub text: String,
+    /// Байтовый диапазон слова в исходном тексте.
+    pub span: Range<usize>,
+}
+
+/// Разбивает текст на термины.
+pub fn tokenize(text: &str) -> Vec<Term> {
+    let mut terms = Vec::new();
+    let mut start = None;
+    for (index, c) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(index),
+            (false, Some(word_start)) => {
+                terms.push(Term {
+                    text: stem(&normalize(&text[word_start..index])),
+                    span: word_start..index,
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    terms
+}
+
+fn normalize(word: &str) -> String {
+    word.to_lowercase().replace('ё', "е")
+}
+
+/// Отрезает частое окончание, если основа остаётся достаточно длинной.
+fn stem(word: &str) -> String {
+    let cyrillic = word.chars().any(|c| ('а'..='я').contains(&c));
+    let suffixes = if cyrillic {
+        RUSSIAN_SUFFIXES
+    } else {
+        ENGLISH_SUFFIXES
+    };
+    let length = word.chars().count();
+    for suffix in suffixes {
+        if let Some(base) = word.strip_suffix(suffix) {
+            if length - suffix.chars().count() >= MIN_STEM_CHARS {
+                return if *suffix == "ies" {
+                    format!("{base}y")
+                } else {
+                    base.to_string()
+                };
+            }
+        }
+    }
+    word.to_string()
+}
+
+/// Разобранный поисковый запрос.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchQuery {
+    terms: Vec<String>,
+}
+
+impl SearchQuery {
+    pub fn parse(query: &str) -> Self {
+        let mut terms: Vec<String> = Vec::new();
+        for term in tokenize(query) {
+            if !terms.contains(&term.text) {
+                terms.push(term.text);
+            }
+        }
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn terms(&self) -> &[String] {
+        &self.terms
+    }
+
+    /// Совпадает ли термин индекса с термином запроса.
+    fn term_matches(query_term: &str, term: &str) -> bool {
+        term == query_term
+            || (query_term.chars().count() >= MIN_PREFIX_CHARS && term.starts_with(query_term))
+    }
+
+    /// Байтовые диапазоны слов текста, совпавших с запросом (для подсветки).
+    pub fn highlights(&self, text: &str) -> Vec<Range<usize>> {
+        tokenize(text)
+            .into_iter()
+            .filter(|term| {
+                self.terms
+                    .iter()
+                    .any(|query_term| Self::term_matches(query_term, &term.text))
+            })
+            .map(|term| term.span)
+            .collect()
+    }
+}
+
+/// Инвертированный индекс: термин → записи и вес совпадения в них.
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    postings: BTreeMap<String, BTreeMap<HistoryId, u32>>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Строит индекс по вопросам и ответам записей истории.
+    pub fn from_entries(entries: &[HistoryEntry]) -> Self {
+        let mut index = Self::new();
+        for entry in entries {
+            index.insert(entry);
+        }
+        index
+    }
+
+    /// Добавляет запись в индекс. Совпадения в вопросе весят больше.
+    pub fn insert(&mut self, entry: &HistoryEntry) {
+        self.add_text(entry.id, entry.question.as_str(), QUESTION_WEIGHT);
+        self.add_text(entry.id, &entry.result.answer, 1);
+    }
+
+    fn add_text(&mut self, id: HistoryId, text: &str, weight: u32) {
+        for term in tokenize(text) {
+            *self
+                .postings
+                .entry(term.text)
+                .or_default()
+                .entry(id)
+                .or_default() += weight;
+        }
+    }
+
+    /// Ищет записи, содержащие все термины запроса.
+    ///
+    /// Результат отсортирован по убыванию веса, при равенстве — от новых
+    /// записей к старым.
+    pub fn search(&self, query: &SearchQuery) -> Vec<(HistoryId, u32)> {
+        let mut scores: Option<BTreeMap<HistoryId, u32>> = None;
+        for query_term in query.terms() {
+            let mut term_scores: BTreeMap<HistoryId, u32> = BTreeMap::new();
+            let candidates = self
+                .postings
+                .range(query_term.clone()..)
+                .take_while(|(term, _)| term.starts_with(query_term.as_str()))
+                .filter(|(term, _)| SearchQuery::term_matches(query_term, term));
+            for (_, documents) in candidates {
+                for (id, weight) in documents {
+                    *term_scores.entry(*id).or_default() += weight;
+                }
+            }
+            scores = Some(match scores {
+                None => term_scores,
+                Some(previous) => previous
+                    .into_iter()
+                    .filter_map(|(id, score)| term_scores.get(&id).map(|extra| (id, score + extra)))
+                    .collect(),
+            });
+        }
+
+        let mut hits: Vec<(HistoryId, u32)> = scores.unwrap_or_default().into_iter().collect();
+        hits.sort_by(|left, right| right.1.cmp(&left.1).then(right.0.cmp(&left.0)));
+        hits
+    }
+}
+
+/// Найденная запись истории с диапазонами для подсветки.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistorySearchHit {
+    pub entry: HistoryEntry,
+    pub question_highlights: Vec<Range<usize>>,
+    pub answer_highlights: Vec<Range<usize>>,
+}
+
+/// Ищет записи по запросу; пустой запрос ничего не находит.
+///
+/// Индекс строится заново при каждом поиске: записей немного, а так
+/// результат всегда отражает текущий список.
+pub fn search_history(entries: &[HistoryEntry], query: &SearchQuery) -> Vec<HistorySearchHit> {
+    SearchIndex::from_entries(entries)
+        .search(query)
+        .into_iter()
+        .filter_map(|(id, _)| entries.iter().find(|entry| entry.id == id))
+        .map(|entry| HistorySearchHit {
+            question_highlights: query.highlights(entry.question.as_str()),
+            answer_highlights: query.highlights(&entry.result.answer),
+            entry: entry.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AnswerSource, AskResult, Question, Timestamp};
+
+    fn entry(id: u64, question: &str, answer: &str) -> HistoryEntry {
+        HistoryEntry {
+            id: HistoryId::new(id),
+            question: Question::try_new(question).unwrap(),
+            result: AskResult {
+                answer: answer.to_string(),
+                source: AnswerSource::Mock,
+                system_prompt_applied: false,
+                cached: false,
+            },
+            recorded_at: Timestamp::from_millis(id),
+        }
+    }
+
+    fn terms(text: &str) -> Vec<String> {
+        tokenize(text).into_iter().map(|term| term.text).collect()
+    }
+
+    #[test]
+    fn tokenizer_normalizes_and_stems_russian_and_english() {
+        assert_eq!(
+            terms("Рокет, рокете и РОКЕТОМ"),
+            vec!["рокет", "рокет", "и", "рокет"]
+        );
+        assert_eq!(terms("Ёжики"), vec!["ежик"]);
+        assert_eq!(terms("Testing libraries"), vec!["test", "library"]);
+        let spans: Vec<Range<usize>> = tokenize("ok, мир").into_iter().map(|t| t.span).collect();
+        assert_eq!(spans, vec![0..2, 4..10]);
+    }
+
+    #[test]
+    fn search_matches_word_forms_and_prefixes() {
+        let index = SearchIndex::from_entries(&[
+            entry(1, "Что такое Rocket?", "Rocket — веб‑фреймворк для Rust."),
+            entry(2, "Как работает Axum?", "Axum построен на Tokio."),
+        ]);
+        let ids = |query: &str| -> Vec<u64> {
+            index
+                .search(&SearchQuery::parse(query))
+                .into_iter()
+                .map(|(id, _)| id.as_u64())
+                .collect()
+        };
+
+        assert_eq!(ids("rockets"), vec![1]);
+        assert_eq!(ids("фреймворками"), vec![1]);
+        assert_eq!(ids("фрейм"), vec![1]);
+        assert_eq!(ids("tok"), vec![2]);
+        // Все термины должны встретиться в одной записи.
+        assert_eq!(ids("rocket tokio"), Vec::<u64>::new());
+        assert_eq!(ids("   "), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn search_ranks_question_matches_higher() {
+        let index = SearchIndex::from_entries(&[
+            entry(1, "Про Rust", "Rocket упоминается в ответе"),
+            entry(2, "Что такое Rocket?", "Фреймворк"),
+            entry(3, "Ещё раз", "Rocket упоминается в ответе"),
+        ]);
+        let ids: Vec<u64> = index
+            .search(&SearchQuery::parse("rocket"))
+            .into_iter()
+            .map(|(id, _)| id.as_u64())
+            .collect();
+        assert_eq!(ids, vec![2, 3, 1]);
+    }
+
+    #[test]
+    fn search_history_returns_entries_with_highlights() {
+        let entries = [
+            entry(1, "Что такое Rocket?", "Rocket — веб‑фреймворк."),
+            entry(2, "Что такое Axum?", "Тоже фреймворк."),
+        ];
+        let search = |query: &str| search_history(&entries, &SearchQuery::parse(query));
+
+        assert!(search("рокет rocket").is_empty());
+
+        let hits = search("rocket");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].question_highlights, vec![18..24]);
+        assert_eq!(hits[0].answer_highlights, vec![0..6]);
+
+        assert_eq!(search("фреймворки").len(), 2);
+        assert!(search("  ").is_empty());
+    }
+
+    #[test]
+    fn highlights_cover_matching_words() {
+        let query = SearchQuery::parse("рокет");
+        let text = "Рокетом пользуются, а Rocket — нет";
+        let highlighted: Vec<&str> = query
+            .highlights(text)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect();
+        assert_eq!(highlighted, vec!["Рокетом"]);
+    }
+}
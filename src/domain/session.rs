@@ -0,0 +1,140 @@
+//! Сессия пользователя на backend.
+//!
+//! После входа backend выдаёт токен доступа, срок его действия и,
+//! возможно, токен обновления. Токен доступа обновляется заранее —
+//! за `refresh_margin` до истечения, чтобы запрос не ушёл с уже
+//! недействительным токеном.
+use std::fmt;
+
+use super::errors::DomainError;
+use super::value_objects::Timestamp;
+
+/// Логин и пароль для входа.
+#[derive(Clone, PartialEq, Eq)]
+pub struct LoginCredentials {
+    username: String,
+    password: String,
+}
+
+impl LoginCredentials {
+    /// Логин обрезается по краям; пароль передаётся как есть.
+    pub fn try_new(
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Result<Self, DomainError> {
+        let username = username.into().trim().to_string();
+        let password = password.into();
+        if username.is_empty() {
+            return Err(DomainError::EmptyUsername);
+        }
+        if password.is_empty() {
+            return Err(DomainError::EmptyPassword);
+        }
+        Ok(Self { username, password })
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
+
+impl fmt::Debug for LoginCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoginCredentials")
+            .field("username", &self.username)
+            .field("password", &"***")
+            .finish()
+    }
+}
+
+/// Сессия вошедшего пользователя.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Session {
+    pub username: String,
+    pub access_token: String,
+    /// Токен для продления сессии без пароля.
+    pub refresh_token: Option<String>,
+    /// Когда истекает токен доступа; `None` — срок неизвестен.
+    pub expires_at: Option<Timestamp>,
+}
+
+impl Session {
+    /// Момент, когда токен пора обновить: за `margin_ms` до истечения.
+    pub fn refresh_at(&self, margin_ms: u64) -> Option<Timestamp> {
+        self.expires_at.map(|expires_at| {
+            Timestamp::from_millis(expires_at.as_millis().saturating_sub(margin_ms))
+        })
+    }
+
+    /// Токен доступа пора обновить.
+    pub fn needs_refresh(&self, now: Timestamp, margin_ms: u64) -> bool {
+        self.refresh_at(margin_ms)
+            .is_some_and(|refresh_at| refresh_at <= now)
+    }
+
+    /// Токен доступа уже истёк.
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.needs_refresh(now, 0)
+    }
+
+    /// Сессию можно продлить без пароля.
+    pub fn can_refresh(&self) -> bool {
+        self.refresh_token.is_some()
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("username", &self.username)
+            .field("access_token", &"***")
+            .field("refresh_token", &self.refresh_token.as_ref().map(|_| "***"))
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_is_refreshed_ahead_of_expiry() {
+        let session = Session {
+            username: "student".to_string(),
+            access_token: "secret-access".to_string(),
+            refresh_token: Some("secret-refresh".to_string()),
+            expires_at: Some(Timestamp::from_millis(60_000)),
+        };
+        assert!(!session.needs_refresh(Timestamp::from_millis(29_999), 30_000));
+        assert!(session.needs_refresh(Timestamp::from_millis(30_000), 30_000));
+        assert!(!session.is_expired(Timestamp::from_millis(59_999)));
+        assert!(session.is_expired(Timestamp::from_millis(60_000)));
+
+        let unlimited = Session {
+            expires_at: None,
+            ..session.clone()
+        };
+        assert!(!unlimited.needs_refresh(Timestamp::from_millis(u64::MAX), 30_000));
+        assert!(!format!("{session:?}").contains("secret"));
+    }
+
+    #[test]
+    fn login_requires_username_and_password() {
+        let credentials = LoginCredentials::try_new("  student ", "p@ss").unwrap();
+        assert_eq!(credentials.username(), "student");
+        assert_eq!(
+            LoginCredentials::try_new(" ", "p@ss"),
+            Err(DomainError::EmptyUsername)
+        );
+        assert_eq!(
+            LoginCredentials::try_new("student", ""),
+            Err(DomainError::EmptyPassword)
+        );
+        assert!(!format!("{credentials:?}").contains("p@ss"));
+    }
+}
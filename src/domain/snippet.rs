@@ -0,0 +1,193 @@
+//! Фрагменты кода из ответа модели.
+//!
+//! Ответ в Markdown может содержать блоки кода в ограничителях
+//! (```` ```rust ```` … ```` ``` ````). Здесь они выделяются в список
+//! `CodeSnippet`, а язык из строки‑ограничителя приводится к `CodeLanguage`.
+use super::entities::AskResult;
+
+/// Язык фрагмента кода.
+///
+/// Распознаются языки, для которых UI умеет подсвечивать синтаксис.
+/// Прочие метки сохраняются в `Unknown`, отсутствие метки — `PlainText`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CodeLanguage {
+    Rust,
+    Python,
+    Sql,
+    Shell,
+    Json,
+    PlainText,
+    Unknown(String),
+}
+
+impl CodeLanguage {
+    /// Определяет язык по метке блока кода (`rust`, `py`, `bash`, …).
+    pub fn from_tag(tag: Option<&str>) -> Self {
+        let Some(tag) = tag.map(str::trim).filter(|tag| !tag.is_empty()) else {
+            return CodeLanguage::PlainText;
+        };
+        match tag.to_ascii_lowercase().as_str() {
+            "rust" | "rs" => CodeLanguage::Rust,
+            "python" | "py" | "python3" => CodeLanguage::Python,
+            "sql" | "postgresql" | "postgres" | "mysql" | "sqlite" => CodeLanguage::Sql,
+            "sh" | "bash" | "shell" | "zsh" | "console" | "shell-session" => CodeLanguage::Shell,
+            "json" | "jsonc" => CodeLanguage::Json,
+            "text" | "txt" | "plain" | "plaintext" => CodeLanguage::PlainText,
+            other => CodeLanguage::Unknown(other.to_string()),
+        }
+    }
+
+    /// Название языка для подписи блока.
+    pub fn label(&self) -> &str {
+        match self {
+            CodeLanguage::Rust => "Rust",
+            CodeLanguage::Python => "Python",
+            CodeLanguage::Sql => "SQL",
+            CodeLanguage::Shell => "Shell",
+            CodeLanguage::Json => "JSON",
+            CodeLanguage::PlainText => "Текст",
+            CodeLanguage::Unknown(tag) => tag,
+        }
+    }
+
+    /// Расширение файла для сохранения фрагмента.
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            CodeLanguage::Rust => "rs",
+            CodeLanguage::Python => "py",
+            CodeLanguage::Sql => "sql",
+            CodeLanguage::Shell => "sh",
+            CodeLanguage::Json => "json",
+            CodeLanguage::PlainText | CodeLanguage::Unknown(_) => "txt",
+        }
+    }
+}
+
+/// Фрагмент кода из ответа.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeSnippet {
+    pub language: CodeLanguage,
+    pub code: String,
+}
+
+impl CodeSnippet {
+    pub fn new(language: CodeLanguage, code: impl Into<String>) -> Self {
+        Self {
+            language,
+            code: code.into(),
+        }
+    }
+
+    /// Имя файла для скачивания, например `snippet.rs`.
+    pub fn file_name(&self) -> String {
+        format!("snippet.{}", self.language.file_extension())
+    }
+
+    /// Выделяет блоки кода из Markdown‑текста.
+    ///
+    /// Незакрытый блок продолжается до конца текста — так же его
+    /// показывает и UI.
+    pub fn extract_all(text: &str) -> Vec<CodeSnippet> {
+        let mut snippets = Vec::new();
+        let mut lines = text.lines();
+        while let Some(line) = lines.next() {
+            let Some((fence, tag)) = fence_open(line.trim()) else {
+                continue;
+            };
+            let mut code = Vec::new();
+            for line in lines.by_ref() {
+                if is_fence_close(line, &fence) {
+                    break;
+                }
+                code.push(line);
+            }
+            snippets.push(CodeSnippet::new(
+                CodeLanguage::from_tag(tag),
+                code.join("\n"),
+            ));
+        }
+        snippets
+    }
+}
+
+impl AskResult {
+    /// Фрагменты кода, найденные в ответе.
+    pub fn code_snippets(&self) -> Vec<CodeSnippet> {
+        CodeSnippet::extract_all(&self.answer)
+    }
+}
+
+/// Открывающий ограничитель: сам ограничитель и метка языка.
+///
+/// Используется и при разборе Markdown в UI, чтобы блоки кода там
+/// совпадали с извлечёнными фрагментами.
+pub(crate) fn fence_open(line: &str) -> Option<(String, Option<&str>)> {
+    let marker = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let count = line.chars().take_while(|c| *c == marker).count();
+    if count < 3 {
+        return None;
+    }
+    let info = line[count..].trim();
+    if marker == '`' && info.contains('`') {
+        return None;
+    }
+    Some((
+        marker.to_string().repeat(count),
+        info.split_whitespace().next(),
+    ))
+}
+
+/// Закрывающий ограничитель: те же символы, не короче открывающего.
+pub(crate) fn is_fence_close(line: &str, fence: &str) -> bool {
+    let trimmed = line.trim();
+    let marker = fence.chars().next().unwrap_or('`');
+    trimmed.starts_with(fence) && trimmed.chars().all(|c| c == marker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::AnswerSource;
+
+    #[test]
+    fn extracts_snippets_with_languages() {
+        let result = AskResult {
+            answer: "Пример:\n```rust\nfn main() {}\n```\nи запрос:\n~~~SQL\nSELECT 1;\n~~~\n```\nplain\n```"
+                .to_string(),
+            source: AnswerSource::Mock,
+            system_prompt_applied: false,
+            cached: false,
+        };
+        assert_eq!(
+            result.code_snippets(),
+            vec![
+                CodeSnippet::new(CodeLanguage::Rust, "fn main() {}"),
+                CodeSnippet::new(CodeLanguage::Sql, "SELECT 1;"),
+                CodeSnippet::new(CodeLanguage::PlainText, "plain"),
+            ]
+        );
+    }
+
+    #[test]
+    fn longer_fence_keeps_inner_fences_and_unclosed_runs_to_end() {
+        let snippets = CodeSnippet::extract_all("````md\n```\ninner\n```\n````\n```py\nprint(1)");
+        assert_eq!(snippets[0].code, "```\ninner\n```");
+        assert_eq!(
+            snippets[1],
+            CodeSnippet::new(CodeLanguage::Python, "print(1)")
+        );
+    }
+
+    #[test]
+    fn file_name_follows_language() {
+        assert_eq!(
+            CodeSnippet::new(CodeLanguage::from_tag(Some("bash")), "ls").file_name(),
+            "snippet.sh"
+        );
+        assert_eq!(
+            CodeSnippet::new(CodeLanguage::from_tag(Some("kotlin")), "").file_name(),
+            "snippet.txt"
+        );
+        assert_eq!(CodeLanguage::from_tag(Some("JSON")), CodeLanguage::Json);
+    }
+}
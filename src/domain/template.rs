@@ -0,0 +1,219 @@
+//! Шаблоны вопросов с переменными.
+//!
+//! Шаблон — это текст с подстановками вида `{{имя}}`, например
+//! `Объясни ошибку: {{error}}`. Правила:
+//! - имя переменной состоит из букв, цифр и `_` (пробелы внутри скобок
+//!   допускаются и отбрасываются);
+//! - каждая `{{` должна закрываться `}}`;
+//! - при отрисовке должны быть заданы все переменные шаблона, а лишние
+//!   значения считаются ошибкой — так опечатка в имени не теряется молча.
+//!
+//! Результат отрисовки проходит через `QuestionPolicy` и превращается в `Question`.
+use std::collections::BTreeMap;
+
+use super::errors::DomainError;
+use super::value_objects::{Question, QuestionPolicy};
+
+/// Часть разобранного шаблона.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Variable(String),
+}
+
+/// Шаблон вопроса.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptTemplate {
+    name: String,
+    body: String,
+    segments: Vec<Segment>,
+}
+
+impl PromptTemplate {
+    /// Создаёт шаблон: проверяет название и разбирает подстановки.
+    pub fn try_new(name: impl Into<String>, body: impl Into<String>) -> Result<Self, DomainError> {
+        let name = name.into().trim().to_string();
+        if name.is_empty() {
+            return Err(DomainError::EmptyTemplateName);
+        }
+        let body = body.into();
+        if body.trim().is_empty() {
+            return Err(DomainError::EmptyTemplateBody);
+        }
+        let segments = parse_segments(&body)?;
+        Ok(Self {
+            name,
+            body,
+            segments,
+        })
+    }
+
+    /// Название шаблона.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Исходный текст шаблона.
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Имена переменных в порядке первого появления (без повторов).
+    pub fn variables(&self) -> Vec<&str> {
+        let mut variables: Vec<&str> = Vec::new();
+        for segment in &self.segments {
+            if let Segment::Variable(name) = segment {
+                if !variables.contains(&name.as_str()) {
+                    variables.push(name);
+                }
+            }
+        }
+        variables
+    }
+
+    /// Подставляет значения и превращает результат в вопрос.
+    ///
+    /// Пустое (из пробелов) значение считается незаданным.
+    pub fn render(
+        &self,
+        values: &BTreeMap<String, String>,
+        policy: &QuestionPolicy,
+    ) -> Result<Question, DomainError> {
+        let variables = self.variables();
+        let missing: Vec<String> = variables
+            .iter()
+            .filter(|name| {
+                values
+                    .get(**name)
+                    .is_none_or(|value| value.trim().is_empty())
+            })
+            .map(|name| name.to_string())
+            .collect();
+        if !missing.is_empty() {
+            return Err(DomainError::TemplateMissingVariables(missing));
+        }
+        let unused: Vec<String> = values
+            .keys()
+            .filter(|key| !variables.contains(&key.as_str()))
+            .cloned()
+            .collect();
+        if !unused.is_empty() {
+            return Err(DomainError::TemplateUnusedVariables(unused));
+        }
+
+        let text: String = self
+            .segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => text.as_str(),
+                Segment::Variable(name) => values[name].as_str(),
+            })
+            .collect();
+        policy.apply(text)
+    }
+}
+
+/// Разбирает текст шаблона на фрагменты текста и переменные.
+fn parse_segments(body: &str) -> Result<Vec<Segment>, DomainError> {
+    let mut segments = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            segments.push(Segment::Text(rest[..start].to_string()));
+        }
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or(DomainError::TemplateUnclosedPlaceholder)?;
+        let name = after[..end].trim();
+        if name.is_empty() {
+            return Err(DomainError::TemplateEmptyPlaceholder);
+        }
+        if !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(DomainError::TemplateInvalidPlaceholder(name.to_string()));
+        }
+        segments.push(Segment::Variable(name.to_string()));
+        rest = &after[end + 2..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest.to_string()));
+    }
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn template_lists_variables_once_in_order() {
+        let template =
+            PromptTemplate::try_new("Ошибка", "{{ lang }}: объясни {{error}} на {{lang}}").unwrap();
+        assert_eq!(template.variables(), vec!["lang", "error"]);
+    }
+
+    #[test]
+    fn template_renders_question() {
+        let template = PromptTemplate::try_new("Ошибка", "Объясни ошибку: {{error}}").unwrap();
+        let question = template
+            .render(
+                &values(&[("error", "E0382 borrow of moved value")]),
+                &QuestionPolicy::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            question.as_str(),
+            "Объясни ошибку: E0382 borrow of moved value"
+        );
+    }
+
+    #[test]
+    fn template_reports_missing_and_unused_variables() {
+        let template = PromptTemplate::try_new("Перевод", "Переведи {{text}} на {{lang}}").unwrap();
+        let policy = QuestionPolicy::default();
+        assert_eq!(
+            template.render(&values(&[("text", "привет"), ("lang", " ")]), &policy),
+            Err(DomainError::TemplateMissingVariables(vec![
+                "lang".to_string()
+            ]))
+        );
+        assert_eq!(
+            template.render(
+                &values(&[("text", "привет"), ("lang", "en"), ("tone", "x")]),
+                &policy
+            ),
+            Err(DomainError::TemplateUnusedVariables(vec![
+                "tone".to_string()
+            ]))
+        );
+    }
+
+    #[test]
+    fn template_rejects_malformed_placeholders() {
+        assert_eq!(
+            PromptTemplate::try_new("t", "Текст {{error"),
+            Err(DomainError::TemplateUnclosedPlaceholder)
+        );
+        assert_eq!(
+            PromptTemplate::try_new("t", "Текст {{  }}"),
+            Err(DomainError::TemplateEmptyPlaceholder)
+        );
+        assert_eq!(
+            PromptTemplate::try_new("t", "Текст {{my-var}}"),
+            Err(DomainError::TemplateInvalidPlaceholder(
+                "my-var".to_string()
+            ))
+        );
+        assert_eq!(
+            PromptTemplate::try_new(" ", "Текст"),
+            Err(DomainError::EmptyTemplateName)
+        );
+    }
+}
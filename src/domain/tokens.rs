@@ -0,0 +1,209 @@
+//! Оценка размера запроса в токенах.
+//!
+//! Точное число токенов знает только токенизатор модели, а UI нужна
+//! быстрая оценка до отправки. Поэтому оценка вынесена в трейт
+//! `TokenEstimator`: по умолчанию используется эвристика, а при появлении
+//! настоящего токенизатора его можно подставить без изменений в UI.
+use super::conversation::Message;
+use super::errors::DomainError;
+
+/// Оценка размера запроса: вопрос и контекст диалога отдельно.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TokenEstimate {
+    pub question: u32,
+    pub context: u32,
+}
+
+impl TokenEstimate {
+    /// Общий размер запроса.
+    pub fn total(&self) -> u32 {
+        self.question.saturating_add(self.context)
+    }
+}
+
+/// Оценщик числа токенов.
+pub trait TokenEstimator {
+    /// Оценивает число токенов в тексте.
+    fn estimate_text(&self, text: &str) -> u32;
+
+    /// Накладные расходы на одно сообщение контекста (роль, разделители).
+    fn message_overhead(&self) -> u32 {
+        4
+    }
+
+    /// Оценивает вопрос вместе с контекстом диалога.
+    fn estimate(&self, question: &str, context: &[Message]) -> TokenEstimate {
+        let context = context
+            .iter()
+            .map(|message| {
+                self.estimate_text(&message.content)
+                    .saturating_add(self.message_overhead())
+            })
+            .fold(0u32, u32::saturating_add);
+        TokenEstimate {
+            question: self.estimate_text(question),
+            context,
+        }
+    }
+}
+
+/// Эвристическая оценка без словаря токенизатора.
+///
+/// Текст делится на слова и знаки препинания. Знак — один токен, слово —
+/// один токен на каждые `latin_chars_per_token` латинских символов или
+/// `other_chars_per_token` прочих (кириллица, цифры): русский текст
+/// токенизируется плотнее английского.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeuristicTokenEstimator {
+    pub latin_chars_per_token: u32,
+    pub other_chars_per_token: u32,
+}
+
+impl Default for HeuristicTokenEstimator {
+    fn default() -> Self {
+        Self {
+            latin_chars_per_token: 4,
+            other_chars_per_token: 3,
+        }
+    }
+}
+
+impl TokenEstimator for HeuristicTokenEstimator {
+    fn estimate_text(&self, text: &str) -> u32 {
+        let mut tokens = 0u32;
+        let mut latin = 0u32;
+        let mut other = 0u32;
+        let flush = |latin: &mut u32, other: &mut u32, tokens: &mut u32| {
+            *tokens = tokens
+                .saturating_add(latin.div_ceil(self.latin_chars_per_token.max(1)))
+                .saturating_add(other.div_ceil(self.other_chars_per_token.max(1)));
+            *latin = 0;
+            *other = 0;
+        };
+
+        for c in text.chars() {
+            if c.is_ascii_alphabetic() {
+                latin += 1;
+            } else if c.is_alphanumeric() || c == '_' {
+                other += 1;
+            } else {
+                flush(&mut latin, &mut other, &mut tokens);
+                if !c.is_whitespace() {
+                    tokens = tokens.saturating_add(1);
+                }
+            }
+        }
+        flush(&mut latin, &mut other, &mut tokens);
+        tokens
+    }
+}
+
+/// Бюджет токенов на один запрос.
+///
+/// Превышение бюджета не запрещает отправку — UI только предупреждает.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TokenBudget(u32);
+
+impl TokenBudget {
+    pub const MAX: u32 = 131_072;
+    pub const DEFAULT: u32 = 8_000;
+
+    pub fn try_new(value: u32) -> Result<Self, DomainError> {
+        if value == 0 || value > Self::MAX {
+            return Err(DomainError::InvalidTokenBudget(value.to_string()));
+        }
+        Ok(Self(value))
+    }
+
+    /// Разбирает бюджет из пользовательского ввода.
+    pub fn parse(value: &str) -> Result<Self, DomainError> {
+        let trimmed = value.trim();
+        let parsed = trimmed
+            .parse::<u32>()
+            .map_err(|_| DomainError::InvalidTokenBudget(trimmed.to_string()))?;
+        Self::try_new(parsed)
+    }
+
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+
+    /// На сколько токенов оценка превышает бюджет (если превышает).
+    pub fn excess(&self, estimate: &TokenEstimate) -> Option<u32> {
+        estimate
+            .total()
+            .checked_sub(self.0)
+            .filter(|excess| *excess > 0)
+    }
+}
+
+impl Default for TokenBudget {
+    fn default() -> Self {
+        Self(Self::DEFAULT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AnswerSource, AskResult, Conversation, Question, Timestamp};
+
+    #[test]
+    fn heuristic_counts_words_and_punctuation() {
+        let estimator = HeuristicTokenEstimator::default();
+        assert_eq!(estimator.estimate_text(""), 0);
+        assert_eq!(estimator.estimate_text("   \n"), 0);
+        // "hello" — 2 токена (5 / 4), "world" — 2, "!" — 1.
+        assert_eq!(estimator.estimate_text("hello world!"), 5);
+        // "Привет" — 2 токена (6 / 3), "," — 1, "мир" — 1.
+        assert_eq!(estimator.estimate_text("Привет, мир"), 4);
+    }
+
+    #[test]
+    fn estimate_includes_context_with_overhead() {
+        let mut conversation = Conversation::new();
+        conversation.record_exchange(
+            &Question::try_new("Что такое Rust?").unwrap(),
+            Timestamp::from_millis(1),
+            &AskResult {
+                answer: "Язык".to_string(),
+                source: AnswerSource::Mock,
+                system_prompt_applied: false,
+                cached: false,
+            },
+            Timestamp::from_millis(2),
+        );
+        let estimator = HeuristicTokenEstimator::default();
+        let estimate = estimator.estimate("А Go?", conversation.messages());
+
+        // "Что"(1) "такое"(2) "Rust"(1) "?"(1) + 4; "Язык"(2) + 4.
+        assert_eq!(estimate.context, 15);
+        assert_eq!(estimate.question, 3);
+        assert_eq!(estimate.total(), 18);
+    }
+
+    #[test]
+    fn budget_validates_and_reports_excess() {
+        assert_eq!(
+            TokenBudget::parse("0"),
+            Err(DomainError::InvalidTokenBudget("0".to_string()))
+        );
+        assert_eq!(
+            TokenBudget::parse("много"),
+            Err(DomainError::InvalidTokenBudget("много".to_string()))
+        );
+        let budget = TokenBudget::parse(" 10 ").unwrap();
+        let estimate = TokenEstimate {
+            question: 4,
+            context: 6,
+        };
+        assert_eq!(budget.excess(&estimate), None);
+        assert_eq!(
+            budget.excess(&TokenEstimate {
+                question: 5,
+                context: 7,
+            }),
+            Some(2)
+        );
+    }
+}
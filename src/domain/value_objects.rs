@@ -3,61 +3,356 @@
 //! Value object — это тип, который инкапсулирует правила валидности данных.
 //! В нашем проекте это:
 //! - `Question` — вопрос пользователя;
-//! - `ApiBaseUrl` — базовый URL API.
+//! - `QuestionPolicy` — правила нормализации и ограничения размера вопроса;
+//! - `SystemPrompt` — пользовательский системный промпт;
+//! - `ApiBaseUrl` — базовый URL API (с разбором на компоненты);
+//! - `Timestamp` — момент времени (для сообщений диалога).
 use super::errors::DomainError;
 
 /// Вопрос пользователя.
 ///
-/// Правило: вопрос не должен быть пустым.
+/// Правила задаёт `QuestionPolicy`: вопрос не должен быть пустым и не должен
+/// превышать лимиты по символам и строкам. Текст хранится уже нормализованным.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Question(String);
 
 impl Question {
-    /// Создаёт вопрос и валидирует его содержимое.
+    /// Создаёт вопрос и валидирует его по политике по умолчанию.
     pub fn try_new(value: impl Into<String>) -> Result<Self, DomainError> {
-        let value = value.into();
-        if value.trim().is_empty() {
+        QuestionPolicy::default().apply(value)
+    }
+
+    /// Возвращает текст вопроса.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Размер текста вопроса после нормализации.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuestionMetrics {
+    pub chars: usize,
+    pub lines: usize,
+}
+
+/// Политика для вопросов: нормализация текста и ограничения размера.
+///
+/// Один и тот же объект используется и в use‑cases (при отправке),
+/// и в UI (для подсказки под полем ввода), поэтому лимиты не расходятся.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuestionPolicy {
+    /// Максимальное количество символов (Unicode‑символов, не байтов).
+    pub max_chars: usize,
+    /// Максимальное количество строк.
+    pub max_lines: usize,
+    /// Удалять управляющие символы (кроме перевода строки и табуляции).
+    pub strip_control_chars: bool,
+    /// Убирать пробелы в начале/конце текста и в конце строк, а также
+    /// схлопывать подряд идущие пустые строки в одну.
+    pub normalize_whitespace: bool,
+}
+
+impl Default for QuestionPolicy {
+    fn default() -> Self {
+        Self {
+            max_chars: 4000,
+            max_lines: 100,
+            strip_control_chars: true,
+            normalize_whitespace: true,
+        }
+    }
+}
+
+impl QuestionPolicy {
+    /// Нормализует текст согласно политике (без проверки лимитов).
+    pub fn normalize(&self, value: &str) -> String {
+        let mut text = value.replace("\r\n", "\n").replace('\r', "\n");
+        if self.strip_control_chars {
+            text.retain(|c| !c.is_control() || c == '\n' || c == '\t');
+        }
+        if self.normalize_whitespace {
+            let mut lines: Vec<&str> = Vec::new();
+            for line in text.lines().map(str::trim_end) {
+                let previous_blank = lines.last().is_some_and(|last| last.is_empty());
+                if line.is_empty() && previous_blank {
+                    continue;
+                }
+                lines.push(line);
+            }
+            text = lines.join("\n").trim().to_string();
+        }
+        text
+    }
+
+    /// Возвращает размер текста после нормализации.
+    pub fn measure(&self, value: &str) -> QuestionMetrics {
+        let text = self.normalize(value);
+        QuestionMetrics {
+            chars: text.chars().count(),
+            lines: text.lines().count(),
+        }
+    }
+
+    /// Нормализует текст и проверяет его по правилам политики.
+    pub fn apply(&self, value: impl Into<String>) -> Result<Question, DomainError> {
+        let text = self.normalize(&value.into());
+        if text.trim().is_empty() {
             return Err(DomainError::EmptyQuestion);
         }
+        let chars = text.chars().count();
+        if chars > self.max_chars {
+            return Err(DomainError::QuestionTooLong {
+                max: self.max_chars,
+                actual: chars,
+            });
+        }
+        let lines = text.lines().count();
+        if lines > self.max_lines {
+            return Err(DomainError::QuestionTooManyLines {
+                max: self.max_lines,
+                actual: lines,
+            });
+        }
+        Ok(Question(text))
+    }
+}
+
+/// Системный промпт, задаваемый пользователем для одного запроса.
+///
+/// Правила: промпт не пустой и не длиннее `SystemPrompt::MAX_CHARS` символов.
+/// Пробелы по краям убираются.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SystemPrompt(String);
+
+impl SystemPrompt {
+    /// Максимальная длина промпта в символах.
+    pub const MAX_CHARS: usize = 2000;
+
+    /// Создаёт системный промпт и валидирует его.
+    pub fn try_new(value: impl Into<String>) -> Result<Self, DomainError> {
+        let value = value.into().trim().to_string();
+        if value.is_empty() {
+            return Err(DomainError::EmptySystemPrompt);
+        }
+        let chars = value.chars().count();
+        if chars > Self::MAX_CHARS {
+            return Err(DomainError::SystemPromptTooLong {
+                max: Self::MAX_CHARS,
+                actual: chars,
+            });
+        }
         Ok(Self(value))
     }
 
-    /// Возвращает исходный текст вопроса.
+    /// Возвращает текст промпта.
     pub fn as_str(&self) -> &str {
         &self.0
     }
 }
 
+/// Схема базового URL API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UrlScheme {
+    Http,
+    Https,
+}
+
+impl UrlScheme {
+    /// Возвращает схему в том виде, в котором она пишется в URL.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UrlScheme::Http => "http",
+            UrlScheme::Https => "https",
+        }
+    }
+}
+
 /// Базовый URL API.
 ///
-/// Правило: строка не должна быть пустой. Дополнительную проверку
-/// (например, корректность схемы) можно добавить позже.
+/// Правила:
+/// - схема — только `http` или `https`;
+/// - хост обязателен, порт (если указан) — число от 1 до 65535;
+/// - параметры запроса (`?...`) и фрагмент (`#...`) запрещены;
+/// - путь может содержать префикс (например, `/api/v1`).
+///
+/// При создании URL нормализуется один раз: схема и хост приводятся
+/// к нижнему регистру, завершающие слеши убираются.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ApiBaseUrl(String);
+pub struct ApiBaseUrl {
+    scheme: UrlScheme,
+    host: String,
+    port: Option<u16>,
+    path: String,
+    normalized: String,
+}
 
 impl ApiBaseUrl {
     /// Создаёт базовый URL и валидирует его.
     pub fn try_new(value: impl Into<String>) -> Result<Self, DomainError> {
         let value = value.into();
-        if value.trim().is_empty() {
+        let value = value.trim();
+        if value.is_empty() {
             return Err(DomainError::EmptyBaseUrl);
         }
-        Ok(Self(value))
+
+        let (scheme, rest) = value
+            .split_once("://")
+            .ok_or(DomainError::MissingUrlScheme)?;
+        let scheme = match scheme.to_ascii_lowercase().as_str() {
+            "http" => UrlScheme::Http,
+            "https" => UrlScheme::Https,
+            _ => return Err(DomainError::UnsupportedUrlScheme(scheme.to_string())),
+        };
+
+        if rest.contains('#') {
+            return Err(DomainError::UrlFragmentNotAllowed);
+        }
+        if rest.contains('?') {
+            return Err(DomainError::UrlQueryNotAllowed);
+        }
+
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, ""),
+        };
+        let (host, port) = parse_authority(authority)?;
+        let path = parse_path(path)?;
+
+        let mut normalized = format!("{}://{}", scheme.as_str(), host);
+        if let Some(port) = port {
+            normalized.push_str(&format!(":{}", port));
+        }
+        normalized.push_str(&path);
+
+        Ok(Self {
+            scheme,
+            host,
+            port,
+            path,
+            normalized,
+        })
     }
 
-    /// Возвращает строковое представление URL.
+    /// Возвращает нормализованное строковое представление URL.
     pub fn as_str(&self) -> &str {
-        &self.0
+        &self.normalized
+    }
+
+    /// Схема URL.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn scheme(&self) -> UrlScheme {
+        self.scheme
+    }
+
+    /// Хост (в нижнем регистре; IPv6‑адрес — в квадратных скобках).
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Явно указанный порт, если он есть.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    /// Префикс пути без завершающего слеша (пустая строка, если префикса нет).
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn path(&self) -> &str {
+        &self.path
     }
 
     /// Склеивает базовый URL и путь эндпоинта.
     ///
-    /// Метод нормализует слеши, чтобы `base/` + `/health` корректно
-    /// превращались в `base/health`.
+    /// Префикс пути базового URL сохраняется: `http://host/api/v1` +
+    /// `/health` превращается в `http://host/api/v1/health`.
     pub fn join(&self, path: &str) -> String {
-        let base = self.0.trim_end_matches('/');
         let path = path.trim_start_matches('/');
-        format!("{}/{}", base, path)
+        format!("{}/{}", self.normalized, path)
+    }
+}
+
+/// Разбирает часть URL `host[:port]`.
+fn parse_authority(authority: &str) -> Result<(String, Option<u16>), DomainError> {
+    if authority.is_empty() {
+        return Err(DomainError::MissingUrlHost);
+    }
+    if authority.contains('@') {
+        return Err(DomainError::InvalidUrlHost(authority.to_string()));
+    }
+
+    let (host, port) = if let Some(ipv6) = authority.strip_prefix('[') {
+        let (address, tail) = ipv6
+            .split_once(']')
+            .ok_or_else(|| DomainError::InvalidUrlHost(authority.to_string()))?;
+        let valid = !address.is_empty()
+            && address
+                .chars()
+                .all(|c| c.is_ascii_hexdigit() || c == ':' || c == '.');
+        if !valid {
+            return Err(DomainError::InvalidUrlHost(authority.to_string()));
+        }
+        let port = match tail {
+            "" => None,
+            _ => Some(
+                tail.strip_prefix(':')
+                    .ok_or_else(|| DomainError::InvalidUrlHost(authority.to_string()))?,
+            ),
+        };
+        (format!("[{}]", address.to_ascii_lowercase()), port)
+    } else {
+        let (host, port) = match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        };
+        if host.is_empty() {
+            return Err(DomainError::MissingUrlHost);
+        }
+        let valid = host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+            && !host.starts_with(['-', '.'])
+            && !host.ends_with('-');
+        if !valid {
+            return Err(DomainError::InvalidUrlHost(host.to_string()));
+        }
+        (host.to_ascii_lowercase(), port)
+    };
+
+    let port = match port {
+        None => None,
+        Some(raw) => match raw.parse::<u16>() {
+            Ok(port) if port > 0 => Some(port),
+            _ => return Err(DomainError::InvalidUrlPort(raw.to_string())),
+        },
+    };
+    Ok((host, port))
+}
+
+/// Проверяет префикс пути и убирает завершающие слеши.
+fn parse_path(path: &str) -> Result<String, DomainError> {
+    if path.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(DomainError::InvalidUrlPath(path.to_string()));
+    }
+    Ok(path.trim_end_matches('/').to_string())
+}
+
+/// Момент времени в миллисекундах с начала эпохи Unix.
+///
+/// Домен не обращается к часам браузера напрямую: время передаётся снаружи
+/// (через порт `Clock`), поэтому сущности можно тестировать на хосте.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+    /// Создаёт метку времени из количества миллисекунд.
+    pub fn from_millis(millis: u64) -> Self {
+        Self(millis)
+    }
+
+    /// Возвращает количество миллисекунд с начала эпохи Unix.
+    pub fn as_millis(&self) -> u64 {
+        self.0
     }
 }
 
@@ -71,10 +366,114 @@ mod tests {
         assert!(Question::try_new("Что такое Rust?").is_ok());
     }
 
+    #[test]
+    fn question_policy_normalizes_text() {
+        let policy = QuestionPolicy::default();
+        let question = policy
+            .apply("  Первая строка  \r\n\n\n\nВторая\u{0007} строка\t\n  ")
+            .unwrap();
+        assert_eq!(question.as_str(), "Первая строка\n\nВторая строка");
+    }
+
+    #[test]
+    fn question_policy_enforces_limits() {
+        let policy = QuestionPolicy {
+            max_chars: 5,
+            max_lines: 2,
+            ..QuestionPolicy::default()
+        };
+        assert_eq!(
+            policy.apply("Привет, мир"),
+            Err(DomainError::QuestionTooLong { max: 5, actual: 11 })
+        );
+        assert_eq!(
+            policy.apply("а\nб\nв"),
+            Err(DomainError::QuestionTooManyLines { max: 2, actual: 3 })
+        );
+        assert_eq!(policy.apply("\u{0000}\n"), Err(DomainError::EmptyQuestion));
+        assert_eq!(
+            policy.measure(" а\nб "),
+            QuestionMetrics { chars: 3, lines: 2 }
+        );
+    }
+
+    #[test]
+    fn system_prompt_is_trimmed_and_limited() {
+        let prompt = SystemPrompt::try_new("  Отвечай кратко. ").unwrap();
+        assert_eq!(prompt.as_str(), "Отвечай кратко.");
+        assert_eq!(
+            SystemPrompt::try_new(" \n "),
+            Err(DomainError::EmptySystemPrompt)
+        );
+        let long = "я".repeat(SystemPrompt::MAX_CHARS + 1);
+        assert_eq!(
+            SystemPrompt::try_new(long),
+            Err(DomainError::SystemPromptTooLong {
+                max: SystemPrompt::MAX_CHARS,
+                actual: SystemPrompt::MAX_CHARS + 1,
+            })
+        );
+    }
+
     #[test]
     fn api_base_url_join_handles_slashes() {
         let base = ApiBaseUrl::try_new("http://localhost:8000/").unwrap();
         assert_eq!(base.join("/health"), "http://localhost:8000/health");
         assert_eq!(base.join("ask"), "http://localhost:8000/ask");
     }
+
+    #[test]
+    fn api_base_url_parses_components() {
+        let base = ApiBaseUrl::try_new(" HTTPS://Example.COM:8443/api/v1// ").unwrap();
+        assert_eq!(base.scheme(), UrlScheme::Https);
+        assert_eq!(base.host(), "example.com");
+        assert_eq!(base.port(), Some(8443));
+        assert_eq!(base.path(), "/api/v1");
+        assert_eq!(base.as_str(), "https://example.com:8443/api/v1");
+
+        let ipv6 = ApiBaseUrl::try_new("http://[::1]:8000").unwrap();
+        assert_eq!(ipv6.host(), "[::1]");
+        assert_eq!(ipv6.port(), Some(8000));
+    }
+
+    #[test]
+    fn api_base_url_join_keeps_path_prefix() {
+        let base = ApiBaseUrl::try_new("http://host/api/v1/").unwrap();
+        assert_eq!(base.join("/health"), "http://host/api/v1/health");
+        assert_eq!(base.join("ask"), "http://host/api/v1/ask");
+    }
+
+    #[test]
+    fn api_base_url_rejects_invalid_values() {
+        let cases = [
+            ("", DomainError::EmptyBaseUrl),
+            ("localhost:8000", DomainError::MissingUrlScheme),
+            (
+                "ftp://x",
+                DomainError::UnsupportedUrlScheme("ftp".to_string()),
+            ),
+            ("http://", DomainError::MissingUrlHost),
+            ("http://:8000", DomainError::MissingUrlHost),
+            (
+                "http://exa mple",
+                DomainError::InvalidUrlHost("exa mple".to_string()),
+            ),
+            (
+                "http://host:0",
+                DomainError::InvalidUrlPort("0".to_string()),
+            ),
+            (
+                "http://host:http",
+                DomainError::InvalidUrlPort("http".to_string()),
+            ),
+            ("http://host/api?x=1", DomainError::UrlQueryNotAllowed),
+            (
+                "http://host/api?x=1#frag",
+                DomainError::UrlFragmentNotAllowed,
+            ),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(ApiBaseUrl::try_new(value), Err(expected), "{value}");
+        }
+    }
 }
@@ -0,0 +1,97 @@
+//! Хранилище кэша ответов в `localStorage` браузера.
+//!
+//! Реализует порт `AnswerCacheStore`: записи хранятся одним JSON‑массивом
+//! в порядке использования, от давних к недавним.
+use gloo_storage::errors::StorageError as GlooStorageError;
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::application::ports::{AnswerCacheStore, CachedAnswer, StorageError};
+use crate::domain::{AskResult, Timestamp};
+use crate::infrastructure::history_storage::{source_from_stored, source_to_stored};
+
+const ANSWER_CACHE_KEY: &str = "rust_gigachat_webapp.answer_cache";
+
+/// Формат хранения записи кэша.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct StoredCachedAnswer {
+    key: String,
+    answer: String,
+    source: String,
+    system_prompt_applied: bool,
+    stored_at: u64,
+}
+
+impl From<&CachedAnswer> for StoredCachedAnswer {
+    fn from(entry: &CachedAnswer) -> Self {
+        Self {
+            key: entry.key.clone(),
+            answer: entry.result.answer.clone(),
+            source: source_to_stored(&entry.result.source),
+            system_prompt_applied: entry.result.system_prompt_applied,
+            stored_at: entry.stored_at.as_millis(),
+        }
+    }
+}
+
+impl From<StoredCachedAnswer> for CachedAnswer {
+    fn from(stored: StoredCachedAnswer) -> Self {
+        Self {
+            key: stored.key,
+            result: AskResult {
+                answer: stored.answer,
+                source: source_from_stored(&stored.source),
+                system_prompt_applied: stored.system_prompt_applied,
+                cached: false,
+            },
+            stored_at: Timestamp::from_millis(stored.stored_at),
+        }
+    }
+}
+
+/// Кэш ответов в `localStorage`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalStorageAnswerCache;
+
+impl AnswerCacheStore for LocalStorageAnswerCache {
+    fn load(&self) -> Result<Vec<CachedAnswer>, StorageError> {
+        match LocalStorage::get::<Vec<StoredCachedAnswer>>(ANSWER_CACHE_KEY) {
+            Ok(stored) => Ok(stored.into_iter().map(CachedAnswer::from).collect()),
+            Err(GlooStorageError::KeyNotFound(_)) => Ok(Vec::new()),
+            Err(GlooStorageError::SerdeError(error)) => {
+                Err(StorageError::Corrupted(error.to_string()))
+            }
+            Err(error) => Err(StorageError::Unavailable(error.to_string())),
+        }
+    }
+
+    fn save(&self, entries: &[CachedAnswer]) -> Result<(), StorageError> {
+        let stored: Vec<StoredCachedAnswer> =
+            entries.iter().map(StoredCachedAnswer::from).collect();
+        LocalStorage::set(ANSWER_CACHE_KEY, stored)
+            .map_err(|error| StorageError::Unavailable(error.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::AnswerSource;
+
+    #[test]
+    fn stored_answer_round_trips_without_cached_flag() {
+        let entry = CachedAnswer {
+            key: "что такое rust?".to_string(),
+            result: AskResult {
+                answer: "Язык".to_string(),
+                source: AnswerSource::GigaChat,
+                system_prompt_applied: true,
+                cached: false,
+            },
+            stored_at: Timestamp::from_millis(1_700_000_000_000),
+        };
+        let json = serde_json::to_string(&StoredCachedAnswer::from(&entry)).unwrap();
+        let restored: StoredCachedAnswer = serde_json::from_str(&json).unwrap();
+        assert_eq!(CachedAnswer::from(restored), entry);
+    }
+}
@@ -3,38 +3,284 @@
 //! Реализация следует принципу "инфраструктура как адаптер": она реализует
 //! интерфейсы из слоя application, но не влияет на доменные модели.
 //! Здесь же находится преобразование JSON в структуры домена.
+//!
+//! Учётные данные для запроса выдаёт `AuthProvider`: клиент кладёт их
+//! в заголовок, а на ответ 401 один раз просит провайдера обновить их
+//! и повторяет запрос.
+//!
+//! Каждый запрос привязан к `AbortController`: он прерывается по таймауту
+//! клиента или по токену отмены из `AskRequest`.
+//!
+//! Потоковый ответ читается из `POST /ask/stream` как Server‑Sent Events:
+//! события без типа несут `{"delta": "..."}`, событие `done` — сведения
+//! об ответе, событие `error` — ошибку в формате `POST /ask`.
+//!
+//! Сессией пользователя клиент управляет через `POST /auth/login`,
+//! `POST /auth/refresh` и `POST /auth/logout`.
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
+
 use async_trait::async_trait;
-use gloo_net::http::Request;
+use futures::StreamExt;
+use gloo_net::http::{Request, Response};
+use gloo_timers::callback::Timeout;
+use js_sys::{Reflect, Uint8Array};
 use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AbortController, AbortSignal, ReadableStreamDefaultReader};
 
-use crate::application::ports::{ChatGateway, GatewayError, HealthGateway};
-use crate::domain::{ApiBaseUrl, AskResult, HealthStatus, Question};
+use super::clock::BrowserClock;
+use super::sse::{SseDecoder, SseEvent};
+use crate::application::ports::{
+    AnswerChunk, AnswerStream, ApiError, ApiErrorCode, AskRequest, AuthProvider, CancellationToken,
+    ChatGateway, Clock, GatewayError, HealthGateway, SessionGateway, StreamingChatGateway,
+};
+use crate::application::NoAuth;
+use crate::domain::{
+    AnswerSource, ApiBaseUrl, AskResult, HealthStatus, LoginCredentials, Message, ServiceState,
+    Session, Timestamp,
+};
 
 /// HTTP‑клиент, использующий `gloo-net`.
 ///
-/// В реальном проекте здесь могли бы добавиться таймауты, ретраи,
-/// заголовки авторизации и т.п.
-#[derive(Clone, Debug)]
+/// Повторы запросов вынесены в декоратор `RetryingGateway`; сам клиент
+/// делает одну попытку и ограничивает её таймаутом.
+#[derive(Clone)]
 pub struct ApiClient {
     base_url: ApiBaseUrl,
+    timeout_ms: u32,
+    auth: Rc<dyn AuthProvider>,
+}
+
+impl fmt::Debug for ApiClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiClient")
+            .field("base_url", &self.base_url)
+            .field("timeout_ms", &self.timeout_ms)
+            .finish_non_exhaustive()
+    }
 }
 
 impl ApiClient {
-    /// Создаёт клиент с заданным базовым URL.
+    /// Таймаут запроса по умолчанию.
+    pub const DEFAULT_TIMEOUT_MS: u32 = 60_000;
+
+    /// Создаёт клиент с заданным базовым URL и таймаутом по умолчанию.
     pub fn new(base_url: ApiBaseUrl) -> Self {
-        Self { base_url }
+        Self {
+            base_url,
+            timeout_ms: Self::DEFAULT_TIMEOUT_MS,
+            auth: Rc::new(NoAuth),
+        }
+    }
+
+    /// Задаёт таймаут запроса в миллисекундах (`0` — без таймаута).
+    pub fn with_timeout(mut self, timeout_ms: u32) -> Self {
+        self.timeout_ms = timeout_ms;
+        self
+    }
+
+    /// Задаёт источник учётных данных для запросов.
+    pub fn with_auth(mut self, auth: Rc<dyn AuthProvider>) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Собирает запрос с сигналом прерывания и учётными данными
+    /// и отправляет его.
+    ///
+    /// Возвращает ответ вместе с `AbortGuard`, который продолжает следить
+    /// за таймаутом и отменой, пока читается тело. Если backend не принял
+    /// учётные данные, а провайдер сумел их обновить, запрос повторяется
+    /// один раз — поэтому `build` может вызываться дважды.
+    async fn send(
+        &self,
+        build: impl Fn(&AbortSignal) -> Result<Request, gloo_net::Error>,
+        cancellation: &CancellationToken,
+    ) -> Result<(Response, AbortGuard), GatewayError> {
+        let mut reauthenticated = false;
+        loop {
+            let credentials = self.auth.credentials(cancellation).await?;
+            let guard = AbortGuard::new(self.timeout_ms, cancellation)?;
+            let request =
+                build(&guard.signal()).map_err(|e| GatewayError::Network(e.to_string()))?;
+            if let Some(credentials) = &credentials {
+                let (name, value) = credentials.header();
+                request.headers().set(name, &value);
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|e| guard.error(e.to_string()))?;
+            if response.status() == 401
+                && credentials.is_some()
+                && !reauthenticated
+                && self.auth.invalidate().await
+            {
+                reauthenticated = true;
+                continue;
+            }
+            return Ok((response, guard));
+        }
+    }
+
+    /// Отправляет запрос и читает тело ответа целиком.
+    pub(super) async fn fetch(
+        &self,
+        build: impl Fn(&AbortSignal) -> Result<Request, gloo_net::Error>,
+        cancellation: &CancellationToken,
+    ) -> Result<(Response, String), GatewayError> {
+        let (response, guard) = self.send(build, cancellation).await?;
+        let text = guard.text(&response).await?;
+        Ok((response, text))
     }
 
     /// Формирует полный URL эндпоинта.
-    fn endpoint(&self, path: &str) -> String {
+    pub(super) fn endpoint(&self, path: &str) -> String {
         self.base_url.join(path)
     }
 }
 
+/// Прерывание запроса по таймауту или токену отмены.
+///
+/// Запрос прерывается через `AbortController`; по флагам guard отличает
+/// таймаут (`Timeout`) и отмену (`Cancelled`) от сетевого сбоя.
+pub(super) struct AbortGuard {
+    controller: AbortController,
+    cancellation: CancellationToken,
+    timeout_ms: u32,
+    timed_out: Rc<Cell<bool>>,
+    timer: Option<Timeout>,
+}
+
+impl AbortGuard {
+    pub(super) fn new(
+        timeout_ms: u32,
+        cancellation: &CancellationToken,
+    ) -> Result<Self, GatewayError> {
+        let controller = AbortController::new()
+            .map_err(|_| GatewayError::Network("AbortController недоступен".to_string()))?;
+        {
+            let controller = controller.clone();
+            cancellation.on_cancel(move || controller.abort());
+        }
+        let mut guard = Self {
+            controller,
+            cancellation: cancellation.clone(),
+            timeout_ms,
+            timed_out: Rc::new(Cell::new(false)),
+            timer: None,
+        };
+        guard.restart_timer();
+        Ok(guard)
+    }
+
+    /// Запускает таймаут заново (для потока — от последнего фрагмента).
+    fn restart_timer(&mut self) {
+        // Старый `Timeout` отменяется при удалении.
+        self.timer = (self.timeout_ms > 0).then(|| {
+            let controller = self.controller.clone();
+            let timed_out = self.timed_out.clone();
+            Timeout::new(self.timeout_ms, move || {
+                timed_out.set(true);
+                controller.abort();
+            })
+        });
+    }
+
+    pub(super) fn signal(&self) -> AbortSignal {
+        self.controller.signal()
+    }
+
+    fn aborted(&self) -> bool {
+        self.timed_out.get() || self.cancellation.is_cancelled()
+    }
+
+    pub(super) fn error(&self, message: String) -> GatewayError {
+        transport_error(
+            self.timed_out.get(),
+            self.cancellation.is_cancelled(),
+            self.timeout_ms,
+            message,
+        )
+    }
+
+    /// Тело ответа целиком; прерванное чтение даёт ошибку, а нечитаемое
+    /// тело — пустую строку.
+    pub(super) async fn text(&self, response: &Response) -> Result<String, GatewayError> {
+        match response.text().await {
+            Ok(text) => Ok(text),
+            Err(error) if self.aborted() => Err(self.error(error.to_string())),
+            Err(_) => Ok(String::new()),
+        }
+    }
+
+    /// Прерывает запрос (для завершённого запроса ничего не делает).
+    fn abort(&self) {
+        self.controller.abort();
+    }
+}
+
 /// DTO запроса к `POST /ask`.
+///
+/// Поле `history` отправляется только для продолжения диалога, а параметры
+/// генерации и `system_prompt` — только если они заданы. Backend, который
+/// не знает о них, просто проигнорирует лишние ключи.
 #[derive(Debug, Serialize)]
 struct AskRequestDto {
     question: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    history: Vec<MessageDto>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_prompt: Option<String>,
+}
+
+impl From<&AskRequest> for AskRequestDto {
+    fn from(request: &AskRequest) -> Self {
+        let generation = &request.generation;
+        Self {
+            question: request.question.as_str().to_string(),
+            history: request.context.iter().map(MessageDto::from).collect(),
+            temperature: generation.temperature.map(|value| value.value()),
+            top_p: generation.top_p.map(|value| value.value()),
+            max_tokens: generation.max_tokens.map(|value| value.value()),
+            model: generation
+                .model
+                .as_ref()
+                .map(|model| model.as_str().to_string()),
+            system_prompt: request
+                .system_prompt
+                .as_ref()
+                .map(|prompt| prompt.as_str().to_string()),
+        }
+    }
+}
+
+/// DTO одного сообщения из контекста диалога.
+#[derive(Debug, Serialize)]
+struct MessageDto {
+    role: &'static str,
+    content: String,
+}
+
+impl From<&Message> for MessageDto {
+    fn from(message: &Message) -> Self {
+        Self {
+            role: message.role.as_str(),
+            content: message.content.clone(),
+        }
+    }
 }
 
 /// DTO ответа от `POST /ask`.
@@ -45,6 +291,19 @@ struct AskResponseDto {
     system_prompt_applied: bool,
 }
 
+/// DTO фрагмента потокового ответа (событие без типа).
+#[derive(Debug, Deserialize)]
+struct StreamDeltaDto {
+    delta: String,
+}
+
+/// DTO события `done` потокового ответа.
+#[derive(Debug, Deserialize)]
+struct StreamDoneDto {
+    source: String,
+    system_prompt_applied: bool,
+}
+
 /// DTO ответа от `GET /health`.
 #[derive(Debug, Deserialize)]
 struct HealthResponseDto {
@@ -53,6 +312,85 @@ struct HealthResponseDto {
     gigachat_enabled: bool,
 }
 
+/// DTO запроса к `POST /auth/login`.
+#[derive(Debug, Serialize)]
+struct LoginRequestDto<'a> {
+    username: &'a str,
+    password: &'a str,
+}
+
+/// DTO запроса к `POST /auth/refresh`.
+#[derive(Debug, Serialize)]
+struct RefreshRequestDto<'a> {
+    refresh_token: &'a str,
+}
+
+/// DTO ответа со входа или продления сессии.
+///
+/// `expires_in` — срок действия токена в секундах. Backend может
+/// не присылать новый токен обновления — тогда остаётся прежний.
+#[derive(Debug, Deserialize)]
+struct SessionResponseDto {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+    username: Option<String>,
+}
+
+/// Сессия из ответа backend; срок действия отсчитывается от `now`.
+fn session_from_dto(payload: SessionResponseDto, previous: &Session, now: Timestamp) -> Session {
+    Session {
+        username: payload
+            .username
+            .unwrap_or_else(|| previous.username.clone()),
+        access_token: payload.access_token,
+        refresh_token: payload
+            .refresh_token
+            .or_else(|| previous.refresh_token.clone()),
+        expires_at: payload
+            .expires_in
+            .map(|seconds| Timestamp::from_millis(now.as_millis() + seconds * 1_000)),
+    }
+}
+
+/// Преобразует строковый источник ответа из DTO в доменный тип.
+fn answer_source_from_dto(raw: &str) -> AnswerSource {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "gigachat" => AnswerSource::GigaChat,
+        "mock" => AnswerSource::Mock,
+        _ => AnswerSource::Unknown(raw.to_string()),
+    }
+}
+
+/// Преобразует строковый статус из DTO в доменный тип.
+fn service_state_from_dto(raw: &str) -> ServiceState {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "ok" => ServiceState::Ok,
+        "degraded" => ServiceState::Degraded,
+        "down" | "error" => ServiceState::Down,
+        _ => ServiceState::Unknown(raw.to_string()),
+    }
+}
+
+/// Ошибка транспорта: прерванный запрос отличается от сетевого сбоя.
+fn transport_error(
+    timed_out: bool,
+    cancelled: bool,
+    timeout_ms: u32,
+    message: String,
+) -> GatewayError {
+    if cancelled {
+        GatewayError::Cancelled
+    } else if timed_out {
+        GatewayError::Timeout(timeout_ms)
+    } else {
+        GatewayError::Network(message)
+    }
+}
+
+/// Сколько символов тела без JSON‑ошибки показывать как сообщение.
+const MAX_ERROR_MESSAGE_CHARS: usize = 200;
+
 /// DTO ошибки API (если сервер вернул JSON с полем `error`).
 #[derive(Debug, Deserialize)]
 struct ErrorResponseDto {
@@ -60,78 +398,459 @@ struct ErrorResponseDto {
     code: Option<String>,
 }
 
+/// Ошибка API из JSON‑тела ответа, если оно в формате `ErrorResponseDto`.
+fn error_from_payload(status: Option<u16>, text: &str) -> Option<ApiError> {
+    let payload = serde_json::from_str::<ErrorResponseDto>(text).ok()?;
+    Some(ApiError {
+        status,
+        code: payload.code.as_deref().map(ApiErrorCode::parse),
+        message: payload.error,
+        body: text.to_string(),
+    })
+}
+
+/// Ошибка API по неуспешному HTTP‑ответу.
+///
+/// Если тело не содержит JSON‑ошибки, сообщением служит начало тела.
+/// Отказы в доступе (401, 403) выделены в отдельные варианты, чтобы UI
+/// предложил заново ввести учётные данные.
+pub(super) fn http_error(status: u16, text: String) -> GatewayError {
+    let error = error_from_payload(Some(status), &text).unwrap_or_else(|| {
+        let trimmed = text.trim();
+        let message = if trimmed.is_empty() {
+            "без описания".to_string()
+        } else {
+            trimmed.chars().take(MAX_ERROR_MESSAGE_CHARS).collect()
+        };
+        ApiError {
+            status: Some(status),
+            code: None,
+            message,
+            body: text,
+        }
+    });
+    match status {
+        401 => GatewayError::Unauthorized(error),
+        403 => GatewayError::Forbidden(error),
+        _ => GatewayError::Api(error),
+    }
+}
+
+/// Преобразует событие SSE во фрагмент ответа; неизвестные события
+/// (например, служебные `ping`) пропускаются.
+fn chunk_from_event(event: &SseEvent) -> Option<Result<AnswerChunk, GatewayError>> {
+    let chunk = match event.event.as_str() {
+        "message" => serde_json::from_str::<StreamDeltaDto>(&event.data)
+            .map(|payload| AnswerChunk::Delta(payload.delta))
+            .map_err(|_| GatewayError::InvalidPayload),
+        "done" => serde_json::from_str::<StreamDoneDto>(&event.data)
+            .map(|payload| AnswerChunk::Done {
+                source: answer_source_from_dto(&payload.source),
+                system_prompt_applied: payload.system_prompt_applied,
+                cached: false,
+            })
+            .map_err(|_| GatewayError::InvalidPayload),
+        "error" => Err(error_from_payload(None, &event.data)
+            .map(GatewayError::Api)
+            .unwrap_or(GatewayError::InvalidPayload)),
+        _ => return None,
+    };
+    Some(chunk)
+}
+
+/// Состояние чтения потокового ответа.
+struct StreamReader {
+    reader: ReadableStreamDefaultReader,
+    guard: AbortGuard,
+    decoder: SseDecoder,
+    pending: VecDeque<Result<AnswerChunk, GatewayError>>,
+    exhausted: bool,
+}
+
+impl StreamReader {
+    fn into_stream(self) -> AnswerStream {
+        futures::stream::unfold(self, |mut state| async move {
+            loop {
+                if let Some(item) = state.pending.pop_front() {
+                    // Ошибка или `done` завершают поток.
+                    if !matches!(item, Ok(AnswerChunk::Delta(_))) {
+                        state.pending.clear();
+                        state.exhausted = true;
+                    }
+                    return Some((item, state));
+                }
+                if state.exhausted {
+                    return None;
+                }
+                state.read().await;
+            }
+        })
+        .boxed_local()
+    }
+
+    /// Читает очередной кусок тела и раскладывает события в очередь.
+    async fn read(&mut self) {
+        let result = match JsFuture::from(self.reader.read()).await {
+            Ok(result) => result,
+            Err(error) => {
+                let message = error.as_string().unwrap_or_default();
+                self.pending.push_back(Err(self.guard.error(message)));
+                self.exhausted = true;
+                return;
+            }
+        };
+        self.guard.restart_timer();
+
+        let done = Reflect::get(&result, &JsValue::from_str("done"))
+            .ok()
+            .and_then(|value| value.as_bool())
+            .unwrap_or(true);
+        let mut events = Vec::new();
+        if let Ok(value) = Reflect::get(&result, &JsValue::from_str("value")) {
+            if let Some(bytes) = value.dyn_ref::<Uint8Array>() {
+                events = self.decoder.feed(&bytes.to_vec());
+            }
+        }
+        if done {
+            events.extend(self.decoder.finish());
+            self.exhausted = true;
+        }
+        self.pending
+            .extend(events.iter().filter_map(chunk_from_event));
+    }
+}
+
+impl Drop for StreamReader {
+    fn drop(&mut self) {
+        // Поток бросили на середине — соединение больше не нужно.
+        self.guard.abort();
+    }
+}
+
 #[async_trait(?Send)]
 impl ChatGateway for ApiClient {
-    async fn ask(&self, question: Question) -> Result<AskResult, GatewayError> {
+    async fn ask(&self, request: AskRequest) -> Result<AskResult, GatewayError> {
         let url = self.endpoint("/ask");
-        let payload = AskRequestDto {
-            question: question.as_str().to_string(),
-        };
-
-        let response = Request::post(&url)
-            .json(&payload)
-            .map_err(|e| GatewayError::Network(e.to_string()))?
-            .send()
-            .await
-            .map_err(|e| GatewayError::Network(e.to_string()))?;
+        let payload = AskRequestDto::from(&request);
 
+        let build = |signal: &AbortSignal| {
+            Request::post(&url)
+                .abort_signal(Some(signal))
+                .json(&payload)
+        };
+        let (response, text) = self.fetch(build, &request.cancellation).await?;
         let status = response.status();
-        let text = response.text().await.unwrap_or_default();
         if !response.ok() {
-            if let Ok(error_payload) = serde_json::from_str::<ErrorResponseDto>(&text) {
-                let code = error_payload.code.unwrap_or_else(|| "unknown".to_string());
-                return Err(GatewayError::Api(format!(
-                    "{} (код: {})",
-                    error_payload.error, code
-                )));
-            }
-            return Err(GatewayError::Api(format!("HTTP {}: {}", status, text)));
+            return Err(http_error(status, text));
         }
 
         let payload = match serde_json::from_str::<AskResponseDto>(&text) {
             Ok(parsed) => parsed,
             Err(_) => {
-                if let Ok(error_payload) = serde_json::from_str::<ErrorResponseDto>(&text) {
-                    let code = error_payload.code.unwrap_or_else(|| "unknown".to_string());
-                    return Err(GatewayError::Api(format!(
-                        "{} (код: {})",
-                        error_payload.error, code
-                    )));
-                }
-                return Err(GatewayError::InvalidPayload);
+                return Err(error_from_payload(Some(status), &text)
+                    .map(GatewayError::Api)
+                    .unwrap_or(GatewayError::InvalidPayload))
             }
         };
 
         Ok(AskResult {
             answer: payload.answer,
-            source: payload.source,
+            source: answer_source_from_dto(&payload.source),
             system_prompt_applied: payload.system_prompt_applied,
+            cached: false,
         })
     }
 }
 
+#[async_trait(?Send)]
+impl StreamingChatGateway for ApiClient {
+    async fn ask_stream(&self, request: AskRequest) -> Result<AnswerStream, GatewayError> {
+        let url = self.endpoint("/ask/stream");
+        let payload = AskRequestDto::from(&request);
+
+        let build = |signal: &AbortSignal| {
+            Request::post(&url)
+                .header("Accept", "text/event-stream")
+                .abort_signal(Some(signal))
+                .json(&payload)
+        };
+        let (response, guard) = self.send(build, &request.cancellation).await?;
+        let status = response.status();
+        // Старый backend не знает эндпоинта — вопрос уйдёт обычным `POST /ask`.
+        if matches!(status, 404 | 405 | 501) {
+            return Err(GatewayError::StreamingUnsupported);
+        }
+        if !response.ok() {
+            let text = guard.text(&response).await?;
+            return Err(http_error(status, text));
+        }
+
+        let body = response.body().ok_or(GatewayError::InvalidPayload)?;
+        let reader = body
+            .get_reader()
+            .unchecked_into::<ReadableStreamDefaultReader>();
+        Ok(StreamReader {
+            reader,
+            guard,
+            decoder: SseDecoder::new(),
+            pending: VecDeque::new(),
+            exhausted: false,
+        }
+        .into_stream())
+    }
+}
+
 #[async_trait(?Send)]
 impl HealthGateway for ApiClient {
     async fn health(&self) -> Result<HealthStatus, GatewayError> {
         let url = self.endpoint("/health");
-        let response = Request::get(&url)
-            .send()
-            .await
-            .map_err(|e| GatewayError::Network(e.to_string()))?;
-
+        let build = |signal: &AbortSignal| Request::get(&url).abort_signal(Some(signal)).build();
+        let (response, text) = self.fetch(build, &CancellationToken::new()).await?;
         let status = response.status();
-        let text = response.text().await.unwrap_or_default();
         if !response.ok() {
-            return Err(GatewayError::Api(format!("HTTP {}: {}", status, text)));
+            return Err(http_error(status, text));
         }
 
         let payload: HealthResponseDto =
             serde_json::from_str(&text).map_err(|_| GatewayError::InvalidPayload)?;
 
         Ok(HealthStatus {
-            status: payload.status,
+            status: service_state_from_dto(&payload.status),
             version: payload.version,
             gigachat_enabled: payload.gigachat_enabled,
         })
     }
 }
+
+impl ApiClient {
+    /// Отправляет запрос сессии и разбирает ответ с токенами.
+    async fn session_request(
+        &self,
+        path: &str,
+        payload: &impl Serialize,
+        previous: &Session,
+    ) -> Result<Session, GatewayError> {
+        let url = self.endpoint(path);
+        let build =
+            |signal: &AbortSignal| Request::post(&url).abort_signal(Some(signal)).json(payload);
+        let (response, text) = self.fetch(build, &CancellationToken::new()).await?;
+        if !response.ok() {
+            return Err(http_error(response.status(), text));
+        }
+        let payload = serde_json::from_str::<SessionResponseDto>(&text)
+            .map_err(|_| GatewayError::InvalidPayload)?;
+        Ok(session_from_dto(payload, previous, BrowserClock.now()))
+    }
+}
+
+#[async_trait(?Send)]
+impl SessionGateway for ApiClient {
+    async fn login(&self, credentials: &LoginCredentials) -> Result<Session, GatewayError> {
+        let payload = LoginRequestDto {
+            username: credentials.username(),
+            password: credentials.password(),
+        };
+        let anonymous = Session {
+            username: credentials.username().to_string(),
+            access_token: String::new(),
+            refresh_token: None,
+            expires_at: None,
+        };
+        self.session_request("/auth/login", &payload, &anonymous)
+            .await
+    }
+
+    async fn refresh(&self, session: &Session) -> Result<Session, GatewayError> {
+        let refresh_token = session
+            .refresh_token
+            .as_deref()
+            .ok_or(GatewayError::InvalidPayload)?;
+        let payload = RefreshRequestDto { refresh_token };
+        self.session_request("/auth/refresh", &payload, session)
+            .await
+    }
+
+    async fn logout(&self, session: &Session) -> Result<(), GatewayError> {
+        let url = self.endpoint("/auth/logout");
+        let authorization = format!("Bearer {}", session.access_token);
+        let build = |signal: &AbortSignal| {
+            Request::post(&url)
+                .header("Authorization", &authorization)
+                .abort_signal(Some(signal))
+                .build()
+        };
+        let (response, text) = self.fetch(build, &CancellationToken::new()).await?;
+        // Сессия, которую backend уже не знает, всё равно завершена.
+        if response.ok() || response.status() == 401 {
+            return Ok(());
+        }
+        Err(http_error(response.status(), text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{GenerationSettings, MaxTokens, Question, SystemPrompt, Temperature};
+
+    #[test]
+    fn dto_strings_map_to_domain_enums() {
+        assert_eq!(answer_source_from_dto("GigaChat"), AnswerSource::GigaChat);
+        assert_eq!(answer_source_from_dto("mock"), AnswerSource::Mock);
+        assert_eq!(
+            answer_source_from_dto("cache"),
+            AnswerSource::Unknown("cache".to_string())
+        );
+
+        assert_eq!(service_state_from_dto("OK"), ServiceState::Ok);
+        assert_eq!(service_state_from_dto("degraded"), ServiceState::Degraded);
+        assert_eq!(service_state_from_dto("down"), ServiceState::Down);
+        assert_eq!(
+            service_state_from_dto("starting"),
+            ServiceState::Unknown("starting".to_string())
+        );
+    }
+
+    #[test]
+    fn stream_events_map_to_chunks() {
+        let event = |event: &str, data: &str| SseEvent {
+            event: event.to_string(),
+            data: data.to_string(),
+        };
+
+        assert_eq!(
+            chunk_from_event(&event("message", r#"{"delta":"При"}"#)),
+            Some(Ok(AnswerChunk::Delta("При".to_string())))
+        );
+        assert_eq!(
+            chunk_from_event(&event(
+                "done",
+                r#"{"source":"gigachat","system_prompt_applied":true}"#
+            )),
+            Some(Ok(AnswerChunk::Done {
+                source: AnswerSource::GigaChat,
+                system_prompt_applied: true,
+                cached: false,
+            }))
+        );
+        assert_eq!(
+            chunk_from_event(&event("error", r#"{"error":"Лимит","code":"quota"}"#)),
+            Some(Err(GatewayError::Api(ApiError {
+                status: None,
+                code: Some(ApiErrorCode::Other("quota".to_string())),
+                message: "Лимит".to_string(),
+                body: r#"{"error":"Лимит","code":"quota"}"#.to_string(),
+            })))
+        );
+        assert_eq!(
+            chunk_from_event(&event("message", "не json")),
+            Some(Err(GatewayError::InvalidPayload))
+        );
+        assert_eq!(chunk_from_event(&event("ping", "")), None);
+    }
+
+    #[test]
+    fn http_errors_keep_status_code_and_body() {
+        let body = r#"{"error":"Question cannot be empty","code":"EMPTY_QUESTION"}"#;
+        let GatewayError::Api(error) = http_error(400, body.to_string()) else {
+            panic!("ожидалась ошибка API");
+        };
+        assert_eq!(error.status, Some(400));
+        assert_eq!(error.code, Some(ApiErrorCode::EmptyQuestion));
+        assert_eq!(error.message, "Question cannot be empty");
+        assert_eq!(error.body, body);
+        assert!(!error.retryable());
+
+        let GatewayError::Api(error) = http_error(503, "  ".to_string()) else {
+            panic!("ожидалась ошибка API");
+        };
+        assert_eq!(error.code, None);
+        assert_eq!(error.message, "без описания");
+        assert!(error.retryable());
+        assert_eq!(error.to_string(), "HTTP 503: без описания");
+
+        let unauthorized = http_error(401, r#"{"error":"Invalid API key"}"#.to_string());
+        assert!(
+            matches!(&unauthorized, GatewayError::Unauthorized(error) if error.message == "Invalid API key")
+        );
+        assert!(unauthorized.requires_auth());
+        assert!(!unauthorized.retryable());
+        assert!(matches!(
+            http_error(403, String::new()),
+            GatewayError::Forbidden(_)
+        ));
+    }
+
+    #[test]
+    fn session_response_keeps_previous_refresh_token() {
+        let previous = Session {
+            username: "student".to_string(),
+            access_token: "old".to_string(),
+            refresh_token: Some("refresh-1".to_string()),
+            expires_at: None,
+        };
+        let now = Timestamp::from_millis(5_000);
+
+        let payload: SessionResponseDto =
+            serde_json::from_str(r#"{"access_token":"new","expires_in":300}"#).unwrap();
+        let renewed = session_from_dto(payload, &previous, now);
+        assert_eq!(renewed.username, "student");
+        assert_eq!(renewed.access_token, "new");
+        assert_eq!(renewed.refresh_token.as_deref(), Some("refresh-1"));
+        assert_eq!(renewed.expires_at, Some(Timestamp::from_millis(305_000)));
+
+        let payload: SessionResponseDto = serde_json::from_str(
+            r#"{"access_token":"a","refresh_token":"refresh-2","username":"Student"}"#,
+        )
+        .unwrap();
+        let rotated = session_from_dto(payload, &previous, now);
+        assert_eq!(rotated.username, "Student");
+        assert_eq!(rotated.refresh_token.as_deref(), Some("refresh-2"));
+        assert_eq!(rotated.expires_at, None);
+    }
+
+    #[test]
+    fn aborted_requests_map_to_timeout_or_cancelled() {
+        let error = |timed_out, cancelled| {
+            transport_error(timed_out, cancelled, 5_000, "failed".to_string())
+        };
+        assert_eq!(
+            error(false, false),
+            GatewayError::Network("failed".to_string())
+        );
+        assert_eq!(error(true, false), GatewayError::Timeout(5_000));
+        // Отмена пользователем важнее таймаута, сработавшего следом.
+        assert_eq!(error(true, true), GatewayError::Cancelled);
+    }
+
+    #[test]
+    fn ask_request_dto_skips_unset_fields() {
+        let question = Question::try_new("Что такое Rust?").unwrap();
+        let plain = AskRequestDto::from(&AskRequest::new(question.clone()));
+        assert_eq!(
+            serde_json::to_value(&plain).unwrap(),
+            serde_json::json!({ "question": "Что такое Rust?" })
+        );
+
+        let generation = GenerationSettings {
+            temperature: Some(Temperature::try_new(0.5).unwrap()),
+            max_tokens: Some(MaxTokens::try_new(256).unwrap()),
+            ..GenerationSettings::default()
+        };
+        let prompt = SystemPrompt::try_new("Отвечай кратко").unwrap();
+        let tuned = AskRequestDto::from(
+            &AskRequest::new(question)
+                .with_generation(generation)
+                .with_system_prompt(Some(prompt)),
+        );
+        assert_eq!(
+            serde_json::to_value(&tuned).unwrap(),
+            serde_json::json!({
+                "question": "Что такое Rust?",
+                "temperature": 0.5,
+                "max_tokens": 256,
+                "system_prompt": "Отвечай кратко"
+            })
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! DTO формата chat completions.
+//!
+//! Этот формат (`POST /chat/completions`, `GET /models`) понимают
+//! и GigaChat API, и OpenAI‑совместимые серверы (llama.cpp, vLLM), поэтому
+//! он общий для `GigaChatDirectGateway` и `OpenAiCompatibleGateway`.
+use serde::{Deserialize, Serialize};
+
+use super::api_client::http_error;
+use crate::application::ports::{ApiError, AskRequest, GatewayError};
+use crate::domain::{Message, ModelName};
+
+/// DTO запроса к `POST /chat/completions`.
+///
+/// Без модели поле `model` не отправляется: сервер с одной загруженной
+/// моделью (например, llama.cpp) возьмёт её сам.
+#[derive(Debug, Serialize)]
+pub(super) struct ChatCompletionRequestDto {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    messages: Vec<ChatMessageDto>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    stream: bool,
+}
+
+impl ChatCompletionRequestDto {
+    /// Системный промпт, контекст диалога и вопрос — одним списком сообщений.
+    ///
+    /// Модель из параметров генерации важнее `default_model`.
+    pub(super) fn new(request: &AskRequest, default_model: Option<&ModelName>) -> Self {
+        let generation = &request.generation;
+        let system = request.system_prompt.as_ref().map(|prompt| ChatMessageDto {
+            role: "system",
+            content: prompt.as_str().to_string(),
+        });
+        let question = ChatMessageDto {
+            role: "user",
+            content: request.question.as_str().to_string(),
+        };
+        Self {
+            model: generation
+                .model
+                .as_ref()
+                .or(default_model)
+                .map(|model| model.as_str().to_string()),
+            messages: system
+                .into_iter()
+                .chain(request.context.iter().map(ChatMessageDto::from))
+                .chain(Some(question))
+                .collect(),
+            temperature: generation.temperature.map(|value| value.value()),
+            top_p: generation.top_p.map(|value| value.value()),
+            max_tokens: generation.max_tokens.map(|value| value.value()),
+            stream: false,
+        }
+    }
+}
+
+/// DTO одного сообщения чата.
+#[derive(Debug, Serialize)]
+struct ChatMessageDto {
+    role: &'static str,
+    content: String,
+}
+
+impl From<&Message> for ChatMessageDto {
+    fn from(message: &Message) -> Self {
+        Self {
+            role: message.role.as_str(),
+            content: message.content.clone(),
+        }
+    }
+}
+
+/// DTO ответа от `POST /chat/completions`.
+#[derive(Debug, Deserialize)]
+pub(super) struct ChatCompletionResponseDto {
+    /// Модель, которая ответила.
+    pub(super) model: Option<String>,
+    choices: Vec<ChatChoiceDto>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoiceDto {
+    message: ChatReplyDto,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatReplyDto {
+    content: String,
+}
+
+impl ChatCompletionResponseDto {
+    /// Текст первого варианта, предложенного моделью.
+    pub(super) fn into_answer(self) -> Result<String, GatewayError> {
+        self.choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .ok_or(GatewayError::InvalidPayload)
+    }
+}
+
+/// DTO ответа от `GET /models`.
+#[derive(Debug, Deserialize)]
+pub(super) struct ModelsResponseDto {
+    data: Vec<ModelDto>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelDto {
+    id: String,
+}
+
+impl ModelsResponseDto {
+    /// Сервер предлагает модель `model`.
+    pub(super) fn contains(&self, model: &ModelName) -> bool {
+        self.data.iter().any(|entry| entry.id == model.as_str())
+    }
+
+    /// Первая из моделей сервера.
+    pub(super) fn first(&self) -> Option<&str> {
+        self.data.first().map(|entry| entry.id.as_str())
+    }
+}
+
+/// Ошибка по неуспешному ответу: статус разбирается как у `ApiClient`,
+/// а `message`, если сервер его прислал в своём формате, заменяет
+/// сообщение об ошибке.
+pub(super) fn http_error_with_message(
+    status: u16,
+    text: String,
+    message: Option<String>,
+) -> GatewayError {
+    let mut error = http_error(status, text);
+    if let (
+        Some(message),
+        GatewayError::Api(ApiError {
+            message: target, ..
+        })
+        | GatewayError::Unauthorized(ApiError {
+            message: target, ..
+        })
+        | GatewayError::Forbidden(ApiError {
+            message: target, ..
+        }),
+    ) = (message, &mut error)
+    {
+        *target = message;
+    }
+    error
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AnswerSource, MessageRole, Question, SystemPrompt, Timestamp};
+
+    #[test]
+    fn request_puts_system_prompt_and_context_before_question() {
+        let request = AskRequest::new(Question::try_new("А в Rust?").unwrap())
+            .with_context(vec![
+                Message {
+                    role: MessageRole::User,
+                    content: "Что такое замыкание?".to_string(),
+                    timestamp: Timestamp::from_millis(1),
+                    source: None,
+                },
+                Message {
+                    role: MessageRole::Assistant,
+                    content: "Функция с окружением.".to_string(),
+                    timestamp: Timestamp::from_millis(2),
+                    source: Some(AnswerSource::GigaChat),
+                },
+            ])
+            .with_system_prompt(Some(SystemPrompt::try_new("Отвечай кратко").unwrap()));
+        let model = ModelName::try_new("GigaChat").unwrap();
+
+        let payload =
+            serde_json::to_value(ChatCompletionRequestDto::new(&request, Some(&model))).unwrap();
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "model": "GigaChat",
+                "messages": [
+                    {"role": "system", "content": "Отвечай кратко"},
+                    {"role": "user", "content": "Что такое замыкание?"},
+                    {"role": "assistant", "content": "Функция с окружением."},
+                    {"role": "user", "content": "А в Rust?"},
+                ],
+                "stream": false,
+            })
+        );
+
+        let payload = serde_json::to_value(ChatCompletionRequestDto::new(&request, None)).unwrap();
+        assert!(payload.get("model").is_none());
+    }
+}
@@ -0,0 +1,17 @@
+//! Часы браузера.
+//!
+//! Реализация порта `Clock` через `Date.now()` из JavaScript.
+use js_sys::Date;
+
+use crate::application::ports::Clock;
+use crate::domain::Timestamp;
+
+/// Часы, берущие время из браузера.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BrowserClock;
+
+impl Clock for BrowserClock {
+    fn now(&self) -> Timestamp {
+        Timestamp::from_millis(Date::now() as u64)
+    }
+}
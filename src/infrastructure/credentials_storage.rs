@@ -0,0 +1,149 @@
+//! Учётные данные в `sessionStorage` браузера.
+//!
+//! Ключ API, токен или логин с паролем — секреты, поэтому они хранятся
+//! не в `localStorage` рядом с остальными настройками, а в `sessionStorage`:
+//! данные живут, пока открыта вкладка, и не попадают в конфигурацию
+//! приложения. Как и в других хранилищах, между `AuthSettings` и JSON
+//! стоит DTO.
+//!
+//! У каждого профиля backend свои учётные данные: ключ в `sessionStorage`
+//! дополняется названием профиля.
+use gloo_storage::errors::StorageError as GlooStorageError;
+use gloo_storage::{SessionStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::application::ports::StorageError;
+use crate::application::AuthSettings;
+
+const CREDENTIALS_KEY: &str = "rust_gigachat_webapp.credentials";
+
+/// Формат хранения учётных данных.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+enum StoredCredentials {
+    ApiKey {
+        key: String,
+    },
+    Bearer {
+        token: String,
+    },
+    Login {
+        endpoint: String,
+        username: String,
+        password: String,
+    },
+}
+
+impl StoredCredentials {
+    /// DTO для настроек; `AuthSettings::None` не хранится.
+    fn from_settings(settings: &AuthSettings) -> Option<Self> {
+        Some(match settings.clone() {
+            AuthSettings::None => return None,
+            AuthSettings::ApiKey(key) => StoredCredentials::ApiKey { key },
+            AuthSettings::Bearer(token) => StoredCredentials::Bearer { token },
+            AuthSettings::Login {
+                endpoint,
+                username,
+                password,
+            } => StoredCredentials::Login {
+                endpoint,
+                username,
+                password,
+            },
+        })
+    }
+
+    fn into_settings(self) -> AuthSettings {
+        match self {
+            StoredCredentials::ApiKey { key } => AuthSettings::ApiKey(key),
+            StoredCredentials::Bearer { token } => AuthSettings::Bearer(token),
+            StoredCredentials::Login {
+                endpoint,
+                username,
+                password,
+            } => AuthSettings::Login {
+                endpoint,
+                username,
+                password,
+            },
+        }
+    }
+}
+
+/// Учётные данные текущей вкладки.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionStorageCredentials {
+    key: String,
+}
+
+impl SessionStorageCredentials {
+    /// Учётные данные профиля backend с названием `profile`.
+    pub fn for_profile(profile: &str) -> Self {
+        Self {
+            key: format!("{CREDENTIALS_KEY}.{profile}"),
+        }
+    }
+
+    /// Сохранённые учётные данные; без них — `AuthSettings::None`.
+    pub fn load(&self) -> Result<AuthSettings, StorageError> {
+        match SessionStorage::get::<StoredCredentials>(&self.key) {
+            Ok(stored) => Ok(stored.into_settings()),
+            Err(GlooStorageError::KeyNotFound(_)) => Ok(AuthSettings::None),
+            Err(GlooStorageError::SerdeError(error)) => {
+                Err(StorageError::Corrupted(error.to_string()))
+            }
+            Err(error) => Err(StorageError::Unavailable(error.to_string())),
+        }
+    }
+
+    /// Сохраняет учётные данные; `AuthSettings::None` их удаляет.
+    pub fn save(&self, settings: &AuthSettings) -> Result<(), StorageError> {
+        match StoredCredentials::from_settings(settings) {
+            Some(stored) => SessionStorage::set(&self.key, stored)
+                .map_err(|error| StorageError::Unavailable(error.to_string())),
+            None => {
+                self.clear();
+                Ok(())
+            }
+        }
+    }
+
+    pub fn clear(&self) {
+        SessionStorage::delete(&self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip_through_stored_format() {
+        let login = AuthSettings::Login {
+            endpoint: "https://example.com/auth/login".to_string(),
+            username: "student".to_string(),
+            password: "secret".to_string(),
+        };
+        let stored = StoredCredentials::from_settings(&login).unwrap();
+        let json = serde_json::to_value(&stored).unwrap();
+        assert_eq!(json["method"], "login");
+        let restored: StoredCredentials = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.into_settings(), login);
+
+        let key = AuthSettings::ApiKey("k-123".to_string());
+        let stored = StoredCredentials::from_settings(&key).unwrap();
+        assert_eq!(
+            serde_json::to_value(&stored).unwrap(),
+            serde_json::json!({ "method": "api_key", "key": "k-123" })
+        );
+        assert_eq!(StoredCredentials::from_settings(&AuthSettings::None), None);
+    }
+
+    #[test]
+    fn profiles_keep_credentials_under_separate_keys() {
+        let course = SessionStorageCredentials::for_profile("Backend курса");
+        let local = SessionStorageCredentials::for_profile("llama.cpp");
+        assert_eq!(course.key, "rust_gigachat_webapp.credentials.Backend курса");
+        assert_ne!(course, local);
+    }
+}
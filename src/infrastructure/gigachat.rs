@@ -0,0 +1,393 @@
+//! Прямое подключение к GigaChat API, минуя `edu-rust-gigachat-app-backend`.
+//!
+//! Токен доступа выдаёт OAuth‑эндпоинт по схеме client credentials:
+//! `POST` с заголовками `Authorization: Basic <ключ авторизации>` и `RqUID`
+//! и телом `scope=...` в ответ даёт `{"access_token": "...",
+//! "expires_at": <мс>}`. Токен живёт около получаса; запоминает и обновляет
+//! его `RefreshingTokenAuth`, которому `GigaChatTokenSource` служит
+//! источником.
+//!
+//! Вопросы уходят в `POST /chat/completions`, проверкой связи служит
+//! `GET /models`. Адреса обоих эндпоинтов задаются явно, поэтому вместо
+//! GigaChat можно поднять локальную заглушку с теми же ответами; адрес
+//! OAuth‑эндпоинта по адресу API подсказывает
+//! `GigaChatTokenSource::oauth_url_for`.
+//!
+//! Транспорт (таймаут, отмена, повтор после 401) общий с `ApiClient`.
+use std::fmt;
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gloo_net::http::Request;
+use serde::Deserialize;
+use web_sys::AbortSignal;
+
+use super::api_client::{AbortGuard, ApiClient};
+use super::chat_completions::{
+    http_error_with_message, ChatCompletionRequestDto, ChatCompletionResponseDto, ModelsResponseDto,
+};
+use crate::application::ports::{
+    AccessToken, AnswerStream, AskRequest, AuthProvider, CancellationToken, ChatGateway,
+    GatewayError, HealthGateway, StreamingChatGateway, TokenSource,
+};
+use crate::domain::{
+    AnswerSource, ApiBaseUrl, AskResult, HealthStatus, ModelName, ServiceState, Timestamp,
+};
+
+/// Получение токена GigaChat по ключу авторизации.
+#[derive(Clone)]
+pub struct GigaChatTokenSource {
+    oauth_url: String,
+    authorization_key: String,
+    scope: String,
+    timeout_ms: u32,
+}
+
+impl fmt::Debug for GigaChatTokenSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GigaChatTokenSource")
+            .field("oauth_url", &self.oauth_url)
+            .field("scope", &self.scope)
+            .field("timeout_ms", &self.timeout_ms)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GigaChatTokenSource {
+    /// OAuth‑эндпоинт GigaChat.
+    pub const DEFAULT_OAUTH_URL: &'static str = "https://ngw.devices.sberbank.ru:9443/api/v2/oauth";
+    /// Область доступа для физических лиц.
+    pub const DEFAULT_SCOPE: &'static str = "GIGACHAT_API_PERS";
+    /// Таймаут получения токена по умолчанию.
+    pub const DEFAULT_TIMEOUT_MS: u32 = 15_000;
+
+    /// Источник токенов по ключу авторизации (Base64 от
+    /// `client_id:client_secret`, как его выдаёт личный кабинет).
+    pub fn new(authorization_key: impl Into<String>) -> Self {
+        Self {
+            oauth_url: Self::DEFAULT_OAUTH_URL.to_string(),
+            authorization_key: authorization_key.into(),
+            scope: Self::DEFAULT_SCOPE.to_string(),
+            timeout_ms: Self::DEFAULT_TIMEOUT_MS,
+        }
+    }
+
+    /// Адрес OAuth‑эндпоинта для API по адресу `api_url`: у GigaChat API
+    /// он на отдельном хосте, а заглушка выдаёт токены по `/oauth`
+    /// рядом с API.
+    pub fn oauth_url_for(api_url: &ApiBaseUrl) -> String {
+        if api_url.as_str() == GigaChatDirectGateway::DEFAULT_API_URL {
+            Self::DEFAULT_OAUTH_URL.to_string()
+        } else {
+            api_url.join("/oauth")
+        }
+    }
+
+    /// Задаёт адрес OAuth‑эндпоинта (например, локальной заглушки).
+    pub fn with_oauth_url(mut self, oauth_url: impl Into<String>) -> Self {
+        self.oauth_url = oauth_url.into();
+        self
+    }
+
+    /// Задаёт область доступа (`GIGACHAT_API_B2B`, `GIGACHAT_API_CORP`).
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn with_scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = scope.into();
+        self
+    }
+}
+
+/// DTO ответа OAuth‑эндпоинта; `expires_at` — время истечения в мс.
+#[derive(Debug, Deserialize)]
+struct OAuthResponseDto {
+    access_token: String,
+    expires_at: Option<u64>,
+}
+
+impl From<OAuthResponseDto> for AccessToken {
+    fn from(payload: OAuthResponseDto) -> Self {
+        AccessToken {
+            token: payload.access_token,
+            expires_at: payload.expires_at.map(Timestamp::from_millis),
+        }
+    }
+}
+
+/// Идентификатор запроса `RqUID` в формате UUID v4.
+fn request_uid(mut random: impl FnMut() -> f64) -> String {
+    let mut bytes = [0u8; 16];
+    for byte in &mut bytes {
+        *byte = (random() * 256.0) as u8;
+    }
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+#[async_trait(?Send)]
+impl TokenSource for GigaChatTokenSource {
+    async fn fetch_token(
+        &self,
+        cancellation: &CancellationToken,
+    ) -> Result<AccessToken, GatewayError> {
+        let guard = AbortGuard::new(self.timeout_ms, cancellation)?;
+        let request = Request::post(&self.oauth_url)
+            .abort_signal(Some(&guard.signal()))
+            .header("Accept", "application/json")
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .header(
+                "Authorization",
+                &format!("Basic {}", self.authorization_key),
+            )
+            .header("RqUID", &request_uid(js_sys::Math::random))
+            .body(format!("scope={}", self.scope))
+            .map_err(|e| GatewayError::Network(e.to_string()))?;
+        let response = request
+            .send()
+            .await
+            .map_err(|e| guard.error(e.to_string()))?;
+        let text = guard.text(&response).await?;
+        if !response.ok() {
+            return Err(gigachat_error(response.status(), text));
+        }
+
+        serde_json::from_str::<OAuthResponseDto>(&text)
+            .map(AccessToken::from)
+            .map_err(|_| GatewayError::InvalidPayload)
+    }
+}
+
+/// Шлюз к GigaChat API.
+///
+/// Учётные данные прикладывает `AuthProvider` — обычно
+/// `RefreshingTokenAuth` над `GigaChatTokenSource`.
+#[derive(Clone)]
+pub struct GigaChatDirectGateway {
+    client: ApiClient,
+    model: ModelName,
+}
+
+impl fmt::Debug for GigaChatDirectGateway {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GigaChatDirectGateway")
+            .field("client", &self.client)
+            .field("model", &self.model)
+            .finish()
+    }
+}
+
+impl GigaChatDirectGateway {
+    /// Адрес GigaChat API.
+    pub const DEFAULT_API_URL: &'static str = "https://gigachat.devices.sberbank.ru/api/v1";
+    /// Модель, если в запросе она не указана.
+    pub const DEFAULT_MODEL: &'static str = "GigaChat";
+
+    /// Создаёт шлюз к API по адресу `api_url` (с префиксом `/api/v1`).
+    pub fn new(api_url: ApiBaseUrl, auth: Rc<dyn AuthProvider>) -> Self {
+        Self {
+            client: ApiClient::new(api_url).with_auth(auth),
+            model: ModelName::try_new(Self::DEFAULT_MODEL).expect("имя модели по умолчанию"),
+        }
+    }
+
+    /// Задаёт таймаут запроса в миллисекундах (`0` — без таймаута).
+    pub fn with_timeout(mut self, timeout_ms: u32) -> Self {
+        self.client = self.client.with_timeout(timeout_ms);
+        self
+    }
+
+    /// Задаёт модель по умолчанию.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn with_model(mut self, model: ModelName) -> Self {
+        self.model = model;
+        self
+    }
+}
+
+/// DTO ошибки GigaChat: `{"status": 401, "message": "..."}`.
+#[derive(Debug, Deserialize)]
+struct GigaChatErrorDto {
+    message: String,
+}
+
+/// Ошибка по неуспешному ответу GigaChat.
+fn gigachat_error(status: u16, text: String) -> GatewayError {
+    let message = serde_json::from_str::<GigaChatErrorDto>(&text)
+        .ok()
+        .map(|payload| payload.message);
+    http_error_with_message(status, text, message)
+}
+
+/// Ответ из первого варианта, предложенного моделью.
+fn ask_result_from_dto(
+    payload: ChatCompletionResponseDto,
+    request: &AskRequest,
+) -> Result<AskResult, GatewayError> {
+    Ok(AskResult {
+        answer: payload.into_answer()?,
+        source: AnswerSource::GigaChat,
+        system_prompt_applied: request.system_prompt.is_some(),
+        cached: false,
+    })
+}
+
+/// Состояние API по списку моделей: без нужной модели вопросы
+/// работать не будут, поэтому такое API считается деградировавшим.
+fn health_from_dto(payload: ModelsResponseDto, model: &ModelName) -> HealthStatus {
+    HealthStatus {
+        status: if payload.contains(model) {
+            ServiceState::Ok
+        } else {
+            ServiceState::Degraded
+        },
+        version: model.as_str().to_string(),
+        gigachat_enabled: true,
+    }
+}
+
+#[async_trait(?Send)]
+impl ChatGateway for GigaChatDirectGateway {
+    async fn ask(&self, request: AskRequest) -> Result<AskResult, GatewayError> {
+        let url = self.client.endpoint("/chat/completions");
+        let payload = ChatCompletionRequestDto::new(&request, Some(&self.model));
+
+        let build = |signal: &AbortSignal| {
+            Request::post(&url)
+                .header("Accept", "application/json")
+                .abort_signal(Some(signal))
+                .json(&payload)
+        };
+        let (response, text) = self.client.fetch(build, &request.cancellation).await?;
+        if !response.ok() {
+            return Err(gigachat_error(response.status(), text));
+        }
+
+        let payload = serde_json::from_str::<ChatCompletionResponseDto>(&text)
+            .map_err(|_| GatewayError::InvalidPayload)?;
+        ask_result_from_dto(payload, &request)
+    }
+}
+
+/// Потоком шлюз не отвечает: вопрос уходит обычным запросом.
+#[async_trait(?Send)]
+impl StreamingChatGateway for GigaChatDirectGateway {
+    async fn ask_stream(&self, _request: AskRequest) -> Result<AnswerStream, GatewayError> {
+        Err(GatewayError::StreamingUnsupported)
+    }
+}
+
+#[async_trait(?Send)]
+impl HealthGateway for GigaChatDirectGateway {
+    async fn health(&self) -> Result<HealthStatus, GatewayError> {
+        let url = self.client.endpoint("/models");
+        let build = |signal: &AbortSignal| {
+            Request::get(&url)
+                .header("Accept", "application/json")
+                .abort_signal(Some(signal))
+                .build()
+        };
+        let (response, text) = self.client.fetch(build, &CancellationToken::new()).await?;
+        if !response.ok() {
+            return Err(gigachat_error(response.status(), text));
+        }
+
+        let payload: ModelsResponseDto =
+            serde_json::from_str(&text).map_err(|_| GatewayError::InvalidPayload)?;
+        Ok(health_from_dto(payload, &self.model))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::NoAuth;
+    use crate::domain::Question;
+
+    #[test]
+    fn responses_of_stand_in_server_map_to_domain() {
+        let request = AskRequest::new(Question::try_new("Привет").unwrap());
+        let payload: ChatCompletionResponseDto = serde_json::from_str(
+            r#"{"choices":[{"message":{"role":"assistant","content":"Здравствуйте!"},"index":0,"finish_reason":"stop"}],"created":1,"model":"GigaChat","object":"chat.completion"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            ask_result_from_dto(payload, &request).unwrap(),
+            AskResult {
+                answer: "Здравствуйте!".to_string(),
+                source: AnswerSource::GigaChat,
+                system_prompt_applied: false,
+                cached: false,
+            }
+        );
+        let empty: ChatCompletionResponseDto = serde_json::from_str(r#"{"choices":[]}"#).unwrap();
+        assert!(matches!(
+            ask_result_from_dto(empty, &request),
+            Err(GatewayError::InvalidPayload)
+        ));
+
+        let token: OAuthResponseDto =
+            serde_json::from_str(r#"{"access_token":"abc","expires_at":1706026848841}"#).unwrap();
+        assert_eq!(
+            AccessToken::from(token).expires_at,
+            Some(Timestamp::from_millis(1_706_026_848_841))
+        );
+
+        let models: ModelsResponseDto =
+            serde_json::from_str(r#"{"object":"list","data":[{"id":"GigaChat-Pro"}]}"#).unwrap();
+        let health = health_from_dto(models, &ModelName::try_new("GigaChat").unwrap());
+        assert_eq!(health.status, ServiceState::Degraded);
+
+        match gigachat_error(
+            401,
+            r#"{"status":401,"message":"Token has expired"}"#.to_string(),
+        ) {
+            GatewayError::Unauthorized(error) => assert_eq!(error.message, "Token has expired"),
+            other => panic!("ожидалась Unauthorized, получено {other:?}"),
+        }
+    }
+
+    #[test]
+    fn oauth_url_follows_api_url() {
+        let official = ApiBaseUrl::try_new(GigaChatDirectGateway::DEFAULT_API_URL).unwrap();
+        assert_eq!(
+            GigaChatTokenSource::oauth_url_for(&official),
+            GigaChatTokenSource::DEFAULT_OAUTH_URL
+        );
+        let stub = ApiBaseUrl::try_new("http://localhost:9000/api/v1").unwrap();
+        assert_eq!(
+            GigaChatTokenSource::oauth_url_for(&stub),
+            "http://localhost:9000/api/v1/oauth"
+        );
+    }
+
+    #[test]
+    fn scope_and_model_can_be_overridden() {
+        let source = GigaChatTokenSource::new("ключ").with_scope("GIGACHAT_API_CORP");
+        assert_eq!(source.scope, "GIGACHAT_API_CORP");
+
+        let api_url = ApiBaseUrl::try_new(GigaChatDirectGateway::DEFAULT_API_URL).unwrap();
+        let gateway = GigaChatDirectGateway::new(api_url, Rc::new(NoAuth))
+            .with_model(ModelName::try_new("GigaChat-Pro").unwrap());
+        let models: ModelsResponseDto =
+            serde_json::from_str(r#"{"data":[{"id":"GigaChat-Pro"}]}"#).unwrap();
+        assert_eq!(
+            health_from_dto(models, &gateway.model).status,
+            ServiceState::Ok
+        );
+    }
+
+    #[test]
+    fn request_uid_is_uuid_v4() {
+        let uid = request_uid(|| 0.999);
+        assert_eq!(uid, "ffffffff-ffff-4fff-bfff-ffffffffffff");
+        assert_eq!(request_uid(|| 0.0), "00000000-0000-4000-8000-000000000000");
+    }
+}
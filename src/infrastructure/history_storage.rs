@@ -0,0 +1,155 @@
+//! Хранилище истории в `localStorage` браузера.
+//!
+//! Реализует порт `HistoryRepository`. Записи хранятся одним JSON‑массивом;
+//! доменные типы в хранилище не попадают — между ними и JSON стоит DTO.
+use gloo_storage::errors::StorageError as GlooStorageError;
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::application::ports::{HistoryRepository, StorageError};
+use crate::domain::{AnswerSource, AskResult, HistoryEntry, HistoryId, Question, Timestamp};
+
+const HISTORY_KEY: &str = "rust_gigachat_webapp.history";
+
+/// Формат хранения записи истории.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct StoredHistoryEntry {
+    id: u64,
+    question: String,
+    answer: String,
+    source: String,
+    system_prompt_applied: bool,
+    recorded_at: u64,
+}
+
+impl From<&HistoryEntry> for StoredHistoryEntry {
+    fn from(entry: &HistoryEntry) -> Self {
+        Self {
+            id: entry.id.as_u64(),
+            question: entry.question.as_str().to_string(),
+            answer: entry.result.answer.clone(),
+            source: source_to_stored(&entry.result.source),
+            system_prompt_applied: entry.result.system_prompt_applied,
+            recorded_at: entry.recorded_at.as_millis(),
+        }
+    }
+}
+
+impl StoredHistoryEntry {
+    /// Преобразует DTO в доменную запись; некорректный вопрос даёт `None`.
+    fn into_entry(self) -> Option<HistoryEntry> {
+        Some(HistoryEntry {
+            id: HistoryId::new(self.id),
+            question: Question::try_new(self.question).ok()?,
+            result: AskResult {
+                answer: self.answer,
+                source: source_from_stored(&self.source),
+                system_prompt_applied: self.system_prompt_applied,
+                cached: false,
+            },
+            recorded_at: Timestamp::from_millis(self.recorded_at),
+        })
+    }
+}
+
+pub(super) fn source_to_stored(source: &AnswerSource) -> String {
+    match source {
+        AnswerSource::GigaChat => "gigachat".to_string(),
+        AnswerSource::Mock => "mock".to_string(),
+        AnswerSource::Unknown(raw) => raw.clone(),
+    }
+}
+
+pub(super) fn source_from_stored(raw: &str) -> AnswerSource {
+    match raw {
+        "gigachat" => AnswerSource::GigaChat,
+        "mock" => AnswerSource::Mock,
+        _ => AnswerSource::Unknown(raw.to_string()),
+    }
+}
+
+/// История вопросов в `localStorage`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalStorageHistoryRepository;
+
+impl LocalStorageHistoryRepository {
+    fn load(&self) -> Result<Vec<StoredHistoryEntry>, StorageError> {
+        match LocalStorage::get::<Vec<StoredHistoryEntry>>(HISTORY_KEY) {
+            Ok(stored) => Ok(stored),
+            Err(GlooStorageError::KeyNotFound(_)) => Ok(Vec::new()),
+            Err(GlooStorageError::SerdeError(error)) => {
+                Err(StorageError::Corrupted(error.to_string()))
+            }
+            Err(error) => Err(StorageError::Unavailable(error.to_string())),
+        }
+    }
+
+    fn store(&self, entries: &[StoredHistoryEntry]) -> Result<(), StorageError> {
+        LocalStorage::set(HISTORY_KEY, entries)
+            .map_err(|error| StorageError::Unavailable(error.to_string()))
+    }
+}
+
+impl HistoryRepository for LocalStorageHistoryRepository {
+    fn list(&self) -> Result<Vec<HistoryEntry>, StorageError> {
+        Ok(self
+            .load()?
+            .into_iter()
+            .filter_map(StoredHistoryEntry::into_entry)
+            .collect())
+    }
+
+    fn add(&self, entry: HistoryEntry) -> Result<(), StorageError> {
+        let mut entries = self.load()?;
+        entries.push(StoredHistoryEntry::from(&entry));
+        self.store(&entries)
+    }
+
+    fn remove(&self, id: HistoryId) -> Result<bool, StorageError> {
+        let mut entries = self.load()?;
+        let before = entries.len();
+        entries.retain(|entry| entry.id != id.as_u64());
+        if entries.len() == before {
+            return Ok(false);
+        }
+        self.store(&entries)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_entry_round_trips() {
+        let entry = HistoryEntry {
+            id: HistoryId::new(42),
+            question: Question::try_new("Что такое Rust?").unwrap(),
+            result: AskResult {
+                answer: "Язык".to_string(),
+                source: AnswerSource::Unknown("local-llm".to_string()),
+                system_prompt_applied: true,
+                cached: false,
+            },
+            recorded_at: Timestamp::from_millis(1_700_000_000_000),
+        };
+        let stored = StoredHistoryEntry::from(&entry);
+        let json = serde_json::to_string(&stored).unwrap();
+        let restored: StoredHistoryEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.into_entry(), Some(entry));
+    }
+
+    #[test]
+    fn stored_entry_with_empty_question_is_skipped() {
+        let stored = StoredHistoryEntry {
+            id: 1,
+            question: "  ".to_string(),
+            answer: "ok".to_string(),
+            source: "mock".to_string(),
+            system_prompt_applied: false,
+            recorded_at: 1,
+        };
+        assert_eq!(stored.into_entry(), None);
+    }
+}
@@ -0,0 +1,130 @@
+//! Получение токена доступа у эндпоинта входа.
+//!
+//! Реализация порта `TokenSource`: `POST` на эндпоинт с JSON
+//! `{"username": "...", "password": "..."}`; в ответ ожидается
+//! `{"access_token": "...", "expires_in": 3600}`, где `expires_in` —
+//! срок действия в секундах (может отсутствовать). Ошибки разбираются
+//! так же, как у `ApiClient`: неверный пароль даёт `Unauthorized`.
+use std::fmt;
+
+use async_trait::async_trait;
+use gloo_net::http::Request;
+use serde::{Deserialize, Serialize};
+
+use super::api_client::{http_error, AbortGuard};
+use super::clock::BrowserClock;
+use crate::application::ports::{AccessToken, CancellationToken, Clock, GatewayError, TokenSource};
+use crate::domain::Timestamp;
+
+/// Вход по логину и паролю.
+#[derive(Clone)]
+pub struct LoginTokenSource {
+    endpoint: String,
+    username: String,
+    password: String,
+    timeout_ms: u32,
+}
+
+impl fmt::Debug for LoginTokenSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoginTokenSource")
+            .field("endpoint", &self.endpoint)
+            .field("username", &self.username)
+            .field("timeout_ms", &self.timeout_ms)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LoginTokenSource {
+    /// Таймаут входа по умолчанию.
+    pub const DEFAULT_TIMEOUT_MS: u32 = 15_000;
+
+    pub fn new(
+        endpoint: impl Into<String>,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            username: username.into(),
+            password: password.into(),
+            timeout_ms: Self::DEFAULT_TIMEOUT_MS,
+        }
+    }
+}
+
+/// DTO запроса ко входу.
+#[derive(Debug, Serialize)]
+struct LoginRequestDto<'a> {
+    username: &'a str,
+    password: &'a str,
+}
+
+/// DTO ответа со входа.
+#[derive(Debug, Deserialize)]
+struct LoginResponseDto {
+    #[serde(alias = "token")]
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+/// Токен из ответа; срок действия отсчитывается от `now`.
+fn access_token_from_dto(payload: LoginResponseDto, now: Timestamp) -> AccessToken {
+    AccessToken {
+        token: payload.access_token,
+        expires_at: payload
+            .expires_in
+            .map(|seconds| Timestamp::from_millis(now.as_millis() + seconds * 1_000)),
+    }
+}
+
+#[async_trait(?Send)]
+impl TokenSource for LoginTokenSource {
+    async fn fetch_token(
+        &self,
+        cancellation: &CancellationToken,
+    ) -> Result<AccessToken, GatewayError> {
+        let guard = AbortGuard::new(self.timeout_ms, cancellation)?;
+        let request = Request::post(&self.endpoint)
+            .abort_signal(Some(&guard.signal()))
+            .json(&LoginRequestDto {
+                username: &self.username,
+                password: &self.password,
+            })
+            .map_err(|e| GatewayError::Network(e.to_string()))?;
+        let response = request
+            .send()
+            .await
+            .map_err(|e| guard.error(e.to_string()))?;
+        let text = guard.text(&response).await?;
+        if !response.ok() {
+            return Err(http_error(response.status(), text));
+        }
+
+        let payload = serde_json::from_str::<LoginResponseDto>(&text)
+            .map_err(|_| GatewayError::InvalidPayload)?;
+        Ok(access_token_from_dto(payload, BrowserClock.now()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn login_response_maps_to_access_token() {
+        let now = Timestamp::from_millis(10_000);
+        let payload: LoginResponseDto =
+            serde_json::from_str(r#"{"access_token":"abc","expires_in":60}"#).unwrap();
+        assert_eq!(
+            access_token_from_dto(payload, now),
+            AccessToken {
+                token: "abc".to_string(),
+                expires_at: Some(Timestamp::from_millis(70_000)),
+            }
+        );
+
+        let payload: LoginResponseDto = serde_json::from_str(r#"{"token":"xyz"}"#).unwrap();
+        assert_eq!(access_token_from_dto(payload, now).expires_at, None);
+    }
+}
@@ -0,0 +1,67 @@
+//! Backend‑заглушка без сети.
+//!
+//! Отвечает шаблонной фразой с текстом вопроса и всегда «здоров».
+//! Пригодится, чтобы показать интерфейс или проверить декораторы,
+//! когда ни один настоящий сервер не запущен.
+use async_trait::async_trait;
+
+use crate::application::ports::{
+    AnswerStream, AskRequest, ChatGateway, GatewayError, HealthGateway, StreamingChatGateway,
+};
+use crate::domain::{AnswerSource, AskResult, HealthStatus, ServiceState};
+
+/// Шлюз с ответами‑заглушками.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MockGateway;
+
+#[async_trait(?Send)]
+impl ChatGateway for MockGateway {
+    async fn ask(&self, request: AskRequest) -> Result<AskResult, GatewayError> {
+        if request.cancellation.is_cancelled() {
+            return Err(GatewayError::Cancelled);
+        }
+        Ok(AskResult {
+            answer: format!(
+                "Это тестовый ответ без обращения к модели. Ваш вопрос: «{}».",
+                request.question.as_str()
+            ),
+            source: AnswerSource::Mock,
+            system_prompt_applied: request.system_prompt.is_some(),
+            cached: false,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl StreamingChatGateway for MockGateway {
+    async fn ask_stream(&self, _request: AskRequest) -> Result<AnswerStream, GatewayError> {
+        Err(GatewayError::StreamingUnsupported)
+    }
+}
+
+#[async_trait(?Send)]
+impl HealthGateway for MockGateway {
+    async fn health(&self) -> Result<HealthStatus, GatewayError> {
+        Ok(HealthStatus {
+            status: ServiceState::Ok,
+            version: "mock".to_string(),
+            gigachat_enabled: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::domain::Question;
+
+    #[test]
+    fn mock_answers_with_question_text() {
+        let request = AskRequest::new(Question::try_new("Что такое Rust?").unwrap());
+        let result = block_on(MockGateway.ask(request)).unwrap();
+        assert!(result.answer.contains("Что такое Rust?"));
+        assert_eq!(result.source, AnswerSource::Mock);
+    }
+}
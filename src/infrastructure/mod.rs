@@ -2,7 +2,42 @@
 //!
 //! Здесь находятся адаптеры и технические детали, которые не должны
 //! проникать в домен или use‑cases. В текущем проекте это HTTP‑клиент,
-//! реализующий порты `ChatGateway` и `HealthGateway`.
+//! реализующий порты `ChatGateway` и `HealthGateway`, часы и таймер
+//! браузера для портов `Clock` и `Sleeper`, хранилища истории, очереди
+//! неотправленных вопросов и кэша ответов в `localStorage`. HTTP‑клиент
+//! умеет читать ответ потоком (SSE) и прикладывать учётные данные; токен
+//! для них выдаёт эндпоинт входа, а сами данные хранятся в `sessionStorage`.
+//! Там же хранится сессия пользователя, которой клиент управляет через
+//! порт `SessionGateway`.
+//!
+//! Помимо backend курса, вопросы можно задавать GigaChat API напрямую —
+//! через `GigaChatDirectGateway` с токеном по OAuth client credentials —
+//! или OpenAI‑совместимому серверу через `OpenAiCompatibleGateway`.
+//! Для показа интерфейса без сервера есть заглушка `MockGateway`.
+pub mod answer_cache_storage;
 pub mod api_client;
+mod chat_completions;
+pub mod clock;
+pub mod credentials_storage;
+pub mod gigachat;
+pub mod history_storage;
+pub mod login;
+pub mod mock;
+pub mod openai;
+pub mod outbox_storage;
+pub mod session_storage;
+pub mod sse;
+pub mod timer;
 
+pub use answer_cache_storage::LocalStorageAnswerCache;
 pub use api_client::ApiClient;
+pub use clock::BrowserClock;
+pub use credentials_storage::SessionStorageCredentials;
+pub use gigachat::{GigaChatDirectGateway, GigaChatTokenSource};
+pub use history_storage::LocalStorageHistoryRepository;
+pub use login::LoginTokenSource;
+pub use mock::MockGateway;
+pub use openai::OpenAiCompatibleGateway;
+pub use outbox_storage::LocalStorageOutboxRepository;
+pub use session_storage::SessionStorageSessionRepository;
+pub use timer::BrowserSleeper;
@@ -0,0 +1,250 @@
+//! Подключение к OpenAI‑совместимому серверу (llama.cpp, vLLM и т.п.).
+//!
+//! Вопросы уходят в `POST /v1/chat/completions`, ответом служит
+//! `choices[0].message.content`; проверкой связи служит `GET /v1/models`.
+//! Ключ API, если сервер его требует, прикладывает `AuthProvider`
+//! (обычно это токен в заголовке `Authorization: Bearer …`).
+//!
+//! Потоковых ответов шлюз не даёт: `ask_stream` возвращает
+//! `StreamingUnsupported`, и вопрос отправляется обычным запросом.
+use std::fmt;
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gloo_net::http::Request;
+use serde::Deserialize;
+use web_sys::AbortSignal;
+
+use super::api_client::ApiClient;
+use super::chat_completions::{
+    http_error_with_message, ChatCompletionRequestDto, ChatCompletionResponseDto, ModelsResponseDto,
+};
+use crate::application::ports::{
+    AnswerStream, AskRequest, AuthProvider, CancellationToken, ChatGateway, GatewayError,
+    HealthGateway, StreamingChatGateway,
+};
+use crate::domain::{AnswerSource, ApiBaseUrl, AskResult, HealthStatus, ModelName, ServiceState};
+
+/// Шлюз к OpenAI‑совместимому серверу.
+#[derive(Clone)]
+pub struct OpenAiCompatibleGateway {
+    client: ApiClient,
+    model: Option<ModelName>,
+}
+
+impl fmt::Debug for OpenAiCompatibleGateway {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OpenAiCompatibleGateway")
+            .field("client", &self.client)
+            .field("model", &self.model)
+            .finish()
+    }
+}
+
+impl OpenAiCompatibleGateway {
+    /// Создаёт шлюз к серверу по адресу `base_url` (без префикса `/v1`).
+    pub fn new(base_url: ApiBaseUrl) -> Self {
+        Self {
+            client: ApiClient::new(base_url),
+            model: None,
+        }
+    }
+
+    /// Задаёт таймаут запроса в миллисекундах (`0` — без таймаута).
+    pub fn with_timeout(mut self, timeout_ms: u32) -> Self {
+        self.client = self.client.with_timeout(timeout_ms);
+        self
+    }
+
+    /// Задаёт источник учётных данных для запросов.
+    pub fn with_auth(mut self, auth: Rc<dyn AuthProvider>) -> Self {
+        self.client = self.client.with_auth(auth);
+        self
+    }
+
+    /// Задаёт модель по умолчанию. Без неё модель выбирает сервер.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn with_model(mut self, model: ModelName) -> Self {
+        self.model = Some(model);
+        self
+    }
+}
+
+/// DTO ошибки OpenAI: `{"error": {"message": "...", "type": "..."}}`.
+#[derive(Debug, Deserialize)]
+struct OpenAiErrorDto {
+    error: OpenAiErrorBodyDto,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiErrorBodyDto {
+    message: String,
+}
+
+/// Ошибка по неуспешному ответу сервера.
+fn openai_error(status: u16, text: String) -> GatewayError {
+    let message = serde_json::from_str::<OpenAiErrorDto>(&text)
+        .ok()
+        .map(|payload| payload.error.message);
+    http_error_with_message(status, text, message)
+}
+
+/// Источником ответа служит модель, которая его дала.
+fn ask_result_from_dto(
+    payload: ChatCompletionResponseDto,
+    request: &AskRequest,
+    default_model: Option<&ModelName>,
+) -> Result<AskResult, GatewayError> {
+    let model = payload
+        .model
+        .clone()
+        .or_else(|| {
+            request
+                .generation
+                .model
+                .as_ref()
+                .or(default_model)
+                .map(|model| model.as_str().to_string())
+        })
+        .unwrap_or_else(|| "openai".to_string());
+    Ok(AskResult {
+        answer: payload.into_answer()?,
+        source: AnswerSource::Unknown(model),
+        system_prompt_applied: request.system_prompt.is_some(),
+        cached: false,
+    })
+}
+
+/// Состояние сервера по списку моделей.
+///
+/// Сервер без моделей или без заданной модели ответить не сможет,
+/// поэтому считается деградировавшим. Версией служит имя модели.
+fn health_from_dto(payload: ModelsResponseDto, model: Option<&ModelName>) -> HealthStatus {
+    let (available, version) = match model {
+        Some(model) => (payload.contains(model), model.as_str().to_string()),
+        None => (
+            payload.first().is_some(),
+            payload.first().unwrap_or_default().to_string(),
+        ),
+    };
+    HealthStatus {
+        status: if available {
+            ServiceState::Ok
+        } else {
+            ServiceState::Degraded
+        },
+        version,
+        gigachat_enabled: false,
+    }
+}
+
+#[async_trait(?Send)]
+impl ChatGateway for OpenAiCompatibleGateway {
+    async fn ask(&self, request: AskRequest) -> Result<AskResult, GatewayError> {
+        let url = self.client.endpoint("/v1/chat/completions");
+        let payload = ChatCompletionRequestDto::new(&request, self.model.as_ref());
+
+        let build = |signal: &AbortSignal| {
+            Request::post(&url)
+                .header("Accept", "application/json")
+                .abort_signal(Some(signal))
+                .json(&payload)
+        };
+        let (response, text) = self.client.fetch(build, &request.cancellation).await?;
+        if !response.ok() {
+            return Err(openai_error(response.status(), text));
+        }
+
+        let payload = serde_json::from_str::<ChatCompletionResponseDto>(&text)
+            .map_err(|_| GatewayError::InvalidPayload)?;
+        ask_result_from_dto(payload, &request, self.model.as_ref())
+    }
+}
+
+#[async_trait(?Send)]
+impl StreamingChatGateway for OpenAiCompatibleGateway {
+    async fn ask_stream(&self, _request: AskRequest) -> Result<AnswerStream, GatewayError> {
+        Err(GatewayError::StreamingUnsupported)
+    }
+}
+
+#[async_trait(?Send)]
+impl HealthGateway for OpenAiCompatibleGateway {
+    async fn health(&self) -> Result<HealthStatus, GatewayError> {
+        let url = self.client.endpoint("/v1/models");
+        let build = |signal: &AbortSignal| {
+            Request::get(&url)
+                .header("Accept", "application/json")
+                .abort_signal(Some(signal))
+                .build()
+        };
+        let (response, text) = self.client.fetch(build, &CancellationToken::new()).await?;
+        if !response.ok() {
+            return Err(openai_error(response.status(), text));
+        }
+
+        let payload: ModelsResponseDto =
+            serde_json::from_str(&text).map_err(|_| GatewayError::InvalidPayload)?;
+        Ok(health_from_dto(payload, self.model.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Question;
+
+    #[test]
+    fn completion_maps_to_answer_from_model() {
+        let request = AskRequest::new(Question::try_new("Привет").unwrap());
+        let payload: ChatCompletionResponseDto = serde_json::from_str(
+            r#"{"id":"chatcmpl-1","object":"chat.completion","model":"llama-3-8b","choices":[{"index":0,"message":{"role":"assistant","content":"Привет!"},"finish_reason":"stop"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            ask_result_from_dto(payload, &request, None).unwrap(),
+            AskResult {
+                answer: "Привет!".to_string(),
+                source: AnswerSource::Unknown("llama-3-8b".to_string()),
+                system_prompt_applied: false,
+                cached: false,
+            }
+        );
+
+        match openai_error(
+            404,
+            r#"{"error":{"message":"The model `gpt-x` does not exist","type":"invalid_request_error"}}"#
+                .to_string(),
+        ) {
+            GatewayError::Api(error) => {
+                assert_eq!(error.status, Some(404));
+                assert_eq!(error.message, "The model `gpt-x` does not exist");
+            }
+            other => panic!("ожидалась Api, получено {other:?}"),
+        }
+    }
+
+    #[test]
+    fn models_list_serves_as_health_probe() {
+        let models = || -> ModelsResponseDto {
+            serde_json::from_str(
+                r#"{"object":"list","data":[{"id":"qwen2.5-7b","object":"model"}]}"#,
+            )
+            .unwrap()
+        };
+        let health = health_from_dto(models(), None);
+        assert_eq!(health.status, ServiceState::Ok);
+        assert_eq!(health.version, "qwen2.5-7b");
+
+        let gateway =
+            OpenAiCompatibleGateway::new(ApiBaseUrl::try_new("http://localhost:8080").unwrap())
+                .with_model(ModelName::try_new("llama-3-8b").unwrap());
+        assert_eq!(
+            health_from_dto(models(), gateway.model.as_ref()).status,
+            ServiceState::Degraded
+        );
+
+        let empty: ModelsResponseDto = serde_json::from_str(r#"{"data":[]}"#).unwrap();
+        assert_eq!(health_from_dto(empty, None).status, ServiceState::Degraded);
+    }
+}
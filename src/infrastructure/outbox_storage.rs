@@ -0,0 +1,135 @@
+//! Очередь неотправленных вопросов в `localStorage` браузера.
+//!
+//! Реализует порт `OutboxRepository`. Как и история, очередь хранится
+//! одним JSON‑массивом через DTO.
+use gloo_storage::errors::StorageError as GlooStorageError;
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::application::ports::{OutboxRepository, StorageError};
+use crate::domain::{OutboxId, PendingQuestion, Question, Timestamp};
+
+const OUTBOX_KEY: &str = "rust_gigachat_webapp.outbox";
+
+/// Формат хранения записи очереди.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct StoredPendingQuestion {
+    id: u64,
+    question: String,
+    queued_at: u64,
+    #[serde(default)]
+    attempts: u32,
+    #[serde(default)]
+    last_error: Option<String>,
+}
+
+impl From<&PendingQuestion> for StoredPendingQuestion {
+    fn from(item: &PendingQuestion) -> Self {
+        Self {
+            id: item.id.as_u64(),
+            question: item.question.as_str().to_string(),
+            queued_at: item.queued_at.as_millis(),
+            attempts: item.attempts,
+            last_error: item.last_error.clone(),
+        }
+    }
+}
+
+impl StoredPendingQuestion {
+    /// Преобразует DTO в доменную запись; некорректный вопрос даёт `None`.
+    fn into_item(self) -> Option<PendingQuestion> {
+        Some(PendingQuestion {
+            id: OutboxId::new(self.id),
+            question: Question::try_new(self.question).ok()?,
+            queued_at: Timestamp::from_millis(self.queued_at),
+            attempts: self.attempts,
+            last_error: self.last_error,
+        })
+    }
+}
+
+/// Очередь вопросов в `localStorage`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalStorageOutboxRepository;
+
+impl LocalStorageOutboxRepository {
+    fn load(&self) -> Result<Vec<StoredPendingQuestion>, StorageError> {
+        match LocalStorage::get::<Vec<StoredPendingQuestion>>(OUTBOX_KEY) {
+            Ok(stored) => Ok(stored),
+            Err(GlooStorageError::KeyNotFound(_)) => Ok(Vec::new()),
+            Err(GlooStorageError::SerdeError(error)) => {
+                Err(StorageError::Corrupted(error.to_string()))
+            }
+            Err(error) => Err(StorageError::Unavailable(error.to_string())),
+        }
+    }
+
+    fn store(&self, items: &[StoredPendingQuestion]) -> Result<(), StorageError> {
+        LocalStorage::set(OUTBOX_KEY, items)
+            .map_err(|error| StorageError::Unavailable(error.to_string()))
+    }
+}
+
+impl OutboxRepository for LocalStorageOutboxRepository {
+    fn list(&self) -> Result<Vec<PendingQuestion>, StorageError> {
+        Ok(self
+            .load()?
+            .into_iter()
+            .filter_map(StoredPendingQuestion::into_item)
+            .collect())
+    }
+
+    fn add(&self, item: PendingQuestion) -> Result<(), StorageError> {
+        let mut items = self.load()?;
+        items.push(StoredPendingQuestion::from(&item));
+        self.store(&items)
+    }
+
+    fn update(&self, item: PendingQuestion) -> Result<bool, StorageError> {
+        let mut items = self.load()?;
+        let Some(stored) = items
+            .iter_mut()
+            .find(|stored| stored.id == item.id.as_u64())
+        else {
+            return Ok(false);
+        };
+        *stored = StoredPendingQuestion::from(&item);
+        self.store(&items)?;
+        Ok(true)
+    }
+
+    fn remove(&self, id: OutboxId) -> Result<bool, StorageError> {
+        let mut items = self.load()?;
+        let before = items.len();
+        items.retain(|item| item.id != id.as_u64());
+        if items.len() == before {
+            return Ok(false);
+        }
+        self.store(&items)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_item_round_trips_and_old_format_loads() {
+        let item = PendingQuestion {
+            id: OutboxId::new(7),
+            question: Question::try_new("Что такое Rust?").unwrap(),
+            queued_at: Timestamp::from_millis(1_700_000_000_000),
+            attempts: 2,
+            last_error: Some("Сетевая ошибка: offline".to_string()),
+        };
+        let json = serde_json::to_string(&StoredPendingQuestion::from(&item)).unwrap();
+        let restored: StoredPendingQuestion = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.into_item(), Some(item));
+
+        let minimal: StoredPendingQuestion =
+            serde_json::from_str(r#"{"id":1,"question":"Вопрос","queued_at":5}"#).unwrap();
+        let minimal = minimal.into_item().unwrap();
+        assert_eq!((minimal.attempts, minimal.last_error), (0, None));
+    }
+}
@@ -0,0 +1,122 @@
+//! Сессия пользователя в `sessionStorage` браузера.
+//!
+//! Реализует порт `SessionRepository`. Токены — секреты, поэтому, как и
+//! учётные данные, живут только до закрытия вкладки. Сессия выдана
+//! конкретным backend, поэтому у каждого базового URL она своя.
+use gloo_storage::errors::StorageError as GlooStorageError;
+use gloo_storage::{SessionStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::application::ports::{SessionRepository, StorageError};
+use crate::domain::{ApiBaseUrl, Session, Timestamp};
+
+const SESSION_KEY: &str = "rust_gigachat_webapp.session";
+
+/// Формат хранения сессии.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct StoredSession {
+    username: String,
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_at: Option<u64>,
+}
+
+impl From<&Session> for StoredSession {
+    fn from(session: &Session) -> Self {
+        Self {
+            username: session.username.clone(),
+            access_token: session.access_token.clone(),
+            refresh_token: session.refresh_token.clone(),
+            expires_at: session.expires_at.map(|at| at.as_millis()),
+        }
+    }
+}
+
+impl StoredSession {
+    fn into_session(self) -> Session {
+        Session {
+            username: self.username,
+            access_token: self.access_token,
+            refresh_token: self.refresh_token,
+            expires_at: self.expires_at.map(Timestamp::from_millis),
+        }
+    }
+}
+
+/// Сессия текущей вкладки.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionStorageSessionRepository {
+    key: String,
+}
+
+impl SessionStorageSessionRepository {
+    /// Сессия backend по адресу `base_url`.
+    pub fn for_base_url(base_url: &ApiBaseUrl) -> Self {
+        Self {
+            key: format!("{SESSION_KEY}.{}", base_url.as_str()),
+        }
+    }
+}
+
+impl SessionRepository for SessionStorageSessionRepository {
+    fn load(&self) -> Result<Option<Session>, StorageError> {
+        match SessionStorage::get::<StoredSession>(&self.key) {
+            Ok(stored) => Ok(Some(stored.into_session())),
+            Err(GlooStorageError::KeyNotFound(_)) => Ok(None),
+            Err(GlooStorageError::SerdeError(error)) => {
+                Err(StorageError::Corrupted(error.to_string()))
+            }
+            Err(error) => Err(StorageError::Unavailable(error.to_string())),
+        }
+    }
+
+    fn save(&self, session: &Session) -> Result<(), StorageError> {
+        SessionStorage::set(&self.key, StoredSession::from(session))
+            .map_err(|error| StorageError::Unavailable(error.to_string()))
+    }
+
+    fn clear(&self) -> Result<(), StorageError> {
+        SessionStorage::delete(&self.key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_round_trips_through_stored_format() {
+        let session = Session {
+            username: "student".to_string(),
+            access_token: "access".to_string(),
+            refresh_token: Some("refresh".to_string()),
+            expires_at: Some(Timestamp::from_millis(90_000)),
+        };
+        let json = serde_json::to_string(&StoredSession::from(&session)).unwrap();
+        let stored: StoredSession = serde_json::from_str(&json).unwrap();
+        assert_eq!(stored.into_session(), session);
+
+        // Сессия без срока и токена обновления тоже читается.
+        let stored: StoredSession =
+            serde_json::from_str(r#"{"username":"student","access_token":"a"}"#).unwrap();
+        assert_eq!(stored.into_session().expires_at, None);
+    }
+
+    #[test]
+    fn backends_keep_sessions_under_separate_keys() {
+        let course = ApiBaseUrl::try_new("http://localhost:8080").unwrap();
+        let other = ApiBaseUrl::try_new("https://example.com/api").unwrap();
+        let course = SessionStorageSessionRepository::for_base_url(&course);
+        assert_eq!(
+            course.key,
+            "rust_gigachat_webapp.session.http://localhost:8080"
+        );
+        assert_ne!(
+            course,
+            SessionStorageSessionRepository::for_base_url(&other)
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Разбор потока Server‑Sent Events.
+//!
+//! Декодер получает байты ответа кусками произвольной длины и отдаёт
+//! завершённые события. Строки собираются из байт, поэтому символ UTF‑8,
+//! разрезанный между кусками, не портится. Поддерживаются поля `event`
+//! и `data`; комментарии (`:`) и прочие поля пропускаются.
+
+/// Событие SSE.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    /// Тип события; без поля `event` — `message`.
+    pub event: String,
+    /// Строки `data`, склеенные через перевод строки.
+    pub data: String,
+}
+
+/// Потоковый декодер SSE.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Принимает очередной кусок байт и возвращает завершённые события.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(bytes);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line[..end]);
+            if let Some(event) = self.line(line.strip_suffix('\r').unwrap_or(&line)) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Завершает поток: последнее событие без пустой строки тоже отдаётся.
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        let rest = std::mem::take(&mut self.buffer);
+        if !rest.is_empty() {
+            // Непустая строка событие не завершает, только дополняет его.
+            let line = String::from_utf8_lossy(&rest).into_owned();
+            self.line(line.strip_suffix('\r').unwrap_or(&line));
+        }
+        self.dispatch()
+    }
+
+    fn line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event = self.event.take();
+        if self.data.is_empty() {
+            return None;
+        }
+        Some(SseEvent {
+            event: event.unwrap_or_else(|| "message".to_string()),
+            data: std::mem::take(&mut self.data).join("\n"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_events_split_across_chunks() {
+        let stream = "data: При\r\n\r\n: ping\n\nevent: done\ndata: {\"a\":1}\ndata: 2\n\n";
+        let bytes = stream.as_bytes();
+        let mut decoder = SseDecoder::new();
+        let mut events = Vec::new();
+        // Режем посередине кириллической буквы.
+        for chunk in bytes.chunks(7) {
+            events.extend(decoder.feed(chunk));
+        }
+
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: "message".to_string(),
+                    data: "При".to_string(),
+                },
+                SseEvent {
+                    event: "done".to_string(),
+                    data: "{\"a\":1}\n2".to_string(),
+                },
+            ]
+        );
+        assert_eq!(decoder.finish(), None);
+    }
+
+    #[test]
+    fn finish_flushes_unterminated_event() {
+        let mut decoder = SseDecoder::new();
+        assert!(decoder.feed(b"data: last").is_empty());
+        assert_eq!(
+            decoder.finish(),
+            Some(SseEvent {
+                event: "message".to_string(),
+                data: "last".to_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//! Таймер браузера.
+//!
+//! Реализация порта `Sleeper` через `setTimeout` (`gloo-timers`).
+use async_trait::async_trait;
+use gloo_timers::future::TimeoutFuture;
+
+use crate::application::ports::Sleeper;
+
+/// Ожидание на таймере браузера.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BrowserSleeper;
+
+#[async_trait(?Send)]
+impl Sleeper for BrowserSleeper {
+    async fn sleep(&self, millis: u32) {
+        TimeoutFuture::new(millis).await;
+    }
+}